@@ -0,0 +1,80 @@
+//! Timing of the hash functions on the host, without a benchmark framework.
+//!
+//! Each function is run in a dependent chain, the output of one call
+//! feeding the next, and the best of several batches is reported, which
+//! filters out most scheduler noise. Run with `cargo bench`; the S-box
+//! features apply as usual, e.g. `cargo bench --features sbox-table` for
+//! the SBF round.
+
+use haraka_bpf::{haraka256, haraka512, haraka512_keyed, haraka_s};
+use std::hint::black_box;
+use std::time::Instant;
+
+const BATCH: u32 = 20_000;
+const BATCHES: u32 = 15;
+
+/// Returns the best time per call of `step` over a batch, in nanoseconds.
+fn time(mut step: impl FnMut()) -> f64 {
+    (0..BATCHES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..BATCH {
+                step();
+            }
+            start.elapsed().as_nanos() as f64 / f64::from(BATCH)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn main() {
+    let mut block = [0x5au8; 64];
+    let mut digest = [0u8; 32];
+
+    let results = [
+        (
+            "haraka256<5>",
+            time(|| {
+                haraka256::<5>(&mut digest, black_box(&block[..32].try_into().unwrap()));
+                block[..32].copy_from_slice(&digest);
+            }),
+        ),
+        (
+            "haraka256<6>",
+            time(|| {
+                haraka256::<6>(&mut digest, black_box(&block[..32].try_into().unwrap()));
+                block[..32].copy_from_slice(&digest);
+            }),
+        ),
+        (
+            "haraka512<5>",
+            time(|| {
+                haraka512::<5>(&mut digest, black_box(&block));
+                block[..32].copy_from_slice(&digest);
+            }),
+        ),
+        (
+            "haraka512<6>",
+            time(|| {
+                haraka512::<6>(&mut digest, black_box(&block));
+                block[..32].copy_from_slice(&digest);
+            }),
+        ),
+        (
+            "haraka512_keyed<5>",
+            time(|| {
+                haraka512_keyed::<5>(&mut digest, black_box(&block), &[0xa5; 64]);
+                block[..32].copy_from_slice(&digest);
+            }),
+        ),
+        (
+            "haraka_s, 64 bytes",
+            time(|| {
+                haraka_s(&mut digest, black_box(&block));
+                block[..32].copy_from_slice(&digest);
+            }),
+        ),
+    ];
+    for (name, ns) in results {
+        println!("{name:<20} {ns:>8.0} ns");
+    }
+}
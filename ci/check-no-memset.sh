@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+# Builds the release C library and fails if any function of this crate
+# calls memset, i.e. zero-initializes a temporary that the optimizer could
+# not remove.
+#
+# usage: ci/check-no-memset.sh [target]
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+target="${1:-}"
+args=(--release -p haraka-bpf-ffi)
+if [ -n "$target" ]; then
+    args+=(--target "$target")
+fi
+cargo build "${args[@]}"
+
+lib="target/${target:+$target/}release/libharaka_bpf_ffi.a"
+objdump="${OBJDUMP:-llvm-objdump}"
+if [ ! -f "$lib" ]; then
+    echo "$lib not found" >&2
+    exit 1
+fi
+if ! command -v "$objdump" > /dev/null; then
+    echo "$objdump not found; set OBJDUMP" >&2
+    exit 1
+fi
+
+# Keep the bodies of functions whose (demangled) name mentions haraka. Only
+# grep may fail, when nothing matches.
+calls=$("$objdump" -dr --demangle "$lib" \
+    | awk '/^[0-9a-f]+ <.*>:$/ { keep = /haraka/ } keep' \
+    | { grep -E 'memset' || true; })
+
+if [ -n "$calls" ]; then
+    echo "memset calls found in Haraka code:"
+    echo "$calls"
+    exit 1
+fi
+echo "no memset calls in Haraka code"
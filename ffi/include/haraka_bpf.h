@@ -0,0 +1,86 @@
+#ifndef HARAKA_BPF_H
+#define HARAKA_BPF_H
+
+/* This file is generated by cbindgen. Do not edit it by hand. */
+
+#include <stdint.h>
+
+/**
+ * The call succeeded and the digest was written.
+ */
+#define HARAKA_OK 0
+
+/**
+ * A pointer argument was null; nothing was written.
+ */
+#define HARAKA_ERR_NULL -1
+
+/**
+ * Haraka-256 with 5 rounds: reads 32 bytes from `input`, writes 32 bytes
+ * to `out`.
+ *
+ * # Safety
+ *
+ * Unless null, `input` must be valid for reads of 32 bytes and `out` valid
+ * for writes of 32 bytes.
+ */
+int haraka_bpf_haraka256(uint8_t *out, const uint8_t *input);
+
+/**
+ * Haraka-512 with 5 rounds: reads 64 bytes from `input`, writes 32 bytes
+ * to `out`.
+ *
+ * # Safety
+ *
+ * Unless null, `input` must be valid for reads of 64 bytes and `out` valid
+ * for writes of 32 bytes.
+ */
+int haraka_bpf_haraka512(uint8_t *out, const uint8_t *input);
+
+/**
+ * Keyed Haraka-512 with 5 rounds: reads 64 bytes from each of `state` and
+ * `key`, writes 32 bytes to `out`.
+ *
+ * # Safety
+ *
+ * Unless null, `state` and `key` must be valid for reads of 64 bytes and
+ * `out` valid for writes of 32 bytes.
+ */
+int haraka_bpf_haraka512_keyed(uint8_t *out, const uint8_t *state, const uint8_t *key);
+
+/**
+ * Reference-compatible Haraka-256 (5 rounds).
+ *
+ * Does nothing if a pointer is null.
+ *
+ * # Safety
+ *
+ * See [`haraka_bpf_haraka256`].
+ */
+void haraka256(uint8_t *out, const uint8_t *input);
+
+/**
+ * Reference-compatible Haraka-512 (5 rounds).
+ *
+ * Does nothing if a pointer is null.
+ *
+ * # Safety
+ *
+ * See [`haraka_bpf_haraka512`].
+ */
+void haraka512(uint8_t *out, const uint8_t *input);
+
+/**
+ * Reference-compatible Haraka-512 permutation (5 rounds): reads 64 bytes
+ * from `input`, writes the 64-byte permuted state to `out`.
+ *
+ * Does nothing if a pointer is null.
+ *
+ * # Safety
+ *
+ * Unless null, `input` must be valid for reads of 64 bytes and `out` valid
+ * for writes of 64 bytes.
+ */
+void haraka512_perm(uint8_t *out, const uint8_t *input);
+
+#endif  /* HARAKA_BPF_H */
@@ -0,0 +1,7 @@
+//! Shared and static library build of the `haraka-bpf` C ABI.
+//!
+//! The exported symbols are defined in `haraka_bpf::ffi`; this crate only
+//! exists to produce `cdylib`/`staticlib` artifacts, which a `no_std` rlib
+//! cannot do on its own.
+
+pub use haraka_bpf::ffi::*;
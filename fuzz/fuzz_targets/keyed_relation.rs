@@ -0,0 +1,30 @@
+//! Keyed Haraka-512 XORs the key into the state and feeds forward the
+//! state after that XOR, so it is unkeyed Haraka-512 of `m ^ k`. A change
+//! to the feed-forward (e.g. feeding forward `m` instead) breaks both
+//! relations below for almost every input.
+
+#![no_main]
+
+use haraka_bpf::{haraka512, haraka512_keyed};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: [u8; 128]| {
+    let (m, k) = input.split_at(64);
+    let m: &[u8; 64] = m.try_into().unwrap();
+    let k: &[u8; 64] = k.try_into().unwrap();
+
+    let mut keyed = [0u8; 32];
+    let mut unkeyed = [0u8; 32];
+
+    haraka512_keyed::<5>(&mut keyed, m, &[0; 64]);
+    haraka512::<5>(&mut unkeyed, m);
+    assert_eq!(keyed, unkeyed, "zero key");
+
+    let mut mixed = [0u8; 64];
+    for (dst, (a, b)) in mixed.iter_mut().zip(m.iter().zip(k)) {
+        *dst = a ^ b;
+    }
+    haraka512_keyed::<5>(&mut keyed, m, k);
+    haraka512::<5>(&mut unkeyed, &mixed);
+    assert_eq!(keyed, unkeyed, "key folds into the input");
+});
@@ -0,0 +1,242 @@
+//! Duplex-sponge authenticated encryption over the Haraka-512 permutation
+//! (experimental).
+//!
+//! The state is 64 bytes: a 32-byte rate and a 32-byte capacity, permuted
+//! by the 5-round Haraka-512 permutation `P` of Haraka-S. With a 32-byte
+//! key and a 16-byte nonce:
+//!
+//! ```text
+//! S = P(nonce || AEAD_IV || key);  S[32..] ^= key
+//! for each padded block a of ad (skipped if ad is empty):  S[..32] ^= a;  S = P(S)
+//! S[63] ^= 1
+//! for each padded block m of data:  S[..32] ^= m;  c = S[..len(m)];  S = P(S), except after the last
+//! S[32..] ^= key;  S = P(S);  tag = S[..16]
+//! ```
+//!
+//! Inputs are split into 32-byte blocks and the last, shorter block
+//! (possibly empty) is padded with `0x01` after its last byte. Decryption
+//! overwrites the rate with the ciphertext instead of XORing the
+//! plaintext in, which leaves the same state.
+//!
+//! Each nonce must be used once per key: a repeated nonce reveals the XOR
+//! of two plaintexts. The construction follows the usual duplex AEAD
+//! designs but has no external specification or analysis; the test
+//! vectors are this crate's own, and the format may still change.
+
+use crate::sponge::{permute, RATE};
+
+/// Length of an authentication tag.
+pub const TAG_LEN: usize = 16;
+
+/// Initialization block: the name and version of the construction.
+const AEAD_IV: &[u8; 16] = b"haraka-bpf/aead1";
+
+/// Padding byte, XORed after the last byte of the last block.
+const PAD: u8 = 0x01;
+
+/// Encrypts `data` in place and returns the tag binding `key`, `nonce`,
+/// `ad` and the ciphertext.
+pub fn encrypt(key: &[u8; 32], nonce: &[u8; 16], ad: &[u8], data: &mut [u8]) -> [u8; TAG_LEN] {
+    let mut duplex = Duplex::new(key, nonce, ad);
+    duplex.duplex(data, |s, d| {
+        *s ^= *d;
+        *d = *s;
+    });
+    duplex.tag(key)
+}
+
+/// Decrypts `data` in place and returns `true` if `tag` is its tag under
+/// `key`, `nonce` and `ad`.
+///
+/// The tag is compared in constant time. On failure `data` is zeroed, so
+/// unauthenticated plaintext never reaches the caller.
+pub fn decrypt(
+    key: &[u8; 32],
+    nonce: &[u8; 16],
+    ad: &[u8],
+    data: &mut [u8],
+    tag: &[u8; TAG_LEN],
+) -> bool {
+    let mut duplex = Duplex::new(key, nonce, ad);
+    duplex.duplex(data, |s, d| {
+        let c = *d;
+        *d ^= *s;
+        *s = c;
+    });
+    let valid = crate::ct::eq(&duplex.tag(key), tag);
+    if !valid {
+        data.fill(0);
+    }
+    valid
+}
+
+/// The sponge state between phases.
+struct Duplex {
+    state: [u8; 64],
+}
+
+impl Duplex {
+    /// Loads the key and nonce and absorbs `ad`.
+    fn new(key: &[u8; 32], nonce: &[u8; 16], ad: &[u8]) -> Self {
+        let mut state = [0u8; 64];
+        state[..16].copy_from_slice(nonce);
+        state[16..32].copy_from_slice(AEAD_IV);
+        state[32..].copy_from_slice(key);
+        permute(&mut state);
+        let mut duplex = Self { state };
+        duplex.xor_key(key);
+        if !ad.is_empty() {
+            let (blocks, last) = ad.split_at(ad.len() / RATE * RATE);
+            for block in blocks.chunks_exact(RATE).chain([last]) {
+                for (s, a) in duplex.state.iter_mut().zip(block) {
+                    *s ^= a;
+                }
+                if block.len() < RATE {
+                    duplex.pad(block.len());
+                }
+                permute(&mut duplex.state);
+            }
+        }
+        duplex.state[63] ^= 1;
+        duplex
+    }
+
+    /// Runs `f` on each byte of `data` and the rate byte it meets,
+    /// permuting after every full block, and pads the last block. The
+    /// state is left unpermuted after the last block.
+    fn duplex(&mut self, data: &mut [u8], f: impl Fn(&mut u8, &mut u8)) {
+        let (blocks, last) = data.split_at_mut(data.len() / RATE * RATE);
+        for block in blocks.chunks_exact_mut(RATE) {
+            self.state.iter_mut().zip(block).for_each(|(s, d)| f(s, d));
+            permute(&mut self.state);
+        }
+        self.state
+            .iter_mut()
+            .zip(&mut *last)
+            .for_each(|(s, d)| f(s, d));
+        self.pad(last.len());
+    }
+
+    /// Pads a block holding `len < RATE` bytes.
+    fn pad(&mut self, len: usize) {
+        // The mask lets the compiler see that the index is in bounds.
+        self.state[len % RATE] ^= PAD;
+    }
+
+    fn xor_key(&mut self, key: &[u8; 32]) {
+        for (s, k) in self.state[32..].iter_mut().zip(key) {
+            *s ^= k;
+        }
+    }
+
+    fn tag(mut self, key: &[u8; 32]) -> [u8; TAG_LEN] {
+        self.xor_key(key);
+        permute(&mut self.state);
+        let mut tag = [0u8; TAG_LEN];
+        tag.copy_from_slice(&self.state[..TAG_LEN]);
+        tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = *b"an example 32-byte AEAD test key";
+    const NONCE: [u8; 16] = *b"a 16-byte nonce.";
+    const MSG: &[u8; 45] = b"a message longer than one block of the sponge";
+
+    #[test]
+    fn test_vector() {
+        // This crate's own vector.
+        let expect_tag: [u8; TAG_LEN] = [
+            0x1f, 0x32, 0xa6, 0x49, 0xa7, 0x4d, 0x75, 0xde, 0x72, 0xeb, 0x12, 0x84, 0x3b, 0x06,
+            0x34, 0xb5,
+        ];
+        let expect: [u8; 45] = [
+            0xbe, 0xff, 0xcc, 0x81, 0xdb, 0xe4, 0xc2, 0x80, 0x30, 0xfd, 0xa7, 0x9b, 0xb9, 0x2a,
+            0xec, 0x4a, 0x41, 0xf5, 0x94, 0xbf, 0x5e, 0xd5, 0x30, 0x46, 0xbe, 0x22, 0x3b, 0x10,
+            0xad, 0x3b, 0x24, 0xac, 0xeb, 0x29, 0xb4, 0x62, 0x9a, 0x9c, 0x7a, 0xd2, 0x8d, 0x63,
+            0x6a, 0xf0, 0xec,
+        ];
+        let mut data = *MSG;
+        let tag = encrypt(&KEY, &NONCE, b"header", &mut data);
+        assert_eq!((data, tag), (expect, expect_tag));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for len in [0, 1, 31, 32, 33, 45] {
+            for ad in [&b""[..], b"h", &[7; 32], &[7; 70]] {
+                let mut data = [0u8; 45];
+                data[..len].copy_from_slice(&MSG[..len]);
+                let tag = encrypt(&KEY, &NONCE, ad, &mut data[..len]);
+                if len > 8 {
+                    assert_ne!(&data[..len], &MSG[..len]);
+                }
+                assert!(decrypt(&KEY, &NONCE, ad, &mut data[..len], &tag));
+                assert_eq!(&data[..len], &MSG[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let mut ciphertext = *MSG;
+        let tag = encrypt(&KEY, &NONCE, b"header", &mut ciphertext);
+        let check =
+            |key: &[u8; 32], nonce: &[u8; 16], ad: &[u8], data: &[u8; 45], tag: &[u8; 16]| {
+                let mut data = *data;
+                let valid = decrypt(key, nonce, ad, &mut data, tag);
+                if !valid {
+                    assert_eq!(data, [0; 45]);
+                }
+                valid
+            };
+        assert!(check(&KEY, &NONCE, b"header", &ciphertext, &tag));
+
+        let mut other_key = KEY;
+        other_key[31] ^= 1;
+        let mut other_nonce = NONCE;
+        other_nonce[0] ^= 1;
+        assert!(!check(&other_key, &NONCE, b"header", &ciphertext, &tag));
+        assert!(!check(&KEY, &other_nonce, b"header", &ciphertext, &tag));
+        assert!(!check(&KEY, &NONCE, b"headex", &ciphertext, &tag));
+        assert!(!check(&KEY, &NONCE, b"", &ciphertext, &tag));
+        for i in [0, 31, 32, 44] {
+            let mut forged = ciphertext;
+            forged[i] ^= 0x80;
+            assert!(!check(&KEY, &NONCE, b"header", &forged, &tag));
+        }
+        let mut forged_tag = tag;
+        forged_tag[15] ^= 1;
+        assert!(!check(&KEY, &NONCE, b"header", &ciphertext, &forged_tag));
+    }
+
+    #[test]
+    fn test_padding_separates_inputs() {
+        // Moving a byte between the associated data and the message, or
+        // appending a zero, changes the tag.
+        let tag = |ad: &[u8], msg: &[u8]| {
+            let mut data = [0u8; 33];
+            data[..msg.len()].copy_from_slice(msg);
+            encrypt(&KEY, &NONCE, ad, &mut data[..msg.len()])
+        };
+        let base = tag(b"ab", b"c");
+        assert_ne!(tag(b"a", b"bc"), base);
+        assert_ne!(tag(b"ab\0", b"c"), base);
+        assert_ne!(tag(b"ab", b"c\0"), base);
+        assert_ne!(tag(b"", b""), tag(&[0], b""));
+        assert_ne!(tag(&[1; 32], &[]), tag(&[1; 31], &[]));
+        assert_ne!(tag(b"", &[0; 32]), tag(b"", &[0; 33]));
+    }
+
+    #[test]
+    fn test_nonce_selects_keystream() {
+        let mut a = *MSG;
+        let mut b = *MSG;
+        encrypt(&KEY, &NONCE, b"", &mut a);
+        encrypt(&KEY, &[0; 16], b"", &mut b);
+        assert_ne!(a, b);
+    }
+}
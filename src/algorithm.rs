@@ -0,0 +1,199 @@
+//! Run-time selection of a Haraka variant.
+
+use crate::error::Error;
+use crate::haraka256::haraka256;
+use crate::haraka512::haraka512;
+use crate::haraka512_keyed::haraka512_keyed;
+use crate::sponge::haraka_s;
+use arrayref::array_refs;
+
+/// A Haraka variant with a fixed round count, for frameworks that pick the
+/// hash function at run time (e.g. multihash-style registries).
+///
+/// The fixed-width variants take exactly [`input_len`](Self::input_len)
+/// bytes and produce 32. The keyed variants take the 64-byte state followed
+/// by the 64-byte key. [`HarakaS`](Self::HarakaS) takes any input and fills
+/// the whole output buffer.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Haraka {
+    /// Haraka-256, 5 rounds.
+    H256_5,
+    /// Haraka-256, 6 rounds.
+    H256_6,
+    /// Haraka-512, 5 rounds.
+    H512_5,
+    /// Haraka-512, 6 rounds.
+    H512_6,
+    /// Keyed Haraka-512, 5 rounds.
+    H512Keyed5,
+    /// Keyed Haraka-512, 6 rounds.
+    H512Keyed6,
+    /// The Haraka-S sponge.
+    HarakaS,
+}
+
+impl Haraka {
+    /// Every variant, in declaration order.
+    pub const ALL: [Haraka; 7] = [
+        Haraka::H256_5,
+        Haraka::H256_6,
+        Haraka::H512_5,
+        Haraka::H512_6,
+        Haraka::H512Keyed5,
+        Haraka::H512Keyed6,
+        Haraka::HarakaS,
+    ];
+
+    /// Returns a stable lowercase name, e.g. `"haraka512-5"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Haraka::H256_5 => "haraka256-5",
+            Haraka::H256_6 => "haraka256-6",
+            Haraka::H512_5 => "haraka512-5",
+            Haraka::H512_6 => "haraka512-6",
+            Haraka::H512Keyed5 => "haraka512-keyed-5",
+            Haraka::H512Keyed6 => "haraka512-keyed-6",
+            Haraka::HarakaS => "haraka-s",
+        }
+    }
+
+    /// Returns the variant called `name`, as returned by
+    /// [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|h| h.name() == name)
+    }
+
+    /// Returns the required input length, or `None` if any length works.
+    pub const fn input_len(self) -> Option<usize> {
+        match self {
+            Haraka::H256_5 | Haraka::H256_6 => Some(32),
+            Haraka::H512_5 | Haraka::H512_6 => Some(64),
+            Haraka::H512Keyed5 | Haraka::H512Keyed6 => Some(128),
+            Haraka::HarakaS => None,
+        }
+    }
+
+    /// Returns the output length, or `None` if any length works.
+    pub const fn output_len(self) -> Option<usize> {
+        match self {
+            Haraka::HarakaS => None,
+            _ => Some(32),
+        }
+    }
+
+    /// Hashes `input` into `out`.
+    ///
+    /// Fails with [`Error::InvalidLength`], without writing anything, if
+    /// `input` or `out` does not have the variant's length.
+    pub fn hash(&self, input: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        match self {
+            Haraka::H256_5 => haraka256::<5>(exact_mut(out)?, exact(input)?),
+            Haraka::H256_6 => haraka256::<6>(exact_mut(out)?, exact(input)?),
+            Haraka::H512_5 => haraka512::<5>(exact_mut(out)?, exact(input)?),
+            Haraka::H512_6 => haraka512::<6>(exact_mut(out)?, exact(input)?),
+            Haraka::H512Keyed5 => {
+                let (state, key) = array_refs![exact::<128>(input)?, 64, 64];
+                haraka512_keyed::<5>(exact_mut(out)?, state, key)
+            }
+            Haraka::H512Keyed6 => {
+                let (state, key) = array_refs![exact::<128>(input)?, 64, 64];
+                haraka512_keyed::<6>(exact_mut(out)?, state, key)
+            }
+            Haraka::HarakaS => haraka_s(out, input),
+        }
+        Ok(())
+    }
+}
+
+/// Views `bytes` as an array, failing unless it has exactly `N` bytes.
+fn exact<const N: usize>(bytes: &[u8]) -> Result<&[u8; N], Error> {
+    bytes.try_into().map_err(|_| Error::InvalidLength {
+        expected: N,
+        actual: bytes.len(),
+    })
+}
+
+/// Like [`exact`], for an output buffer.
+fn exact_mut<const N: usize>(bytes: &mut [u8]) -> Result<&mut [u8; N], Error> {
+    let actual = bytes.len();
+    bytes.try_into().map_err(|_| Error::InvalidLength {
+        expected: N,
+        actual,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrayref::array_ref;
+
+    #[test]
+    fn test_matches_direct_calls() {
+        let input: [u8; 128] = core::array::from_fn(|i| i as u8);
+        let mut out = [0u8; 32];
+        let mut expect = [0u8; 32];
+
+        Haraka::H256_6.hash(&input[..32], &mut out).unwrap();
+        haraka256::<6>(&mut expect, array_ref![input, 0, 32]);
+        assert_eq!(out, expect);
+
+        Haraka::H512_5.hash(&input[..64], &mut out).unwrap();
+        haraka512::<5>(&mut expect, array_ref![input, 0, 64]);
+        assert_eq!(out, expect);
+
+        Haraka::H512Keyed5.hash(&input, &mut out).unwrap();
+        haraka512_keyed::<5>(
+            &mut expect,
+            array_ref![input, 0, 64],
+            array_ref![input, 64, 64],
+        );
+        assert_eq!(out, expect);
+
+        let mut long = [0u8; 80];
+        let mut long_expect = [0u8; 80];
+        Haraka::HarakaS.hash(&input[..3], &mut long).unwrap();
+        haraka_s(&mut long_expect, &input[..3]);
+        assert_eq!(long, long_expect);
+    }
+
+    #[test]
+    fn test_variants_differ() {
+        let input: [u8; 128] = core::array::from_fn(|i| i as u8);
+        let mut seen = [[0u8; 32]; 7];
+        for (h, out) in Haraka::ALL.iter().zip(&mut seen) {
+            let len = h.input_len().unwrap_or(64);
+            h.hash(&input[..len], out).unwrap();
+        }
+        seen.sort_unstable();
+        assert!(seen.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn test_lengths_are_checked() {
+        let mut out = [0xeeu8; 32];
+        assert_eq!(
+            Haraka::H512_5.hash(&[0; 32], &mut out),
+            Err(Error::InvalidLength {
+                expected: 64,
+                actual: 32
+            })
+        );
+        assert_eq!(
+            Haraka::H256_5.hash(&[0; 32], &mut [0; 31]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                actual: 31
+            })
+        );
+        assert_eq!(out, [0xee; 32]);
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for h in Haraka::ALL {
+            assert_eq!(Haraka::from_name(h.name()), Some(h));
+        }
+        assert_eq!(Haraka::from_name("sha256"), None);
+    }
+}
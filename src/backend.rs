@@ -0,0 +1,378 @@
+//! Pluggable lane backends.
+//!
+//! The Haraka round logic only needs a handful of 128-bit lane operations.
+//! [`HarakaBackend`] names them, and the rounds of the crate are written
+//! against it: every hash function, from `crate::haraka256` to Haraka-S
+//! and the signatures, runs on `AesNiBackend` when the build targets
+//! AES-NI and on [`SoftBackend`], which wraps the portable `aes`-crate
+//! lane type, otherwise. [`haraka256`] and [`haraka512`] run the same
+//! rounds on any other backend, so that hardware implementations,
+//! instrumented backends or test doubles can be checked against them.
+
+use crate::constants;
+use crate::haraka256::mix2;
+use crate::haraka512::mix4;
+use crate::simd128::Simd128;
+
+/// The backend the hash functions of the crate run on: [`AesNiBackend`]
+/// when the build targets AES-NI, [`SoftBackend`] otherwise. The
+/// `sbox-table` and `sbox-computed` features (and `sanitizer`, which
+/// implies the table) ask for a software round and keep `SoftBackend`, as
+/// does Miri, which cannot run the inline assembly.
+#[cfg(all(
+    target_arch = "x86_64",
+    target_feature = "aes",
+    not(miri),
+    not(any(feature = "sbox-table", feature = "sbox-computed"))
+))]
+pub(crate) type DefaultBackend = AesNiBackend;
+
+/// The backend the hash functions of the crate run on.
+#[cfg(not(all(
+    target_arch = "x86_64",
+    target_feature = "aes",
+    not(miri),
+    not(any(feature = "sbox-table", feature = "sbox-computed"))
+)))]
+pub(crate) type DefaultBackend = SoftBackend;
+
+/// The lane operations the Haraka rounds are built from.
+pub trait HarakaBackend {
+    /// A 128-bit AES state.
+    type Lane: Copy;
+
+    /// Loads a lane from 16 bytes.
+    fn load(src: &[u8; 16]) -> Self::Lane;
+
+    /// Stores a lane into 16 bytes.
+    fn store(lane: &Self::Lane, dst: &mut [u8; 16]);
+
+    /// XORs `src` into `dst`.
+    fn xor(dst: &mut Self::Lane, src: &Self::Lane);
+
+    /// Applies one AES encryption round with round key `key`, like
+    /// `_mm_aesenc_si128`.
+    fn aesenc(lane: &mut Self::Lane, key: &Self::Lane);
+
+    /// Like [`aesenc`](Self::aesenc), with the round key given as the 16
+    /// bytes of a round constant.
+    ///
+    /// The default implementation loads the key into a lane first.
+    fn aesenc_bytes(lane: &mut Self::Lane, key: &[u8; 16]) {
+        Self::aesenc(lane, &Self::load(key));
+    }
+
+    /// Applies [`aesenc_bytes`](Self::aesenc_bytes) to four independent
+    /// lanes, `lanes[i]` with `keys[i]`, as a Haraka-512 round does.
+    ///
+    /// The default implementation does the four rounds in turn; backends
+    /// that evaluate several blocks in one pass can batch them.
+    fn aesenc4_bytes(lanes: &mut [Self::Lane; 4], keys: &[[u8; 16]; 4]) {
+        for (lane, key) in lanes.iter_mut().zip(keys) {
+            Self::aesenc_bytes(lane, key);
+        }
+    }
+
+    /// Applies the Haraka-256 MIX permutation.
+    fn mix2(s: &mut [Self::Lane; 2]);
+
+    /// Applies the Haraka-512 MIX permutation.
+    fn mix4(s: &mut [Self::Lane; 4]);
+}
+
+/// The portable software backend.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SoftBackend;
+
+impl HarakaBackend for SoftBackend {
+    type Lane = Simd128;
+
+    #[inline(always)]
+    fn load(src: &[u8; 16]) -> Simd128 {
+        Simd128::read(src)
+    }
+
+    #[inline(always)]
+    fn store(lane: &Simd128, dst: &mut [u8; 16]) {
+        lane.write(dst);
+    }
+
+    #[inline(always)]
+    fn xor(dst: &mut Simd128, src: &Simd128) {
+        Simd128::pxor(dst, src);
+    }
+
+    #[inline(always)]
+    fn aesenc(lane: &mut Simd128, key: &Simd128) {
+        Simd128::aesenc(lane, key);
+    }
+
+    /// Uses the key in place, so that the `folded-tables` round can tell
+    /// the standard constants by their address.
+    #[inline(always)]
+    fn aesenc_bytes(lane: &mut Simd128, key: &[u8; 16]) {
+        Simd128::aesenc_bytes(lane, key);
+    }
+
+    /// Hands the four lanes over together, which the bitsliced round
+    /// evaluates in one parallel pass.
+    #[inline(always)]
+    fn aesenc4_bytes(lanes: &mut [Simd128; 4], keys: &[[u8; 16]; 4]) {
+        Simd128::aesenc4(lanes, keys);
+    }
+
+    #[inline(always)]
+    fn mix2(s: &mut [Simd128; 2]) {
+        mix2(s);
+    }
+
+    #[inline(always)]
+    fn mix4(s: &mut [Simd128; 4]) {
+        mix4(s);
+    }
+}
+
+/// A hardware backend issuing `aesenc` and the unpack instructions through
+/// inline assembly.
+///
+/// It needs no crate and no run-time CPU detection; it exists only when the
+/// build targets AES-NI (`-C target-feature=+aes` or a `target-cpu` that
+/// has it), so it cannot be selected for a CPU without it.
+///
+/// The hash functions of the crate run on it unless a software round is
+/// asked for with the `sbox-table` or `sbox-computed` feature.
+#[cfg(all(target_arch = "x86_64", target_feature = "aes"))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AesNiBackend;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "aes"))]
+mod aesni {
+    use core::arch::asm;
+    pub(super) use core::arch::x86_64::__m128i;
+
+    /// Each function is one instruction on two XMM registers.
+    macro_rules! binary {
+        ($($name:ident => $insn:literal,)*) => {
+            $(
+                #[inline(always)]
+                pub(super) fn $name(dst: &mut __m128i, src: __m128i) {
+                    // SAFETY: the instruction only reads and writes the two
+                    // registers, and the AES instructions are available in
+                    // every build that has the `aes` target feature.
+                    unsafe {
+                        asm!(
+                            concat!($insn, " {0}, {1}"),
+                            inout(xmm_reg) *dst,
+                            in(xmm_reg) src,
+                            options(pure, nomem, nostack, preserves_flags),
+                        )
+                    }
+                }
+            )*
+        };
+    }
+
+    binary! {
+        aesenc => "aesenc",
+        pxor => "pxor",
+        unpacklo_epi32 => "punpckldq",
+        unpackhi_epi32 => "punpckhdq",
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "aes"))]
+impl HarakaBackend for AesNiBackend {
+    type Lane = aesni::__m128i;
+
+    #[inline(always)]
+    fn load(src: &[u8; 16]) -> aesni::__m128i {
+        // SAFETY: every 16-byte pattern is a valid `__m128i`, and the bytes
+        // land in the order `movdqu` would load them.
+        unsafe { core::mem::transmute::<[u8; 16], aesni::__m128i>(*src) }
+    }
+
+    #[inline(always)]
+    fn store(lane: &aesni::__m128i, dst: &mut [u8; 16]) {
+        // SAFETY: as in `load`.
+        *dst = unsafe { core::mem::transmute::<aesni::__m128i, [u8; 16]>(*lane) };
+    }
+
+    #[inline(always)]
+    fn xor(dst: &mut aesni::__m128i, src: &aesni::__m128i) {
+        aesni::pxor(dst, *src);
+    }
+
+    #[inline(always)]
+    fn aesenc(lane: &mut aesni::__m128i, key: &aesni::__m128i) {
+        aesni::aesenc(lane, *key);
+    }
+
+    #[inline(always)]
+    fn mix2(s: &mut [aesni::__m128i; 2]) {
+        let [s0, s1] = s;
+        let mut tmp = *s0;
+        aesni::unpackhi_epi32(&mut tmp, *s1);
+        aesni::unpacklo_epi32(s0, *s1);
+        *s1 = tmp;
+    }
+
+    #[inline(always)]
+    fn mix4(s: &mut [aesni::__m128i; 4]) {
+        // The sequence of `haraka512::mix4`.
+        let [s0, s1, s2, s3] = s;
+        let mut tmp = *s0;
+        aesni::unpacklo_epi32(&mut tmp, *s1);
+        aesni::unpackhi_epi32(s0, *s1);
+        *s1 = *s2;
+        aesni::unpacklo_epi32(s1, *s3);
+        aesni::unpackhi_epi32(s2, *s3);
+
+        *s3 = *s0;
+        aesni::unpacklo_epi32(s3, *s2);
+        aesni::unpackhi_epi32(s0, *s2);
+        *s2 = *s1;
+        aesni::unpackhi_epi32(s2, tmp);
+        aesni::unpacklo_epi32(s1, tmp);
+    }
+}
+
+/// Computes Haraka-256 with N_ROUNDS rounds on backend `B`.
+///
+/// These are the rounds of [`crate::haraka256`], which runs them on the
+/// default backend.
+pub fn haraka256<B: HarakaBackend, const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 32]) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_256,
+            "Haraka-256 supports at most 12 rounds"
+        )
+    };
+    crate::haraka256::haraka256_rounds::<B>(dst, src, N_ROUNDS);
+}
+
+/// Computes Haraka-512 with N_ROUNDS rounds on backend `B`.
+///
+/// These are the rounds of [`crate::haraka512`], which runs them on the
+/// default backend.
+pub fn haraka512<B: HarakaBackend, const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 64]) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_512,
+            "Haraka-512 supports at most 6 rounds"
+        )
+    };
+    crate::haraka512::haraka512_rounds::<B>(dst, src, N_ROUNDS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static AESENC_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// A byte-array backend that counts AES rounds.
+    struct Counting;
+
+    impl HarakaBackend for Counting {
+        type Lane = [u8; 16];
+
+        fn load(src: &[u8; 16]) -> [u8; 16] {
+            *src
+        }
+
+        fn store(lane: &[u8; 16], dst: &mut [u8; 16]) {
+            *dst = *lane;
+        }
+
+        fn xor(dst: &mut [u8; 16], src: &[u8; 16]) {
+            for (d, s) in dst.iter_mut().zip(src) {
+                *d ^= s;
+            }
+        }
+
+        fn aesenc(lane: &mut [u8; 16], key: &[u8; 16]) {
+            AESENC_CALLS.fetch_add(1, Ordering::Relaxed);
+            let mut s = Simd128::read(lane);
+            Simd128::aesenc(&mut s, &Simd128::read(key));
+            s.write(lane);
+        }
+
+        fn mix2(s: &mut [[u8; 16]; 2]) {
+            let mut lanes = s.map(|l| Simd128::read(&l));
+            SoftBackend::mix2(&mut lanes);
+            for (dst, lane) in s.iter_mut().zip(lanes) {
+                lane.write(dst);
+            }
+        }
+
+        fn mix4(s: &mut [[u8; 16]; 4]) {
+            let mut lanes = s.map(|l| Simd128::read(&l));
+            SoftBackend::mix4(&mut lanes);
+            for (dst, lane) in s.iter_mut().zip(lanes) {
+                lane.write(dst);
+            }
+        }
+    }
+
+    fn counting<const N: usize>() -> [u8; N] {
+        let mut buf = [0u8; N];
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        buf
+    }
+
+    #[test]
+    fn test_soft_matches_default() {
+        let src32 = counting::<32>();
+        let src64 = counting::<64>();
+        let mut expect = [0u8; 32];
+        let mut dst = [0u8; 32];
+
+        crate::haraka256::haraka256::<5>(&mut expect, &src32);
+        haraka256::<SoftBackend, 5>(&mut dst, &src32);
+        assert_eq!(dst, expect);
+
+        crate::haraka512::haraka512::<6>(&mut expect, &src64);
+        haraka512::<SoftBackend, 6>(&mut dst, &src64);
+        assert_eq!(dst, expect);
+    }
+
+    #[test]
+    #[cfg(all(target_arch = "x86_64", target_feature = "aes"))]
+    #[cfg_attr(miri, ignore = "Miri cannot run inline assembly")]
+    fn test_aesni_matches_reference() {
+        // The default functions may run on AES-NI themselves, so the
+        // expected digests come from the table-free reference.
+        let rc = crate::reference::constants();
+        let mut dst = [0u8; 32];
+        for seed in 0..32u8 {
+            let src64: [u8; 64] = core::array::from_fn(|i| (i as u8).wrapping_mul(seed) ^ seed);
+            let src32 = arrayref::array_ref![src64, 16, 32];
+
+            haraka256::<AesNiBackend, 5>(&mut dst, src32);
+            assert_eq!(dst, crate::reference::haraka256(&rc, 5, src32));
+
+            haraka512::<AesNiBackend, 6>(&mut dst, &src64);
+            assert_eq!(dst, crate::reference::haraka512(&rc, 6, &src64));
+        }
+    }
+
+    #[test]
+    fn test_custom_backend() {
+        let src = counting::<64>();
+        let mut expect = [0u8; 32];
+        crate::haraka512::haraka512::<5>(&mut expect, &src);
+
+        let mut dst = [0u8; 32];
+        haraka512::<Counting, 5>(&mut dst, &src);
+        assert_eq!(dst, expect);
+        assert_eq!(AESENC_CALLS.load(Ordering::Relaxed), 40);
+
+        let src = counting::<32>();
+        crate::haraka256::haraka256::<5>(&mut expect, &src);
+        haraka256::<Counting, 5>(&mut dst, &src);
+        assert_eq!(dst, expect);
+    }
+}
@@ -0,0 +1,102 @@
+//! Base58 encoding with the Bitcoin alphabet, as used for Solana addresses.
+
+use core::fmt;
+use core::ops::Deref;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Longest Base58 encoding of 32 bytes.
+const MAX_LEN: usize = 44;
+
+/// The Base58 text of a digest, stored inline so that no allocator is
+/// needed. Dereferences to `str`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Base58 {
+    buf: [u8; MAX_LEN],
+    len: usize,
+}
+
+impl Base58 {
+    /// Returns the encoding as a string slice.
+    pub fn as_str(&self) -> &str {
+        // The buffer only ever holds alphabet characters, which are ASCII.
+        core::str::from_utf8(&self.buf[..self.len]).unwrap()
+    }
+}
+
+impl Deref for Base58 {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Base58 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Base58 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Encodes 32 bytes, most significant byte first.
+pub(crate) fn encode(bytes: &[u8; 32]) -> Base58 {
+    // Base-58 digits of the big-endian integer, least significant first.
+    let mut digits = [0u8; MAX_LEN];
+    let mut n = 0;
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in &mut digits[..n] {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits[n] = (carry % 58) as u8;
+            carry /= 58;
+            n += 1;
+        }
+    }
+
+    // Every leading zero byte is written as a leading '1'.
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut buf = [ALPHABET[0]; MAX_LEN];
+    for (dst, &digit) in buf[zeros..].iter_mut().zip(digits[..n].iter().rev()) {
+        *dst = ALPHABET[digit as usize];
+    }
+    Base58 {
+        buf,
+        len: zeros + n,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        // Solana's system program address.
+        assert_eq!(&*encode(&[0u8; 32]), "11111111111111111111111111111111");
+        let counting: [u8; 32] = core::array::from_fn(|i| i as u8);
+        assert_eq!(
+            encode(&counting).as_str(),
+            "1thX6LZfHDZZKUs92febYZhYRcXddmzfzF2NvTkPNE"
+        );
+        assert_eq!(
+            encode(&[0xff; 32]).as_str(),
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG"
+        );
+        let mut leading = [7u8; 32];
+        leading[..2].fill(0);
+        assert_eq!(
+            encode(&leading).as_str(),
+            "112QftUJC9yYkE7xr4ikuFmaHKhLCxkpK5RL1J2QqC2"
+        );
+    }
+}
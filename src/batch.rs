@@ -0,0 +1,209 @@
+use crate::error::Error;
+use crate::haraka512::{haraka512, haraka512_x2};
+
+/// Number of bytes spanned by `count` elements of `size` bytes placed every
+/// `stride` bytes. Overflow is reported as `usize::MAX`, which no buffer can
+/// satisfy.
+#[inline(always)]
+fn span(count: usize, stride: usize, size: usize) -> usize {
+    match count {
+        0 => 0,
+        _ => (count - 1)
+            .checked_mul(stride)
+            .and_then(|n| n.checked_add(size))
+            .unwrap_or(usize::MAX),
+    }
+}
+
+#[inline(always)]
+fn check(len: usize, count: usize, stride: usize, size: usize) -> Result<(), Error> {
+    if stride < size {
+        return Err(Error::InvalidStride { stride, min: size });
+    }
+    let required = span(count, stride, size);
+    if len < required {
+        return Err(Error::BufferTooSmall {
+            required,
+            actual: len,
+        });
+    }
+    Ok(())
+}
+
+/// Hashes `count` messages with Haraka-512.
+///
+/// Message `i` is read from `src[i * src_stride..][..64]` and its digest is
+/// written to `dst[i * dst_stride..][..32]`. Bytes in between are left
+/// untouched. All bounds are validated before anything is written.
+pub fn haraka512_batch_strided<const N_ROUNDS: usize>(
+    dst: &mut [u8],
+    dst_stride: usize,
+    src: &[u8],
+    src_stride: usize,
+    count: usize,
+) -> Result<(), Error> {
+    check(src.len(), count, src_stride, 64)?;
+    check(dst.len(), count, dst_stride, 32)?;
+
+    // Messages are hashed in pairs on the two halves of a Simd256. The
+    // bounds were checked above, so every element is present; iterating
+    // over chunks keeps the loop free of panicking index operations.
+    let msgs = src.chunks(src_stride).filter_map(|c| c.first_chunk::<64>());
+    let digests = dst
+        .chunks_mut(dst_stride)
+        .filter_map(|c| c.first_chunk_mut::<32>());
+    let mut pairs = msgs.zip(digests).take(count);
+    while let Some((m0, d0)) = pairs.next() {
+        match pairs.next() {
+            Some((m1, d1)) => haraka512_x2::<N_ROUNDS>([d0, d1], [m0, m1]),
+            None => haraka512::<N_ROUNDS>(d0, m0),
+        }
+    }
+    Ok(())
+}
+
+/// Hashes `count` tightly packed 64-byte messages into tightly packed
+/// 32-byte digests.
+pub fn haraka512_batch<const N_ROUNDS: usize>(
+    dst: &mut [u8],
+    src: &[u8],
+    count: usize,
+) -> Result<(), Error> {
+    haraka512_batch_strided::<N_ROUNDS>(dst, 32, src, 64, count)
+}
+
+/// Hashes `msgs` in parallel on the rayon global thread pool.
+#[cfg(feature = "rayon")]
+pub fn par_hash_many<const N_ROUNDS: usize>(msgs: &[[u8; 64]]) -> alloc::vec::Vec<[u8; 32]> {
+    use rayon::prelude::*;
+
+    msgs.par_iter()
+        .map(|msg| {
+            let mut digest = [0u8; 32];
+            haraka512::<N_ROUNDS>(&mut digest, msg);
+            digest
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrayref::array_ref;
+
+    fn messages() -> [u8; 4 * 64] {
+        let mut src = [0u8; 4 * 64];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = (i * 7) as u8;
+        }
+        src
+    }
+
+    #[test]
+    fn test_batch_matches_single() {
+        let src = messages();
+        let mut dst = [0u8; 4 * 32];
+        haraka512_batch::<5>(&mut dst, &src, 4).unwrap();
+        for i in 0..4 {
+            let mut expect = [0u8; 32];
+            haraka512::<5>(&mut expect, array_ref![src, 64 * i, 64]);
+            assert_eq!(array_ref![dst, 32 * i, 32], &expect);
+        }
+    }
+
+    #[test]
+    fn test_batch_strided() {
+        // Messages are 80 bytes apart, digests 40 bytes apart; the gaps in
+        // the output must be preserved.
+        let mut src = [0u8; 3 * 80];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut dst = [0xeeu8; 2 * 40 + 32];
+        haraka512_batch_strided::<5>(&mut dst, 40, &src[..2 * 80 + 64], 80, 3).unwrap();
+        for i in 0..3 {
+            let mut expect = [0u8; 32];
+            haraka512::<5>(&mut expect, array_ref![src, 80 * i, 64]);
+            assert_eq!(array_ref![dst, 40 * i, 32], &expect);
+        }
+        assert_eq!(&dst[32..40], &[0xee; 8]);
+        assert_eq!(&dst[72..80], &[0xee; 8]);
+    }
+
+    #[test]
+    fn test_batch_empty() {
+        assert_eq!(haraka512_batch::<5>(&mut [], &[], 0), Ok(()));
+    }
+
+    #[test]
+    fn test_batch_short_buffers() {
+        let src = messages();
+        let mut dst = [0u8; 4 * 32];
+        assert_eq!(
+            haraka512_batch::<5>(&mut dst, &src[..255], 4),
+            Err(Error::BufferTooSmall {
+                required: 256,
+                actual: 255
+            })
+        );
+        assert_eq!(
+            haraka512_batch::<5>(&mut dst[..127], &src, 4),
+            Err(Error::BufferTooSmall {
+                required: 128,
+                actual: 127
+            })
+        );
+        // Nothing is written when validation fails.
+        assert_eq!(dst, [0u8; 4 * 32]);
+    }
+
+    #[test]
+    fn test_batch_invalid_stride() {
+        let src = messages();
+        let mut dst = [0u8; 4 * 32];
+        assert_eq!(
+            haraka512_batch_strided::<5>(&mut dst, 31, &src, 64, 1),
+            Err(Error::InvalidStride {
+                stride: 31,
+                min: 32
+            })
+        );
+        assert_eq!(
+            haraka512_batch_strided::<5>(&mut dst, 32, &src, 63, 1),
+            Err(Error::InvalidStride {
+                stride: 63,
+                min: 64
+            })
+        );
+    }
+
+    #[test]
+    fn test_batch_overflowing_count() {
+        assert_eq!(
+            haraka512_batch::<5>(&mut [], &[], usize::MAX),
+            Err(Error::BufferTooSmall {
+                required: usize::MAX,
+                actual: 0
+            })
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_hash_many() {
+        let msgs: alloc::vec::Vec<[u8; 64]> = (0..1000u32)
+            .map(|i| {
+                let mut msg = [0u8; 64];
+                msg[..4].copy_from_slice(&i.to_le_bytes());
+                msg
+            })
+            .collect();
+        let digests = par_hash_many::<5>(&msgs);
+        assert_eq!(digests.len(), msgs.len());
+        for (msg, digest) in msgs.iter().zip(&digests) {
+            let mut expect = [0u8; 32];
+            haraka512::<5>(&mut expect, msg);
+            assert_eq!(digest, &expect);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Emits the crate's standard test vectors.
+//!
+//! ```text
+//! haraka-vectors [--format json|csv]
+//! ```
+
+use haraka_bpf::vectors;
+use std::io;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let format = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] | ["--format", "json"] => "json",
+        ["--format", "csv"] => "csv",
+        _ => {
+            eprintln!("usage: haraka-vectors [--format json|csv]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let vectors = vectors::generate();
+    let stdout = io::stdout().lock();
+    let result = match format {
+        "csv" => vectors::write_csv(stdout, &vectors),
+        _ => vectors::write_json(stdout, &vectors),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("haraka-vectors: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Command-line front end for generating fixtures and debugging digests.
+//!
+//! ```text
+//! haraka [--variant 256|512|512-keyed] [--rounds 5|6] [--key HEX]
+//!        [--hex HEX | FILE ... | -]
+//! ```
+//!
+//! Inputs are taken from `--hex` strings, from files, or from stdin when no
+//! input is given (or for `-`). Each input must have the exact size of the
+//! selected variant: 32 bytes for `256`, 64 bytes for `512` and
+//! `512-keyed`. The hex-encoded digest is printed, followed by the input
+//! name for files.
+
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::{env, fs};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Variant {
+    H256,
+    H512,
+    H512Keyed,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Input {
+    Hex(String),
+    File(String),
+    Stdin,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    variant: Variant,
+    rounds: usize,
+    key: Option<[u8; 64]>,
+    inputs: Vec<Input>,
+}
+
+const USAGE: &str = "usage: haraka [--variant 256|512|512-keyed] [--rounds 5|6] [--key HEX] [--hex HEX | FILE ... | -]";
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, String> {
+    let s = s.trim();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if !s.len().is_multiple_of(2) {
+        return Err("hex string has an odd number of digits".to_string());
+    }
+    let mut bytes = vec![0u8; s.len() / 2];
+    haraka_bpf::decode_hex(s, &mut bytes).map_err(|e| format!("invalid hex string: {e}"))?;
+    Ok(bytes)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut opts = Options {
+        variant: Variant::H512,
+        rounds: 5,
+        key: None,
+        inputs: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for {name}"))
+        };
+        match arg.as_str() {
+            "--variant" => {
+                opts.variant = match value("--variant")?.as_str() {
+                    "256" => Variant::H256,
+                    "512" => Variant::H512,
+                    "512-keyed" => Variant::H512Keyed,
+                    other => return Err(format!("unknown variant {other:?}")),
+                }
+            }
+            "--rounds" => {
+                opts.rounds = match value("--rounds")?.as_str() {
+                    "5" => 5,
+                    "6" => 6,
+                    other => return Err(format!("unsupported round count {other:?}")),
+                }
+            }
+            "--key" => {
+                let key = decode_hex(&value("--key")?)?;
+                let key: [u8; 64] = key
+                    .try_into()
+                    .map_err(|k: Vec<u8>| format!("key must be 64 bytes, got {}", k.len()))?;
+                opts.key = Some(key);
+            }
+            "--hex" => opts.inputs.push(Input::Hex(value("--hex")?)),
+            "-" => opts.inputs.push(Input::Stdin),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            path => opts.inputs.push(Input::File(path.to_string())),
+        }
+    }
+    if opts.inputs.is_empty() {
+        opts.inputs.push(Input::Stdin);
+    }
+    match (opts.variant, opts.key.is_some()) {
+        (Variant::H512Keyed, false) => return Err("--variant 512-keyed requires --key".to_string()),
+        (Variant::H256 | Variant::H512, true) => {
+            return Err("--key is only valid with --variant 512-keyed".to_string())
+        }
+        _ => {}
+    }
+    Ok(opts)
+}
+
+fn exact<const N: usize>(data: &[u8]) -> Result<[u8; N], String> {
+    data.try_into()
+        .map_err(|_| format!("input must be {N} bytes, got {}", data.len()))
+}
+
+fn hash(opts: &Options, data: &[u8]) -> Result<[u8; 32], String> {
+    let mut dst = [0u8; 32];
+    match (opts.variant, opts.rounds) {
+        (Variant::H256, 5) => haraka_bpf::haraka256::<5>(&mut dst, &exact(data)?),
+        (Variant::H256, _) => haraka_bpf::haraka256::<6>(&mut dst, &exact(data)?),
+        (Variant::H512, 5) => haraka_bpf::haraka512::<5>(&mut dst, &exact(data)?),
+        (Variant::H512, _) => haraka_bpf::haraka512::<6>(&mut dst, &exact(data)?),
+        (Variant::H512Keyed, 5) => {
+            let key = opts.key.as_ref().expect("checked by parse_args");
+            haraka_bpf::haraka512_keyed::<5>(&mut dst, &exact(data)?, key)
+        }
+        (Variant::H512Keyed, _) => {
+            let key = opts.key.as_ref().expect("checked by parse_args");
+            haraka_bpf::haraka512_keyed::<6>(&mut dst, &exact(data)?, key)
+        }
+    }
+    Ok(dst)
+}
+
+fn run(opts: &Options) -> Result<(), String> {
+    let mut stdout = io::stdout().lock();
+    for input in &opts.inputs {
+        let (data, name) = match input {
+            Input::Hex(s) => (decode_hex(s)?, None),
+            Input::File(path) => (
+                fs::read(path).map_err(|e| format!("{path}: {e}"))?,
+                Some(path.as_str()),
+            ),
+            Input::Stdin => {
+                let mut data = Vec::new();
+                io::stdin()
+                    .read_to_end(&mut data)
+                    .map_err(|e| format!("stdin: {e}"))?;
+                (data, None)
+            }
+        };
+        let digest = hash(opts, &data)?;
+        let line = match name {
+            Some(name) => format!("{}  {name}", encode_hex(&digest)),
+            None => encode_hex(&digest),
+        };
+        writeln!(stdout, "{line}").map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args(env::args().skip(1)).and_then(|opts| run(&opts)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("haraka: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let opts = parse_args(args(&[])).unwrap();
+        assert_eq!(opts.variant, Variant::H512);
+        assert_eq!(opts.rounds, 5);
+        assert_eq!(opts.inputs, [Input::Stdin]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_args(args(&["--rounds", "7"])).is_err());
+        assert!(parse_args(args(&["--variant", "1024"])).is_err());
+        assert!(parse_args(args(&["--variant", "512-keyed"])).is_err());
+        assert!(parse_args(args(&["--key", "00"])).is_err());
+        assert!(parse_args(args(&["--frobnicate"])).is_err());
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        assert_eq!(decode_hex("0x00ff10").unwrap(), [0x00, 0xff, 0x10]);
+        assert_eq!(encode_hex(&[0x00, 0xff, 0x10]), "00ff10");
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("zz").is_err());
+        // Four bytes, but not four hex digits.
+        assert!(decode_hex("é00").is_err());
+    }
+
+    #[test]
+    fn test_hash_haraka256_vector() {
+        // Same vector as the library's 5-round Haraka-256 test.
+        let opts = parse_args(args(&[
+            "--variant",
+            "256",
+            "--hex",
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ]))
+        .unwrap();
+        let Input::Hex(ref hex) = opts.inputs[0] else {
+            panic!("expected a hex input");
+        };
+        let digest = hash(&opts, &decode_hex(hex).unwrap()).unwrap();
+        assert_eq!(
+            encode_hex(&digest),
+            "8027ccb87949774b78d0545fb72bf70c695c2a0923cbd47bba1159efbf2b2c1c"
+        );
+    }
+
+    #[test]
+    fn test_hash_keyed_6_rounds() {
+        let key = "5a".repeat(64);
+        let opts = parse_args(args(&[
+            "--variant",
+            "512-keyed",
+            "--rounds",
+            "6",
+            "--key",
+            &key,
+        ]))
+        .unwrap();
+        let (state, key) = ([3u8; 64], [0x5a; 64]);
+        let mut expect = [0u8; 32];
+        haraka_bpf::haraka512_keyed::<6>(&mut expect, &state, &key);
+        assert_eq!(hash(&opts, &state).unwrap(), expect);
+    }
+
+    #[test]
+    fn test_hash_wrong_size() {
+        let opts = parse_args(args(&["--variant", "256"])).unwrap();
+        assert!(hash(&opts, &[0u8; 31]).is_err());
+    }
+}
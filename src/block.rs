@@ -0,0 +1,195 @@
+//! Aligned input blocks.
+
+use crate::digest::Digest;
+use crate::error::Error;
+use crate::haraka256::haraka256;
+use crate::haraka512::haraka512;
+use arrayref::array_ref;
+
+macro_rules! block {
+    ($(#[$doc:meta])* $name:ident, $len:literal) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[repr(C, align(16))]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            /// Block length in bytes.
+            pub const LEN: usize = $len;
+
+            /// Wraps raw bytes.
+            #[inline(always)]
+            pub const fn new(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+
+            /// Copies a block out of `bytes`, which must be exactly
+            #[doc = concat!(stringify!($len), " bytes long.")]
+            pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+                if bytes.len() != $len {
+                    return Err(Error::InvalidLength {
+                        expected: $len,
+                        actual: bytes.len(),
+                    });
+                }
+                Ok(Self(*array_ref![bytes, 0, $len]))
+            }
+
+            /// Returns the block bytes.
+            #[inline(always)]
+            pub const fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            /// Returns the block bytes mutably.
+            #[inline(always)]
+            pub fn as_mut_bytes(&mut self) -> &mut [u8; $len] {
+                &mut self.0
+            }
+
+            /// Returns the 16-byte AES lanes of the block. Every lane is
+            /// 16-byte aligned.
+            #[inline(always)]
+            pub fn lanes(&self) -> &[[u8; 16]; $len / 16] {
+                let (lanes, rest) = self.0.as_chunks::<16>();
+                debug_assert!(rest.is_empty());
+                lanes.try_into().expect("block length is a multiple of 16")
+            }
+        }
+
+        impl Default for $name {
+            #[inline(always)]
+            fn default() -> Self {
+                Self([0u8; $len])
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            #[inline(always)]
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            #[inline(always)]
+            fn from(block: $name) -> Self {
+                block.0
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = Error;
+
+            /// See [`from_slice`](Self::from_slice).
+            #[inline(always)]
+            fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+                Self::from_slice(bytes)
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            #[inline(always)]
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+    };
+}
+
+block!(
+    /// A 32-byte Haraka-256 input, aligned to 16 bytes.
+    Block32,
+    32
+);
+
+block!(
+    /// A 64-byte Haraka-512 input, aligned to 16 bytes.
+    Block64,
+    64
+);
+
+impl Block32 {
+    /// Computes the Haraka-256 digest of the block.
+    #[inline(always)]
+    pub fn haraka256<const N_ROUNDS: usize>(&self) -> Digest {
+        let mut dst = [0u8; 32];
+        haraka256::<N_ROUNDS>(&mut dst, &self.0);
+        Digest::new(dst)
+    }
+}
+
+impl Block64 {
+    /// Computes the Haraka-512 digest of the block.
+    #[inline(always)]
+    pub fn haraka512<const N_ROUNDS: usize>(&self) -> Digest {
+        let mut dst = [0u8; 32];
+        haraka512::<N_ROUNDS>(&mut dst, &self.0);
+        Digest::new(dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alignment() {
+        assert_eq!(core::mem::align_of::<Block32>(), 16);
+        assert_eq!(core::mem::align_of::<Block64>(), 16);
+        assert_eq!(core::mem::size_of::<Block64>(), 64);
+
+        let blocks = [Block64::default(); 3];
+        for block in &blocks {
+            for lane in block.lanes() {
+                assert_eq!(lane.as_ptr() as usize % 16, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let mut bytes = [0u8; 70];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let block = Block64::from_slice(&bytes[3..67]).unwrap();
+        assert_eq!(&block.as_bytes()[..], &bytes[3..67]);
+        assert_eq!(block.lanes()[1][0], 19);
+        assert_eq!(
+            Block32::from_slice(&bytes[..31]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                actual: 31
+            })
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let bytes = [5u8; 64];
+        let block = Block64::try_from(&bytes[..]).unwrap();
+        assert_eq!(block.as_ref(), &bytes[..]);
+        assert_eq!(
+            Block64::try_from(&bytes[..63]),
+            Err(Error::InvalidLength {
+                expected: 64,
+                actual: 63
+            })
+        );
+        let block: Block32 = bytes[..32].try_into().unwrap();
+        assert_eq!(block.as_ref().len(), 32);
+    }
+
+    #[test]
+    fn test_digests() {
+        let block = Block64::new([7u8; 64]);
+        let mut expect = [0u8; 32];
+        haraka512::<5>(&mut expect, &[7u8; 64]);
+        assert_eq!(block.haraka512::<5>(), Digest::new(expect));
+
+        let block = Block32::from([9u8; 32]);
+        haraka256::<5>(&mut expect, &[9u8; 32]);
+        assert_eq!(block.haraka256::<5>().to_bytes(), expect);
+    }
+}
@@ -0,0 +1,124 @@
+//! Bloom filter helpers over a caller-provided bit array.
+//!
+//! Each 32-byte item is hashed once with Haraka-256. The first two 64-bit
+//! little-endian words of the digest, `h1` and `h2`, then give the `k` bit
+//! positions `h1 + i * h2 (mod m)` for `i in 0..k`, where `m` is the number
+//! of bits in the filter (Kirsch-Mitzenmacher double hashing). `h2` is
+//! forced odd so that it never degenerates to a single position.
+//!
+//! Bit `p` of the filter is bit `p % 8` of byte `p / 8`. The filter itself
+//! is just a byte slice, so it can live directly in account data.
+
+use crate::haraka256::haraka256;
+use arrayref::array_ref;
+
+/// Returns the `k` bit positions of `item` in a filter of `bits` bits.
+///
+/// # Panics
+///
+/// Panics if `bits` is zero.
+pub fn positions<const N_ROUNDS: usize>(
+    item: &[u8; 32],
+    k: u32,
+    bits: u64,
+) -> impl Iterator<Item = u64> {
+    assert!(bits != 0, "bloom filter must not be empty");
+
+    let mut digest = [0u8; 32];
+    haraka256::<N_ROUNDS>(&mut digest, item);
+    let h1 = u64::from_le_bytes(*array_ref![digest, 0, 8]);
+    let h2 = u64::from_le_bytes(*array_ref![digest, 8, 8]) | 1;
+    (0..k as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bits)
+}
+
+/// Sets the `k` bits of `item` in `filter`.
+///
+/// # Panics
+///
+/// Panics if `filter` is empty.
+pub fn insert<const N_ROUNDS: usize>(filter: &mut [u8], k: u32, item: &[u8; 32]) {
+    for p in positions::<N_ROUNDS>(item, k, filter_bits(filter)) {
+        filter[(p / 8) as usize] |= 1 << (p % 8);
+    }
+}
+
+/// Returns `true` if all `k` bits of `item` are set in `filter`.
+///
+/// A `false` result is definitive; a `true` result may be a false positive.
+///
+/// # Panics
+///
+/// Panics if `filter` is empty.
+pub fn contains<const N_ROUNDS: usize>(filter: &[u8], k: u32, item: &[u8; 32]) -> bool {
+    positions::<N_ROUNDS>(item, k, filter_bits(filter))
+        .all(|p| filter[(p / 8) as usize] & (1 << (p % 8)) != 0)
+}
+
+#[inline(always)]
+fn filter_bits(filter: &[u8]) -> u64 {
+    (filter.len() as u64).saturating_mul(8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(i: u32) -> [u8; 32] {
+        let mut item = [0u8; 32];
+        item[..4].copy_from_slice(&i.to_le_bytes());
+        item
+    }
+
+    #[test]
+    fn test_positions_double_hashing() {
+        let mut digest = [0u8; 32];
+        haraka256::<5>(&mut digest, &item(7));
+        let h1 = u64::from_le_bytes(*array_ref![digest, 0, 8]);
+        let h2 = u64::from_le_bytes(*array_ref![digest, 8, 8]) | 1;
+
+        let mut count = 0;
+        for (i, p) in positions::<5>(&item(7), 6, 1000).enumerate() {
+            let i = i as u64;
+            assert_eq!(p, h1.wrapping_add(i.wrapping_mul(h2)) % 1000);
+            count += 1;
+        }
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn test_insert_contains() {
+        let mut filter = [0u8; 128];
+        for i in 0..50 {
+            insert::<5>(&mut filter, 7, &item(i));
+        }
+        for i in 0..50 {
+            assert!(contains::<5>(&filter, 7, &item(i)));
+        }
+        // 50 items in 1024 bits with k = 7 gives a false-positive rate far
+        // below 1%, so most unseen items must be rejected.
+        let false_positives = (50..1050)
+            .filter(|&i| contains::<5>(&filter, 7, &item(i)))
+            .count();
+        assert!(false_positives < 20, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn test_insert_sets_at_most_k_bits() {
+        let mut filter = [0u8; 64];
+        insert::<5>(&mut filter, 5, &item(1));
+        let set: u32 = filter.iter().map(|b| b.count_ones()).sum();
+        assert!((1..=5).contains(&set));
+    }
+
+    #[test]
+    fn test_zero_hashes_contains_everything() {
+        let filter = [0u8; 8];
+        assert!(contains::<5>(&filter, 0, &item(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "bloom filter must not be empty")]
+    fn test_empty_filter_panics() {
+        insert::<5>(&mut [], 3, &item(0));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::backend::DefaultBackend;
+use crate::ct;
+use crate::haraka256::{haraka256_lanes, load2, store2};
+use crate::haraka512::haraka512;
+use arrayref::array_ref;
+
+/// Iterates Haraka-256 `n` times, starting from `start`.
+///
+/// The state stays in two `Simd128` lanes for the whole chain; it is only
+/// serialized once at the end. `n == 0` returns `start` unchanged.
+#[inline(always)]
+pub fn hash_chain<const N_ROUNDS: usize>(start: &[u8; 32], n: u64) -> [u8; 32] {
+    let mut s = load2::<DefaultBackend>(start);
+
+    for _ in 0..n {
+        haraka256_lanes::<N_ROUNDS>(&mut s);
+    }
+
+    let mut dst = [0u8; 32];
+    store2::<DefaultBackend>(&mut dst, &s);
+    dst
+}
+
+/// Checks that `to` is reached from `from` after exactly `steps` iterations.
+///
+/// A long chain can be split at public checkpoints `c_0, c_1, ..., c_m` and
+/// each segment `(c_i, c_{i+1})` verified independently, e.g. one per
+/// instruction. The final comparison does not exit early on a mismatch.
+#[inline(always)]
+pub fn verify_chain_segment<const N_ROUNDS: usize>(
+    from: &[u8; 32],
+    to: &[u8; 32],
+    steps: u64,
+) -> bool {
+    ct::eq(&hash_chain::<N_ROUNDS>(from, steps), to)
+}
+
+/// Folds a stream of 32-byte chunks into one digest.
+///
+/// Starting from an all-zero accumulator, each chunk is absorbed as
+/// `acc = haraka512(acc || chunk)`; an empty stream gives the all-zero
+/// value. Nothing is buffered, so lists of any length hash in constant
+/// memory.
+pub fn haraka_fold<const N_ROUNDS: usize>(chunks: impl Iterator<Item = [u8; 32]>) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    for chunk in chunks {
+        block[32..].copy_from_slice(&chunk);
+        let mut acc = [0u8; 32];
+        haraka512::<N_ROUNDS>(&mut acc, &block);
+        block[..32].copy_from_slice(&acc);
+    }
+    *array_ref![block, 0, 32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haraka256::haraka256;
+
+    fn naive_chain<const N_ROUNDS: usize>(start: &[u8; 32], n: u64) -> [u8; 32] {
+        let mut cur = *start;
+        for _ in 0..n {
+            let mut next = [0u8; 32];
+            haraka256::<N_ROUNDS>(&mut next, &cur);
+            cur = next;
+        }
+        cur
+    }
+
+    #[test]
+    fn test_hash_chain_zero_steps() {
+        let start = [0x17u8; 32];
+        assert_eq!(hash_chain::<5>(&start, 0), start);
+    }
+
+    #[test]
+    fn test_hash_chain_one_step() {
+        let start = [0x17u8; 32];
+        let mut expect = [0u8; 32];
+        haraka256::<5>(&mut expect, &start);
+        assert_eq!(hash_chain::<5>(&start, 1), expect);
+    }
+
+    #[test]
+    fn test_hash_chain_matches_naive() {
+        let mut start = [0u8; 32];
+        for (i, b) in start.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        assert_eq!(hash_chain::<5>(&start, 100), naive_chain::<5>(&start, 100));
+        assert_eq!(hash_chain::<6>(&start, 100), naive_chain::<6>(&start, 100));
+    }
+
+    #[test]
+    fn test_verify_chain_segment() {
+        let start = [0x99u8; 32];
+        let end = hash_chain::<5>(&start, 10);
+        assert!(verify_chain_segment::<5>(&start, &end, 10));
+        assert!(!verify_chain_segment::<5>(&start, &end, 9));
+        assert!(!verify_chain_segment::<5>(&start, &end, 11));
+        assert!(!verify_chain_segment::<6>(&start, &end, 10));
+    }
+
+    #[test]
+    fn test_verify_chain_checkpoints() {
+        // Verifying consecutive segments is equivalent to verifying the
+        // whole chain at once.
+        let start = [0x01u8; 32];
+        let mid = hash_chain::<5>(&start, 7);
+        let end = hash_chain::<5>(&mid, 5);
+        assert!(verify_chain_segment::<5>(&start, &mid, 7));
+        assert!(verify_chain_segment::<5>(&mid, &end, 5));
+        assert!(verify_chain_segment::<5>(&start, &end, 12));
+    }
+
+    #[test]
+    fn test_haraka_fold() {
+        assert_eq!(haraka_fold::<5>(core::iter::empty()), [0; 32]);
+
+        let chunks = [[1u8; 32], [2; 32], [3; 32]];
+        let mut expect = [0u8; 32];
+        for chunk in &chunks {
+            let mut block = [0u8; 64];
+            block[..32].copy_from_slice(&expect);
+            block[32..].copy_from_slice(chunk);
+            haraka512::<5>(&mut expect, &block);
+        }
+        assert_eq!(haraka_fold::<5>(chunks.into_iter()), expect);
+        assert_ne!(haraka_fold::<5>(chunks.into_iter().rev()), expect);
+        assert_ne!(haraka_fold::<5>(chunks.into_iter().take(2)), expect);
+    }
+}
@@ -0,0 +1,65 @@
+//! Hash commitments for commit-reveal protocols.
+//!
+//! A commitment is the Haraka-S digest of a fixed 32-byte domain block,
+//! the 32-byte blinding factor and the committed value, in that order:
+//!
+//! ```text
+//! commit(value, blinding) = HarakaS(DOMAIN || blinding || value)
+//! ```
+//!
+//! The domain block sets these digests apart from other Haraka-S uses, and
+//! the fixed-length blinding keeps the encoding unambiguous for values of
+//! any length.
+
+use crate::ct;
+use crate::sponge::{domain_block, HarakaS};
+
+/// Domain block absorbed before every commitment.
+const DOMAIN: &[u8; 32] = &domain_block("haraka-bpf/commit/v1");
+
+/// Commits to `value` using the secret `blinding` factor.
+pub fn commit(value: &[u8], blinding: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = HarakaS::with_domain(DOMAIN);
+    hasher.update(blinding);
+    hasher.update(value);
+    hasher.finalize()
+}
+
+/// Checks that `commitment` opens to `value` with `blinding`.
+///
+/// The digest comparison is constant-time.
+pub fn verify_commit(commitment: &[u8; 32], value: &[u8], blinding: &[u8; 32]) -> bool {
+    ct::eq(commitment, &commit(value, blinding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_commit() {
+        let blinding = [0x42u8; 32];
+        let commitment = commit(b"heads", &blinding);
+        assert!(verify_commit(&commitment, b"heads", &blinding));
+        assert!(!verify_commit(&commitment, b"tails", &blinding));
+        assert!(!verify_commit(&commitment, b"heads", &[0x43; 32]));
+    }
+
+    #[test]
+    fn test_commit_is_not_plain_sponge() {
+        let blinding = [0u8; 32];
+        let mut plain = [0u8; 32];
+        let mut input = [0u8; 36];
+        input[32..].copy_from_slice(b"vote");
+        crate::sponge::haraka_s(&mut plain, &input);
+        assert_ne!(commit(b"vote", &blinding), plain);
+    }
+
+    #[test]
+    fn test_empty_value() {
+        let blinding = [7u8; 32];
+        let commitment = commit(b"", &blinding);
+        assert!(verify_commit(&commitment, b"", &blinding));
+        assert!(!verify_commit(&commitment, b"\0", &blinding));
+    }
+}
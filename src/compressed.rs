@@ -0,0 +1,160 @@
+//! Leaf hashing for compressed accounts.
+//!
+//! Compressed-state programs (in the style of light-protocol's account
+//! compression) keep accounts off-chain and store only a Merkle leaf per
+//! account. The leaf hashes the account fields together with the tree and
+//! the leaf index, so that the same account in another tree or slot of the
+//! tree has a different leaf:
+//!
+//! ```text
+//! leaf = H(owner, leaf_index, merkle_tree, lamports, address, discriminator, data_hash)
+//! ```
+//!
+//! `leaf_index` is a little-endian `u32` and `lamports` a little-endian
+//! `u64`. A missing address or data is hashed as an empty field, which is
+//! distinct from any present value.
+//!
+//! [`HarakaLeafHasher`] implements [`LeafHasher`] as Haraka-S over a
+//! 32-byte domain block followed by each field prefixed with its length as
+//! a little-endian `u64`, so no two field lists share an encoding.
+
+use crate::sponge::{domain_block, HarakaS};
+
+/// A hash function over a list of byte fields, as used for leaves and data
+/// hashes of compressed accounts.
+pub trait LeafHasher {
+    /// Hashes the concatenation of `fields`, keeping field boundaries.
+    fn hashv(fields: &[&[u8]]) -> [u8; 32];
+}
+
+/// The Haraka-S [`LeafHasher`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HarakaLeafHasher;
+
+/// Domain block absorbed before the fields.
+const DOMAIN: &[u8; 32] = &domain_block("haraka-bpf/compressed/v1");
+
+impl LeafHasher for HarakaLeafHasher {
+    fn hashv(fields: &[&[u8]]) -> [u8; 32] {
+        let mut hasher = HarakaS::with_domain(DOMAIN);
+        for field in fields {
+            hasher.update(&(field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        }
+        hasher.finalize()
+    }
+}
+
+/// The typed data of a compressed account: a discriminator and the hash of
+/// the serialized data, computed by the owning program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompressedAccountData {
+    /// Account type discriminator.
+    pub discriminator: [u8; 8],
+    /// Hash of the account data.
+    pub data_hash: [u8; 32],
+}
+
+/// The fields of a compressed account that go into its leaf.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompressedAccount {
+    /// Program that owns the account.
+    pub owner: [u8; 32],
+    /// Balance in lamports.
+    pub lamports: u64,
+    /// Optional persistent address.
+    pub address: Option<[u8; 32]>,
+    /// Optional typed data.
+    pub data: Option<CompressedAccountData>,
+}
+
+impl CompressedAccount {
+    /// Returns the leaf of the account at `leaf_index` in `merkle_tree`.
+    pub fn hash<H: LeafHasher>(&self, merkle_tree: &[u8; 32], leaf_index: u32) -> [u8; 32] {
+        let (discriminator, data_hash): (&[u8], &[u8]) = match &self.data {
+            Some(data) => (&data.discriminator, &data.data_hash),
+            None => (&[], &[]),
+        };
+        let address: &[u8] = match &self.address {
+            Some(address) => address,
+            None => &[],
+        };
+        H::hashv(&[
+            &self.owner,
+            &leaf_index.to_le_bytes(),
+            merkle_tree,
+            &self.lamports.to_le_bytes(),
+            address,
+            discriminator,
+            data_hash,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account() -> CompressedAccount {
+        CompressedAccount {
+            owner: [0x11; 32],
+            lamports: 1_000_000,
+            address: Some([0x22; 32]),
+            data: Some(CompressedAccountData {
+                discriminator: *b"counter\0",
+                data_hash: [0x33; 32],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_hashv_keeps_boundaries() {
+        assert_ne!(
+            HarakaLeafHasher::hashv(&[b"ab", b"c"]),
+            HarakaLeafHasher::hashv(&[b"a", b"bc"])
+        );
+        assert_ne!(
+            HarakaLeafHasher::hashv(&[b"abc"]),
+            HarakaLeafHasher::hashv(&[b"abc", b""])
+        );
+    }
+
+    #[test]
+    fn test_leaf_encoding() {
+        let account = account();
+        let expect = HarakaLeafHasher::hashv(&[
+            &[0x11; 32],
+            &7u32.to_le_bytes(),
+            &[0x44; 32],
+            &1_000_000u64.to_le_bytes(),
+            &[0x22; 32],
+            b"counter\0",
+            &[0x33; 32],
+        ]);
+        assert_eq!(account.hash::<HarakaLeafHasher>(&[0x44; 32], 7), expect);
+    }
+
+    #[test]
+    fn test_leaf_binds_position_and_fields() {
+        let base = account();
+        let leaf = base.hash::<HarakaLeafHasher>(&[0x44; 32], 7);
+        assert_ne!(base.hash::<HarakaLeafHasher>(&[0x44; 32], 8), leaf);
+        assert_ne!(base.hash::<HarakaLeafHasher>(&[0x45; 32], 7), leaf);
+
+        let mut other = base;
+        other.lamports += 1;
+        assert_ne!(other.hash::<HarakaLeafHasher>(&[0x44; 32], 7), leaf);
+
+        // Absent fields differ from all-zero ones.
+        let mut absent = base;
+        absent.address = None;
+        let mut zero = base;
+        zero.address = Some([0; 32]);
+        assert_ne!(
+            absent.hash::<HarakaLeafHasher>(&[0x44; 32], 7),
+            zero.hash::<HarakaLeafHasher>(&[0x44; 32], 7)
+        );
+        absent.data = None;
+        assert_ne!(absent.hash::<HarakaLeafHasher>(&[0x44; 32], 7), leaf);
+    }
+}
@@ -1,52 +1,202 @@
-use crate::simd128::Simd128;
-
-pub(crate) static HARAKA_CONSTANTS: &[Simd128; 48] = &[
-    Simd128::from(0x0684704ce620c00ab2c5fef075817b9d),
-    Simd128::from(0x8b66b4e188f3a06b640f6ba42f08f717),
-    Simd128::from(0x3402de2d53f28498cf029d609f029114),
-    Simd128::from(0x0ed6eae62e7b4f08bbf3bcaffd5b4f79),
-    Simd128::from(0xcbcfb0cb4872448b79eecd1cbe397044),
-    Simd128::from(0x7eeacdee6e9032b78d5335ed2b8a057b),
-    Simd128::from(0x67c28f435e2e7cd0e2412761da4fef1b),
-    Simd128::from(0x2924d9b0afcacc07675ffde21fc70b3b),
-    Simd128::from(0xab4d63f1e6867fe9ecdb8fcab9d465ee),
-    Simd128::from(0x1c30bf84d4b7cd645b2a404fad037e33),
-    Simd128::from(0xb2cc0bb9941723bf69028b2e8df69800),
-    Simd128::from(0xfa0478a6de6f55724aaa9ec85c9d2d8a),
-    Simd128::from(0xdfb49f2b6b772a120efa4f2e29129fd4),
-    Simd128::from(0x1ea10344f449a23632d611aebb6a12ee),
-    Simd128::from(0xaf0449884b0500845f9600c99ca8eca6),
-    Simd128::from(0x21025ed89d199c4f78a2c7e327e593ec),
-    Simd128::from(0xbf3aaaf8a759c9b7b9282ecd82d40173),
-    Simd128::from(0x6260700d6186b01737f2efd910307d6b),
-    Simd128::from(0x5aca45c22130044381c29153f6fc9ac6),
-    Simd128::from(0x9223973c226b68bb2caf92e836d1943a),
-    Simd128::from(0xd3bf9238225886eb6cbab958e51071b4),
-    Simd128::from(0xdb863ce5aef0c677933dfddd24e1128d),
-    Simd128::from(0xbb606268ffeba09c83e48de3cb2212b1),
-    Simd128::from(0x734bd3dce2e4d19c2db91a4ec72bf77d),
-    Simd128::from(0x43bb47c361301b434b1415c42cb3924e),
-    Simd128::from(0xdba775a8e707eff603b231dd16eb6899),
-    Simd128::from(0x6df3614b3c7559778e5e23027eca472c),
-    Simd128::from(0xcda75a17d6de7d776d1be5b9b88617f9),
-    Simd128::from(0xec6b43f06ba8e9aa9d6c069da946ee5d),
-    Simd128::from(0xcb1e6950f957332ba25311593bf327c1),
-    Simd128::from(0x2cee0c7500da619ce4ed0353600ed0d9),
-    Simd128::from(0xf0b1a5a196e90cab80bbbabc63a4a350),
-    Simd128::from(0xae3db1025e962988ab0dde30938dca39),
-    Simd128::from(0x17bb8f38d554a40b8814f3a82e75b442),
-    Simd128::from(0x34bb8a5b5f427fd7aeb6b779360a16f6),
-    Simd128::from(0x26f65241cbe5543843ce5918ffbaafde),
-    Simd128::from(0x4ce99a54b9f3026aa2ca9cf7839ec978),
-    Simd128::from(0xae51a51a1bdff7be40c06e2822901235),
-    Simd128::from(0xa0c1613cba7ed22bc173bc0f48a659cf),
-    Simd128::from(0x756acc03022882884ad6bdfde9c59da1),
-    Simd128::from(0x2ff372380de7d31e367e4778848f2ad2),
-    Simd128::from(0x08d95c6acf74be8bee36b135b73bd58f),
-    Simd128::from(0x5880f434c9d6ee9866ae1838a3743e4a),
-    Simd128::from(0x593023f0aefabd99d0fdf4c79a9369bd),
-    Simd128::from(0x329ae3d1eb606e6fa5cc637b6f1ecb2a),
-    Simd128::from(0xe00207eb49e01594a4dc93d6cb7594ab),
-    Simd128::from(0x1caa0c4ff751c880942366a665208ef8),
-    Simd128::from(0x02f7f57fdb2dc1ddbd03239fe3e67e4a),
-];
+use arrayref::array_ref;
+
+/// The constants of one Haraka-512 round, or of two Haraka-256 rounds.
+pub(crate) type Round = [[u8; 16]; 8];
+
+/// A full constant table, one group per Haraka-512 round.
+pub(crate) type RoundConstants = [Round; MAX_ROUNDS_512];
+
+/// Largest Haraka-512 round count covered by the table (8 constants per round).
+pub(crate) const MAX_ROUNDS_512: usize = 6;
+
+/// Largest Haraka-256 round count covered by the table (4 constants per round).
+pub(crate) const MAX_ROUNDS_256: usize = 2 * MAX_ROUNDS_512;
+
+/// Number of constants in the table.
+pub(crate) const N_CONSTANTS: usize = 8 * MAX_ROUNDS_512;
+
+/// Aligns its contents to 16 bytes, the size of a lane.
+#[repr(C, align(16))]
+pub(crate) struct Aligned<T>(pub(crate) T);
+
+/// The constant table, parsed at compile time from `constants.bin`: 48
+/// lanes of 16 bytes, least significant byte first (the byte order of
+/// `_mm_load_si128`). Being one 16-byte-aligned static, every constant
+/// starts on a lane boundary and can be loaded with one aligned load.
+///
+/// The bits of the table are the parities of the first 6144 decimal digits
+/// of π after the point; the test oracle derives them from π and checks
+/// them against this file.
+static TABLE: Aligned<RoundConstants> = Aligned(parse(CONSTANT_BYTES));
+
+/// The raw contents of `constants.bin`.
+const CONSTANT_BYTES: &[u8; 16 * N_CONSTANTS] = include_bytes!("constants.bin");
+
+/// FNV-1a of `constants.bin`. The check below fails the build, rather
+/// than a test, when the file is changed by accident.
+const CONSTANTS_FNV1A: u64 = 0x9f584f6be017215a;
+
+const _: () = assert!(
+    fnv1a(CONSTANT_BYTES) == CONSTANTS_FNV1A,
+    "constants.bin does not hold the Haraka v2 round constants"
+);
+
+/// The 64-bit FNV-1a hash of `bytes`.
+const fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    hash
+}
+
+/// The Haraka v2 constants, in the order the rounds consume them: Haraka-512
+/// round `i` reads group `i` from start to end (the first AES round of the
+/// four lanes, then the second), and Haraka-256 rounds `2i` and `2i + 1`
+/// read its two halves in turn. A hash thus walks the table sequentially.
+pub(crate) static HARAKA_CONSTANTS: &RoundConstants = &TABLE.0;
+
+/// Splits the raw table into lanes.
+const fn parse(bytes: &[u8; 16 * N_CONSTANTS]) -> RoundConstants {
+    let mut rc = [[[0; 16]; 8]; MAX_ROUNDS_512];
+    let mut i = 0;
+    while i < N_CONSTANTS {
+        let mut j = 0;
+        while j < 16 {
+            rc[i / 8][i % 8][j] = bytes[16 * i + j];
+            j += 1;
+        }
+        i += 1;
+    }
+    rc
+}
+
+/// Returns the round constants with a domain tweak applied.
+///
+/// `tag` (at most 16 bytes, zero-padded) is XORed into the even-indexed
+/// constants and the block `[tag.len(), kind, 0, ...]` into the odd-indexed
+/// ones. Distinct `(kind, tag)` pairs thus give distinct tables, and every
+/// pair with a non-zero `kind` differs from the standard constants.
+///
+/// Kinds in use: `0x01` personalization strings, `0x02` domain tags,
+/// `0x03` truncated outputs, `0x04` WOTS+ public seeds.
+pub(crate) fn tweaked(kind: u8, tag: &[u8]) -> RoundConstants {
+    debug_assert!(tag.len() <= 16);
+    let mut even = [0u8; 16];
+    for (e, t) in even.iter_mut().zip(tag) {
+        *e = *t;
+    }
+    let mut odd = [0u8; 16];
+    odd[0] = tag.len() as u8;
+    odd[1] = kind;
+    let mut rc = *HARAKA_CONSTANTS;
+    for pair in rc.as_flattened_mut().chunks_exact_mut(2) {
+        for (lane, tweak) in pair.iter_mut().zip([&even, &odd]) {
+            for (b, t) in lane.iter_mut().zip(tweak) {
+                *b ^= t;
+            }
+        }
+    }
+    rc
+}
+
+/// Returns `i` if `key` is the standard constant
+/// `HARAKA_CONSTANTS.as_flattened()[i]` itself, as opposed to a copy or a
+/// tweaked constant, so the table round can use its folded S-box.
+#[cfg(feature = "folded-tables")]
+#[inline(always)]
+pub(crate) fn standard_index(key: &[u8; 16]) -> Option<usize> {
+    let base = HARAKA_CONSTANTS.as_flattened().as_ptr() as usize;
+    let i = (key as *const [u8; 16] as usize).wrapping_sub(base) / 16;
+    (i < N_CONSTANTS).then_some(i)
+}
+
+/// Returns the four constants of Haraka-256 round `i`, which is half of a
+/// Haraka-512 group.
+#[inline(always)]
+pub(crate) fn round256(rc: &RoundConstants, i: usize) -> &[[u8; 16]; 4] {
+    array_ref![rc[i / 2], 4 * (i % 2), 4]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_aligned() {
+        for lane in HARAKA_CONSTANTS.as_flattened() {
+            assert_eq!(lane as *const [u8; 16] as usize % 16, 0);
+        }
+    }
+
+    #[test]
+    fn test_table_matches_published_constants() {
+        // The constants of the Haraka v2 reference code, as the u128 values
+        // of its `_mm_set_epi32` arguments.
+        const PUBLISHED: [u128; N_CONSTANTS] = [
+            0x0684704ce620c00ab2c5fef075817b9d,
+            0x8b66b4e188f3a06b640f6ba42f08f717,
+            0x3402de2d53f28498cf029d609f029114,
+            0x0ed6eae62e7b4f08bbf3bcaffd5b4f79,
+            0xcbcfb0cb4872448b79eecd1cbe397044,
+            0x7eeacdee6e9032b78d5335ed2b8a057b,
+            0x67c28f435e2e7cd0e2412761da4fef1b,
+            0x2924d9b0afcacc07675ffde21fc70b3b,
+            0xab4d63f1e6867fe9ecdb8fcab9d465ee,
+            0x1c30bf84d4b7cd645b2a404fad037e33,
+            0xb2cc0bb9941723bf69028b2e8df69800,
+            0xfa0478a6de6f55724aaa9ec85c9d2d8a,
+            0xdfb49f2b6b772a120efa4f2e29129fd4,
+            0x1ea10344f449a23632d611aebb6a12ee,
+            0xaf0449884b0500845f9600c99ca8eca6,
+            0x21025ed89d199c4f78a2c7e327e593ec,
+            0xbf3aaaf8a759c9b7b9282ecd82d40173,
+            0x6260700d6186b01737f2efd910307d6b,
+            0x5aca45c22130044381c29153f6fc9ac6,
+            0x9223973c226b68bb2caf92e836d1943a,
+            0xd3bf9238225886eb6cbab958e51071b4,
+            0xdb863ce5aef0c677933dfddd24e1128d,
+            0xbb606268ffeba09c83e48de3cb2212b1,
+            0x734bd3dce2e4d19c2db91a4ec72bf77d,
+            0x43bb47c361301b434b1415c42cb3924e,
+            0xdba775a8e707eff603b231dd16eb6899,
+            0x6df3614b3c7559778e5e23027eca472c,
+            0xcda75a17d6de7d776d1be5b9b88617f9,
+            0xec6b43f06ba8e9aa9d6c069da946ee5d,
+            0xcb1e6950f957332ba25311593bf327c1,
+            0x2cee0c7500da619ce4ed0353600ed0d9,
+            0xf0b1a5a196e90cab80bbbabc63a4a350,
+            0xae3db1025e962988ab0dde30938dca39,
+            0x17bb8f38d554a40b8814f3a82e75b442,
+            0x34bb8a5b5f427fd7aeb6b779360a16f6,
+            0x26f65241cbe5543843ce5918ffbaafde,
+            0x4ce99a54b9f3026aa2ca9cf7839ec978,
+            0xae51a51a1bdff7be40c06e2822901235,
+            0xa0c1613cba7ed22bc173bc0f48a659cf,
+            0x756acc03022882884ad6bdfde9c59da1,
+            0x2ff372380de7d31e367e4778848f2ad2,
+            0x08d95c6acf74be8bee36b135b73bd58f,
+            0x5880f434c9d6ee9866ae1838a3743e4a,
+            0x593023f0aefabd99d0fdf4c79a9369bd,
+            0x329ae3d1eb606e6fa5cc637b6f1ecb2a,
+            0xe00207eb49e01594a4dc93d6cb7594ab,
+            0x1caa0c4ff751c880942366a665208ef8,
+            0x02f7f57fdb2dc1ddbd03239fe3e67e4a,
+        ];
+        let table = HARAKA_CONSTANTS.as_flattened();
+        for (i, (lane, published)) in table.iter().zip(PUBLISHED).enumerate() {
+            assert_eq!(*lane, published.to_le_bytes(), "constant {i}");
+        }
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        // Test vectors of the FNV reference code.
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+}
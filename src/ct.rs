@@ -0,0 +1,42 @@
+//! Branch-free helpers for comparing digests without leaking timing
+//! information about the compared values.
+
+/// Returns 1 if `a < b` and 0 otherwise, without branching on the inputs.
+#[inline(always)]
+pub(crate) fn lt_u64(a: u64, b: u64) -> u64 {
+    // The borrow of the widened subtraction ends up in the top bit.
+    ((a as u128).wrapping_sub(b as u128) >> 127) as u64
+}
+
+/// Returns `true` if both byte arrays are equal, inspecting every byte.
+#[inline(always)]
+pub(crate) fn eq<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool {
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+    core::hint::black_box(diff) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lt_u64() {
+        assert_eq!(lt_u64(0, 1), 1);
+        assert_eq!(lt_u64(1, 0), 0);
+        assert_eq!(lt_u64(7, 7), 0);
+        assert_eq!(lt_u64(0, u64::MAX), 1);
+        assert_eq!(lt_u64(u64::MAX, 0), 0);
+        assert_eq!(lt_u64(u64::MAX - 1, u64::MAX), 1);
+    }
+
+    #[test]
+    fn test_eq() {
+        assert!(eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!eq(&[0x80, 0, 0], &[0, 0, 0]));
+        assert!(eq(&[], &[]));
+    }
+}
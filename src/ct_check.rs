@@ -0,0 +1,146 @@
+//! Machine check of the constant-time claim for the software AES path.
+//!
+//! Each probe below is a non-inlined instance of a hash function over
+//! secret input. The test disassembles the test binary itself, walks the
+//! call graph from every probe and fails on any forward conditional branch
+//! it reaches, except bounds checks whose only effect is a panic. Loop
+//! back-edges are allowed: every loop on these paths runs over a round,
+//! lane or block index, never over data. What remains is straight-line
+//! code, with nothing for the secret bytes to steer. The check only means
+//! something for optimized code on the bitsliced S-box, so it needs
+//!
+//! ```text
+//! cargo test --release --features ct-check ct_check
+//! ```
+//!
+//! and `llvm-objdump` (or `OBJDUMP`) on the path. It targets x86-64, and
+//! is not built with `sbox-table`: the table round is branch-free too, but
+//! its loads are indexed by secret bytes, which this check does not see.
+//! Nor is it built with `small-code`, whose shared round loops branch on
+//! the (public) round count.
+
+use crate::disasm::{address, callees, is_panic, mnemonic, Binary};
+use crate::haraka256::haraka256;
+use crate::haraka512::{haraka512, haraka512_perm};
+use crate::haraka512_keyed::haraka512_keyed;
+use core::hint::black_box;
+use std::collections::BTreeMap;
+use std::string::String;
+
+#[inline(never)]
+fn probe_haraka256(dst: &mut [u8; 32], src: &[u8; 32]) {
+    haraka256::<5>(dst, src)
+}
+
+#[inline(never)]
+fn probe_haraka512(dst: &mut [u8; 32], src: &[u8; 64]) {
+    haraka512::<5>(dst, src)
+}
+
+#[inline(never)]
+fn probe_haraka512_perm(dst: &mut [u8; 64], src: &[u8; 64]) {
+    haraka512_perm::<5>(dst, src)
+}
+
+#[inline(never)]
+fn probe_haraka512_keyed(dst: &mut [u8; 32], src: &[u8; 64], key: &[u8; 64]) {
+    haraka512_keyed::<5>(dst, src, key)
+}
+
+const PROBES: [&str; 4] = [
+    "probe_haraka256",
+    "probe_haraka512",
+    "probe_haraka512_perm",
+    "probe_haraka512_keyed",
+];
+
+fn line_address(line: &str) -> Option<u64> {
+    line.split_once(':').and_then(|(addr, _)| address(addr))
+}
+
+/// Returns the target of a forward conditional branch, or `None` for any
+/// other instruction.
+fn forward_branch(line: &str) -> Option<u64> {
+    let m = mnemonic(line)?;
+    if !m.starts_with('j') || m == "jmp" || m == "jmpq" {
+        return None;
+    }
+    let here = line_address(line)?;
+    let target = line
+        .split('\t')
+        .nth(2)
+        .and_then(|ops| ops.split_whitespace().next())
+        .and_then(address)
+        .unwrap_or(u64::MAX);
+    (target > here).then_some(target)
+}
+
+/// Returns true if the code at `target` reaches a panic before any other
+/// control flow, i.e. the branch is a bounds or assertion check.
+fn branches_to_panic(body: &[String], target: u64, got: &BTreeMap<u64, String>) -> bool {
+    let Some(start) = body.iter().position(|l| line_address(l) == Some(target)) else {
+        return false;
+    };
+    for line in &body[start..] {
+        let Some(m) = mnemonic(line) else { continue };
+        if m.starts_with("call") || m.starts_with('j') || m.starts_with("ret") {
+            let mut targets = callees(core::slice::from_ref(line), got).peekable();
+            return targets.peek().is_some() && targets.all(is_panic);
+        }
+    }
+    false
+}
+
+/// Walks the call graph from `probe` and returns the first conditional
+/// branch that is neither a loop back-edge nor a panic check.
+fn check(binary: &Binary, probe: &str) -> Result<(), String> {
+    binary.walk(&std::format!("ct_check::{probe}"), |name, body| {
+        if is_panic(name) {
+            return Ok(());
+        }
+        for line in body {
+            if let Some(target) = forward_branch(line) {
+                if !branches_to_panic(body, target, &binary.got) {
+                    return Err(std::format!("conditional branch in {name}: {line}"));
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// A deliberately leaky probe: whether the hash runs depends on the input.
+#[inline(never)]
+fn probe_leaky(dst: &mut [u8; 32], src: &[u8; 32]) {
+    if src[0] & 1 == 1 {
+        haraka256::<5>(dst, src)
+    }
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_no_branches_on_secret_paths() {
+    // Keep the probes alive in the binary, with inputs the optimizer
+    // cannot see.
+    let mut d32 = [0u8; 32];
+    let mut d64 = [0u8; 64];
+    probe_haraka256(&mut d32, black_box(&[0; 32]));
+    probe_haraka512(&mut d32, black_box(&[0; 64]));
+    probe_haraka512_perm(&mut d64, black_box(&[0; 64]));
+    probe_haraka512_keyed(&mut d32, black_box(&[0; 64]), black_box(&[0; 64]));
+
+    let binary = Binary::current();
+    for probe in PROBES {
+        if let Err(err) = check(&binary, probe) {
+            panic!("{probe}: {err}");
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_flags_data_dependent_branches() {
+    probe_leaky(&mut [0; 32], black_box(&[1; 32]));
+    let err = check(&Binary::current(), "probe_leaky").unwrap_err();
+    assert!(err.contains("probe_leaky"), "{err}");
+}
@@ -0,0 +1,133 @@
+//! Fingerprint and bucket derivation for cuckoo filters.
+//!
+//! An item is hashed once with Haraka-256. The first little-endian 64-bit
+//! word of the digest selects its primary bucket and the next 32 bits give
+//! its fingerprint. The alternate bucket is `index ^ H(fingerprint)`, which
+//! can be computed from either bucket and the stored fingerprint alone, so
+//! entries can be relocated without knowing the original item.
+//!
+//! The number of buckets must be a power of two for the XOR relation to be
+//! an involution.
+
+use crate::haraka256::haraka256;
+use arrayref::array_ref;
+
+/// Fingerprint and candidate buckets of an item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slot {
+    /// Non-zero fingerprint, `bits` bits wide.
+    pub fingerprint: u32,
+    /// Primary bucket.
+    pub index: u64,
+    /// Alternate bucket, see [`alt_index`].
+    pub alt_index: u64,
+}
+
+/// Derives the fingerprint and both buckets of `item`.
+///
+/// The fingerprint keeps the low `bits` bits of the digest word and is
+/// never zero, so zero can mark empty entries.
+///
+/// # Panics
+///
+/// Panics if `bits` is not in `1..=32` or `buckets` is not a power of two.
+pub fn slot<const N_ROUNDS: usize>(item: &[u8; 32], bits: u32, buckets: u64) -> Slot {
+    assert!((1..=32).contains(&bits), "fingerprint must be 1 to 32 bits");
+    assert!(
+        buckets.is_power_of_two(),
+        "bucket count must be a power of two"
+    );
+
+    let mut digest = [0u8; 32];
+    haraka256::<N_ROUNDS>(&mut digest, item);
+    let index = u64::from_le_bytes(*array_ref![digest, 0, 8]) & (buckets - 1);
+    let word = u32::from_le_bytes(*array_ref![digest, 8, 4]);
+    let mask = u32::MAX >> (32 - bits);
+    let fingerprint = match word & mask {
+        0 => 1,
+        fp => fp,
+    };
+    Slot {
+        fingerprint,
+        index,
+        alt_index: alt_index::<N_ROUNDS>(index, fingerprint, buckets),
+    }
+}
+
+/// Returns the other bucket of an entry stored in bucket `index` with the
+/// given `fingerprint`.
+///
+/// `alt_index(alt_index(i, fp), fp) == i` for every bucket `i`.
+///
+/// # Panics
+///
+/// Panics if `buckets` is not a power of two.
+pub fn alt_index<const N_ROUNDS: usize>(index: u64, fingerprint: u32, buckets: u64) -> u64 {
+    assert!(
+        buckets.is_power_of_two(),
+        "bucket count must be a power of two"
+    );
+
+    let mut src = [0u8; 32];
+    src[..4].copy_from_slice(&fingerprint.to_le_bytes());
+    let mut digest = [0u8; 32];
+    haraka256::<N_ROUNDS>(&mut digest, &src);
+    (index ^ u64::from_le_bytes(*array_ref![digest, 0, 8])) & (buckets - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(i: u32) -> [u8; 32] {
+        let mut item = [0xc3u8; 32];
+        item[..4].copy_from_slice(&i.to_le_bytes());
+        item
+    }
+
+    #[test]
+    fn test_slot_in_range() {
+        for i in 0..100 {
+            let slot = slot::<5>(&item(i), 12, 1 << 10);
+            assert!(slot.fingerprint != 0 && slot.fingerprint < 1 << 12);
+            assert!(slot.index < 1 << 10);
+            assert!(slot.alt_index < 1 << 10);
+        }
+    }
+
+    #[test]
+    fn test_alt_index_involution() {
+        for i in 0..100 {
+            let slot = slot::<5>(&item(i), 16, 1 << 8);
+            assert_eq!(
+                alt_index::<5>(slot.alt_index, slot.fingerprint, 1 << 8),
+                slot.index
+            );
+        }
+    }
+
+    #[test]
+    fn test_slot_is_stable() {
+        assert_eq!(slot::<5>(&item(9), 8, 64), slot::<5>(&item(9), 8, 64));
+        assert_ne!(slot::<5>(&item(9), 32, 64), slot::<5>(&item(10), 32, 64));
+    }
+
+    #[test]
+    fn test_single_bit_fingerprint() {
+        for i in 0..16 {
+            assert_eq!(slot::<5>(&item(i), 1, 2).fingerprint, 1);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "bucket count must be a power of two")]
+    fn test_buckets_power_of_two() {
+        slot::<5>(&item(0), 8, 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "fingerprint must be 1 to 32 bits")]
+    fn test_fingerprint_bits() {
+        slot::<5>(&item(0), 0, 16);
+    }
+}
@@ -0,0 +1,349 @@
+use crate::encoding;
+use crate::error::Error;
+use arrayref::array_ref;
+use core::fmt;
+
+/// A 32-byte Haraka digest.
+///
+/// This is a thin wrapper around `[u8; 32]` that gives digests a distinct
+/// type and hosts helpers such as the 64-bit word view used by difficulty
+/// checks.
+///
+/// Digests are ordered lexicographically by their bytes, which is the order
+/// of the digests read as 256-bit big-endian integers. This is the order
+/// used by [`meets_target`](crate::meets_target), and lets digests serve as
+/// `BTreeMap` keys. The comparison is not constant-time.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Digest([u8; 32]);
+
+impl Digest {
+    /// Wraps raw digest bytes.
+    #[inline(always)]
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the digest bytes.
+    #[inline(always)]
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Consumes the digest and returns its bytes.
+    #[inline(always)]
+    pub const fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Returns the digest as four big-endian 64-bit words.
+    ///
+    /// Comparing the word arrays lexicographically gives the same order as
+    /// comparing the bytes, and `words[0].leading_zeros()` counts the
+    /// leading zero bits of the first word.
+    #[inline(always)]
+    pub fn to_words(&self) -> [u64; 4] {
+        words(&self.0)
+    }
+
+    /// Builds a digest from four big-endian 64-bit words.
+    ///
+    /// This is the inverse of [`Digest::to_words`].
+    #[inline(always)]
+    pub fn from_words(words: &[u64; 4]) -> Self {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        Self(bytes)
+    }
+
+    /// Writes the lowercase hex encoding of the digest into `out` and
+    /// returns it.
+    pub fn encode_hex<'a>(&self, out: &'a mut [u8; 64]) -> &'a str {
+        encoding::encode_hex(&self.0, out)
+    }
+
+    /// Parses a digest from 64 hex characters of either case.
+    pub fn decode_hex(text: &str) -> Result<Self, Error> {
+        let mut bytes = [0u8; 32];
+        encoding::decode_hex(text.as_bytes(), &mut bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Writes the padded standard Base64 encoding of the digest into `out`
+    /// and returns it.
+    #[cfg(feature = "base64")]
+    pub fn encode_base64<'a>(&self, out: &'a mut [u8; 44]) -> &'a str {
+        encoding::encode_base64(&self.0, out)
+    }
+
+    /// Parses a digest from its canonical padded Base64 encoding.
+    #[cfg(feature = "base64")]
+    pub fn decode_base64(text: &str) -> Result<Self, Error> {
+        let mut bytes = [0u8; 32];
+        encoding::decode_base64(text.as_bytes(), &mut bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Encodes the digest in Base58, like a Solana address.
+    #[cfg(feature = "base58")]
+    pub fn to_base58(&self) -> crate::Base58 {
+        crate::base58::encode(&self.0)
+    }
+}
+
+impl From<[u8; 32]> for Digest {
+    #[inline(always)]
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Digest> for [u8; 32] {
+    #[inline(always)]
+    fn from(digest: Digest) -> Self {
+        digest.0
+    }
+}
+
+/// Reads a digest from exactly 32 bytes, e.g. out of instruction data.
+impl TryFrom<&[u8]> for Digest {
+    type Error = Error;
+
+    #[inline(always)]
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| Error::InvalidLength {
+            expected: 32,
+            actual: bytes.len(),
+        })?;
+        Ok(Self(bytes))
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Shows the first two and the last byte, e.g. `Digest(3fa9…b2)`; the
+/// alternate form `{:#?}` shows every byte.
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "Digest({self:x})")
+        } else {
+            let b = &self.0;
+            write!(f, "Digest({:02x}{:02x}…{:02x})", b[0], b[1], b[31])
+        }
+    }
+}
+
+/// Formats the digest as 64 lowercase hex characters.
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut text = [0u8; 64];
+        f.write_str(self.encode_hex(&mut text))
+    }
+}
+
+impl fmt::UpperHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in &self.0 {
+            write!(f, "{b:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits 32 digest bytes into four big-endian 64-bit words.
+#[inline(always)]
+pub(crate) fn words(bytes: &[u8; 32]) -> [u64; 4] {
+    [
+        u64::from_be_bytes(*array_ref![bytes, 0, 8]),
+        u64::from_be_bytes(*array_ref![bytes, 8, 8]),
+        u64::from_be_bytes(*array_ref![bytes, 16, 8]),
+        u64::from_be_bytes(*array_ref![bytes, 24, 8]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_words() {
+        let mut bytes = [0u8; 32];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let expect = [
+            0x0001020304050607,
+            0x08090a0b0c0d0e0f,
+            0x1011121314151617,
+            0x18191a1b1c1d1e1f,
+        ];
+        assert_eq!(Digest::new(bytes).to_words(), expect);
+    }
+
+    #[test]
+    fn test_words_roundtrip() {
+        let words = [0xdeadbeef, 0, u64::MAX, 0x0123456789abcdef];
+        assert_eq!(Digest::from_words(&words).to_words(), words);
+    }
+
+    #[test]
+    fn test_words_preserve_order() {
+        let mut lo = [0u8; 32];
+        let mut hi = [0u8; 32];
+        lo[7] = 0xff;
+        hi[6] = 0x01;
+        assert!(lo < hi);
+        assert!(words(&lo) < words(&hi));
+    }
+
+    /// Formats into a fixed buffer, since tests run without `std`.
+    struct Buf {
+        bytes: [u8; 128],
+        len: usize,
+    }
+
+    impl fmt::Write for Buf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    fn format(args: fmt::Arguments<'_>) -> Buf {
+        let mut buf = Buf {
+            bytes: [0; 128],
+            len: 0,
+        };
+        fmt::Write::write_fmt(&mut buf, args).unwrap();
+        buf
+    }
+
+    impl PartialEq<&str> for Buf {
+        fn eq(&self, other: &&str) -> bool {
+            &self.bytes[..self.len] == other.as_bytes()
+        }
+    }
+
+    impl fmt::Debug for Buf {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(core::str::from_utf8(&self.bytes[..self.len]).unwrap())
+        }
+    }
+
+    const FULL: &str = "3fa91111111111111111111111111111111111111111111111111111111111b2";
+
+    #[test]
+    fn test_formatting() {
+        let mut bytes = [0x11u8; 32];
+        bytes[0] = 0x3f;
+        bytes[1] = 0xa9;
+        bytes[31] = 0xb2;
+        let digest = Digest::new(bytes);
+        assert_eq!(format(format_args!("{digest:?}")), "Digest(3fa9…b2)");
+        assert_eq!(format(format_args!("{digest}")), FULL);
+        assert_eq!(format(format_args!("{digest:x}")), FULL);
+        assert_eq!(
+            format(format_args!("{digest:X}")),
+            "3FA91111111111111111111111111111111111111111111111111111111111B2"
+        );
+        assert_eq!(
+            format(format_args!("{digest:#?}")),
+            "Digest(3fa91111111111111111111111111111111111111111111111111111111111b2)"
+        );
+    }
+
+    #[test]
+    fn test_ordering_is_big_endian() {
+        let mut lo = [0u8; 32];
+        let mut hi = [0u8; 32];
+        lo[31] = 0xff;
+        hi[30] = 0x01;
+        let (lo, hi) = (Digest::new(lo), Digest::new(hi));
+        assert!(lo < hi);
+        assert_eq!(lo.cmp(&lo), core::cmp::Ordering::Equal);
+        assert_eq!(lo.to_words() < hi.to_words(), lo < hi);
+        // Consistent with the constant-time target check.
+        assert!(crate::meets_target(lo.as_bytes(), hi.as_bytes()));
+        assert!(!crate::meets_target(hi.as_bytes(), lo.as_bytes()));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let data = [9u8; 40];
+        let digest = Digest::try_from(&data[4..36]).unwrap();
+        assert_eq!(digest.as_ref(), &data[..32]);
+        assert_eq!(
+            Digest::try_from(&data[..]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                actual: 40
+            })
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        let digest = Digest::new(core::array::from_fn(|i| i as u8));
+        let mut text = [0u8; 64];
+        let hex = digest.encode_hex(&mut text);
+        assert_eq!(
+            hex,
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+        );
+        assert_eq!(Digest::decode_hex(hex), Ok(digest));
+        assert_eq!(
+            Digest::decode_hex(&hex[1..]),
+            Err(Error::InvalidLength {
+                expected: 64,
+                actual: 63
+            })
+        );
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_base64() {
+        let digest = Digest::new([0xfb; 32]);
+        let mut text = [0u8; 44];
+        let b64 = digest.encode_base64(&mut text);
+        assert_eq!(b64, "+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/s=");
+        assert_eq!(Digest::decode_base64(b64), Ok(digest));
+    }
+
+    #[cfg(feature = "base58")]
+    #[test]
+    fn test_to_base58() {
+        let digest = Digest::new([0u8; 32]);
+        assert_eq!(&*digest.to_base58(), "11111111111111111111111111111111");
+    }
+
+    #[test]
+    fn test_haraka256_words() {
+        let src = [0x42u8; 32];
+        let mut dst = [0u8; 32];
+        crate::haraka256::<5>(&mut dst, &src);
+        assert_eq!(
+            crate::haraka256_words::<5>(&src),
+            Digest::new(dst).to_words()
+        );
+    }
+}
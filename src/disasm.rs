@@ -0,0 +1,228 @@
+//! Test helper: disassembly of the running test binary.
+//!
+//! Used by the codegen checks (`ct_check`, `no_panic`, `word_ops`), which look at the
+//! optimized code of small non-inlined probe functions and everything they
+//! call. Expects `llvm-objdump` (or `OBJDUMP`) and x86-64 code.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::Command;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// Splits `objdump -d` output into function bodies keyed by symbol name,
+/// and returns the symbol name at each function start address.
+fn functions(disasm: &str) -> (BTreeMap<String, Vec<String>>, BTreeMap<u64, String>) {
+    let mut out = BTreeMap::new();
+    let mut starts = BTreeMap::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    for line in disasm.lines() {
+        if let Some((addr, name)) = line
+            .split_once(" <")
+            .and_then(|(addr, rest)| Some((address(addr)?, rest.strip_suffix(">:")?)))
+        {
+            if let Some((name, body)) = current.take() {
+                out.insert(name, body);
+            }
+            starts.insert(addr, name.to_string());
+            current = Some((name.to_string(), Vec::new()));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push(line.to_string());
+        }
+    }
+    if let Some((name, body)) = current {
+        out.insert(name, body);
+    }
+    (out, starts)
+}
+
+/// Maps GOT slots to the functions they point to, from `objdump -R`.
+/// Calls into other crates go through `R_X86_64_RELATIVE` slots; calls
+/// into the C library (`memcpy` and friends) through `R_X86_64_GLOB_DAT`
+/// slots, whose targets are recorded in the second map.
+fn got_targets(
+    relocs: &str,
+    starts: &BTreeMap<u64, String>,
+) -> (BTreeMap<u64, String>, BTreeSet<String>) {
+    let mut got = BTreeMap::new();
+    let mut external = BTreeSet::new();
+    for line in relocs.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(slot), Some(kind), Some(value)) = (
+            fields.next().and_then(address),
+            fields.next(),
+            fields.next(),
+        ) else {
+            continue;
+        };
+        match kind {
+            "R_X86_64_RELATIVE" => {
+                let target = value.strip_prefix("*ABS*+").and_then(address);
+                if let Some(name) = target.and_then(|t| starts.get(&t)) {
+                    got.insert(slot, name.clone());
+                }
+            }
+            "R_X86_64_GLOB_DAT" | "R_X86_64_JUMP_SLOT" => {
+                got.insert(slot, value.to_string());
+                external.insert(value.to_string());
+            }
+            _ => {}
+        }
+    }
+    (got, external)
+}
+
+pub(crate) fn mnemonic(line: &str) -> Option<&str> {
+    // `  addr: bytes \t mnemonic \t operands`
+    line.split('\t').nth(1).map(str::trim)
+}
+
+pub(crate) fn address(text: &str) -> Option<u64> {
+    u64::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// Returns true for the functions a panic goes through.
+pub(crate) fn is_panic(name: &str) -> bool {
+    name.starts_with("core::panicking::")
+        || name.starts_with("core::slice::index::")
+        || name.contains("unwrap_failed")
+        || name.contains("expect_failed")
+}
+
+/// Functions a body may call: direct call and tail-call targets, and any
+/// function whose address is loaded from the GOT (an indirect call).
+pub(crate) fn callees<'a>(
+    body: &'a [String],
+    got: &'a BTreeMap<u64, String>,
+) -> impl Iterator<Item = &'a str> {
+    body.iter().filter_map(|line| {
+        if let Some(slot) = line
+            .split_once("# ")
+            .and_then(|(_, c)| address(c.split_whitespace().next()?))
+        {
+            if let Some(name) = got.get(&slot) {
+                return Some(name.as_str());
+            }
+        }
+        let m = mnemonic(line)?;
+        if !(m.starts_with("call") || m.starts_with("jmp")) || line.contains('*') {
+            return None;
+        }
+        // Jumps to `name+offset` stay within a function.
+        let target = line.rsplit_once(" <")?.1.strip_suffix('>')?;
+        (!target.contains('+')).then_some(target)
+    })
+}
+
+pub(crate) struct Binary {
+    funcs: BTreeMap<String, Vec<String>>,
+    pub(crate) got: BTreeMap<u64, String>,
+    /// C library functions, which are not disassembled.
+    external: BTreeSet<String>,
+}
+
+impl Binary {
+    /// Disassembles the running test binary.
+    pub(crate) fn current() -> Self {
+        let objdump = std::env::var("OBJDUMP").unwrap_or_else(|_| "llvm-objdump".into());
+        let exe = std::env::current_exe().unwrap();
+        let run = |args: &[&str]| {
+            let output = Command::new(&objdump)
+                .args(args)
+                .arg(&exe)
+                .output()
+                .expect("failed to run objdump");
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        let (funcs, starts) = functions(&run(&["-d", "--demangle", "--no-show-raw-insn"]));
+        let (got, external) = got_targets(&run(&["-R"]), &starts);
+        Self {
+            funcs,
+            got,
+            external,
+        }
+    }
+
+    /// Walks the call graph from the function whose name contains `probe`,
+    /// calling `visit` with the name and body of every function reached.
+    /// Panic functions are reported to `visit` but not entered; C library
+    /// functions are skipped.
+    pub(crate) fn walk(
+        &self,
+        probe: &str,
+        mut visit: impl FnMut(&str, &[String]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let root = self
+            .funcs
+            .keys()
+            .find(|name| name.contains(probe))
+            .ok_or_else(|| std::format!("{probe} not found in the binary"))?;
+        let mut seen = BTreeSet::new();
+        let mut stack = std::vec![root.as_str()];
+        while let Some(name) = stack.pop() {
+            if !seen.insert(name) || self.external.contains(name) {
+                continue;
+            }
+            let body = self
+                .funcs
+                .get(name)
+                .ok_or_else(|| std::format!("{name} was not disassembled"))?;
+            visit(name, body)?;
+            if !is_panic(name) {
+                stack.extend(callees(body, &self.got));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the longest call chain from the function whose name contains
+    /// `probe`, outermost first. Tail calls count as calls, and C library
+    /// functions as leaves; panic functions are left out. Fails on
+    /// recursion, whose depth is unbounded.
+    #[cfg(feature = "no-panic-check")]
+    pub(crate) fn deepest_chain(&self, probe: &str) -> Result<Vec<String>, String> {
+        let root = self
+            .funcs
+            .keys()
+            .find(|name| name.contains(probe))
+            .ok_or_else(|| std::format!("{probe} not found in the binary"))?;
+        let mut memo = BTreeMap::new();
+        let mut open = BTreeSet::new();
+        self.chain(root, &mut memo, &mut open)
+    }
+
+    #[cfg(feature = "no-panic-check")]
+    fn chain<'a>(
+        &'a self,
+        name: &'a str,
+        memo: &mut BTreeMap<&'a str, Vec<String>>,
+        open: &mut BTreeSet<&'a str>,
+    ) -> Result<Vec<String>, String> {
+        if let Some(chain) = memo.get(name) {
+            return Ok(chain.clone());
+        }
+        if !open.insert(name) {
+            return Err(std::format!("{name} is recursive"));
+        }
+        let mut deepest = Vec::new();
+        if !self.external.contains(name) {
+            let body = self
+                .funcs
+                .get(name)
+                .ok_or_else(|| std::format!("{name} was not disassembled"))?;
+            for callee in callees(body, &self.got) {
+                if is_panic(callee) {
+                    continue;
+                }
+                let chain = self.chain(callee, memo, open)?;
+                if chain.len() > deepest.len() {
+                    deepest = chain;
+                }
+            }
+        }
+        open.remove(name);
+        deepest.insert(0, name.to_string());
+        memo.insert(name, deepest.clone());
+        Ok(deepest)
+    }
+}
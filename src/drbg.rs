@@ -0,0 +1,494 @@
+//! Deterministic random bit generator and sampling helpers.
+//!
+//! [`HarakaDrbg`] runs Haraka-512 in counter mode: output block `i` is
+//!
+//! ```text
+//! haraka512_domain(DRBG_TAG, key || u64le(i) || 0^24)
+//! ```
+//!
+//! where `key` is the 32-byte seed. Every platform produces the same
+//! stream for the same seed, so selections made off-chain can be replayed
+//! and checked by an on-chain program.
+//!
+//! Long-lived generators can protect past output against a later leak of
+//! their state. [`HarakaDrbg::ratchet`], or every output request of a
+//! generator made with [`HarakaDrbg::new_ratcheting`], replaces the key
+//! with
+//!
+//! ```text
+//! haraka512_domain(DRBG_TAG, key || u64le(i) || 0x01 || 0^23)
+//! ```
+//!
+//! and discards the rest of the current block, so the state no longer
+//! determines anything already handed out. [`HarakaDrbg::reseed`] mixes
+//! fresh entropy into the key,
+//!
+//! ```text
+//! key = HarakaS(RESEED_DOMAIN || key || u64le(len) || entropy)
+//! ```
+//!
+//! and restarts the counter, so a leaked state stops predicting output
+//! once entropy unknown to the attacker has been added.
+//!
+//! [`derive_randomness`] mixes a recent blockhash with program seeds into
+//! such a seed:
+//!
+//! ```text
+//! HarakaS(RANDOMNESS_DOMAIN || blockhash || u64le(len) || seed || ...)
+//! ```
+//!
+//! with every seed prefixed by its length, so no two seed lists share an
+//! encoding.
+
+use crate::domain::haraka512_domain;
+use crate::error::Error;
+use crate::sponge::{domain_block, HarakaS};
+use core::num::NonZeroU64;
+
+/// Domain tag of the DRBG output blocks.
+const DRBG_TAG: &[u8; 15] = b"haraka-bpf/drbg";
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// Byte 40 of the Haraka-512 input: zero for output blocks, one for the
+/// ratchet.
+const RATCHET: u8 = 0x01;
+
+/// Domain block absorbed before the key when reseeding.
+const RESEED_DOMAIN: &[u8; 32] = &domain_block("haraka-bpf/drbg-reseed/v1");
+
+/// Domain block absorbed before the blockhash.
+const RANDOMNESS_DOMAIN: &[u8; 32] = &domain_block("haraka-bpf/randomness/v1");
+
+/// Derives 32 bytes of randomness from `recent_blockhash` and `seeds`.
+pub fn derive_randomness(recent_blockhash: &[u8; 32], seeds: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = HarakaS::with_domain(RANDOMNESS_DOMAIN);
+    hasher.update(recent_blockhash);
+    for seed in seeds {
+        hasher.update(&(seed.len() as u64).to_le_bytes());
+        hasher.update(seed);
+    }
+    hasher.finalize()
+}
+
+/// Haraka-512 counter-mode generator.
+#[derive(Clone)]
+pub struct HarakaDrbg {
+    key: [u8; 32],
+    counter: u64,
+    /// Current output block.
+    block: [u8; 32],
+    /// Number of bytes of `block` already handed out.
+    pos: usize,
+    /// Whether every output request ends with a ratchet.
+    ratcheting: bool,
+}
+
+impl HarakaDrbg {
+    /// Creates a generator from a 32-byte seed.
+    pub fn new(seed: &[u8; 32]) -> Self {
+        Self {
+            key: *seed,
+            counter: 0,
+            block: [0u8; 32],
+            pos: 32,
+            ratcheting: false,
+        }
+    }
+
+    /// Creates a generator from a 32-byte seed that ratchets after every
+    /// output request: each call to [`fill_bytes`](Self::fill_bytes),
+    /// [`next_u64`](Self::next_u64) and the samplers built on them.
+    ///
+    /// Its stream differs from that of [`new`](Self::new) after the first
+    /// request, and depends on how the output is split into requests.
+    pub fn new_ratcheting(seed: &[u8; 32]) -> Self {
+        Self {
+            ratcheting: true,
+            ..Self::new(seed)
+        }
+    }
+
+    /// Computes block `counter` of the stream, or the ratcheted key when
+    /// `kind` is [`RATCHET`].
+    fn block(&self, kind: u8) -> [u8; 32] {
+        let mut input = [0u8; 64];
+        input[..32].copy_from_slice(&self.key);
+        input[32..40].copy_from_slice(&self.counter.to_le_bytes());
+        input[40] = kind;
+        let mut out = [0u8; 32];
+        haraka512_domain::<ROUNDS, _>(&mut out, DRBG_TAG, &input);
+        out
+    }
+
+    fn refill(&mut self) {
+        self.block = self.block(0);
+        self.counter = self.counter.wrapping_add(1);
+        self.pos = 0;
+    }
+
+    /// Discards the rest of the current block.
+    fn discard_block(&mut self) {
+        self.block = [0u8; 32];
+        self.pos = 32;
+    }
+
+    /// Replaces the key with a one-way function of the key and counter,
+    /// and discards the unread rest of the current block.
+    ///
+    /// A state captured afterwards reveals nothing about earlier output;
+    /// the output that follows stays a deterministic function of the seed.
+    pub fn ratchet(&mut self) {
+        self.key = self.block(RATCHET);
+        self.discard_block();
+    }
+
+    /// Mixes `entropy` into the key, restarts the counter and discards the
+    /// unread rest of the current block.
+    ///
+    /// Output after a reseed depends on every seed and every reseed so far;
+    /// an attacker who captured the state earlier cannot predict it without
+    /// knowing `entropy`.
+    pub fn reseed(&mut self, entropy: &[u8]) {
+        let mut hasher = HarakaS::with_domain(RESEED_DOMAIN);
+        hasher.update(&self.key);
+        hasher.update(&(entropy.len() as u64).to_le_bytes());
+        hasher.update(entropy);
+        self.key = hasher.finalize();
+        self.counter = 0;
+        self.discard_block();
+    }
+
+    /// Fills `out` with the next bytes of the stream.
+    pub fn fill_bytes(&mut self, mut out: &mut [u8]) {
+        while !out.is_empty() {
+            if self.pos == 32 {
+                self.refill();
+            }
+            let take = (32 - self.pos).min(out.len());
+            let (head, tail) = out.split_at_mut(take);
+            for (o, b) in head.iter_mut().zip(self.block.iter().skip(self.pos)) {
+                *o = *b;
+            }
+            self.pos += take;
+            out = tail;
+        }
+        if self.ratcheting {
+            self.ratchet();
+        }
+    }
+
+    /// Returns the next 8 bytes of the stream as a little-endian integer.
+    pub fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Returns a uniformly distributed integer in `0..bound`.
+    ///
+    /// Draws that would bias the result are rejected and redrawn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    pub fn gen_below(&mut self, bound: u64) -> u64 {
+        let bound = NonZeroU64::new(bound).expect("bound must not be zero");
+        self.below(bound)
+    }
+
+    /// [`gen_below`](Self::gen_below) for a bound that cannot be zero.
+    fn below(&mut self, bound: NonZeroU64) -> u64 {
+        let bound = bound.get();
+        // Largest multiple of `bound` that fits, minus one.
+        let zone = u64::MAX - (u64::MAX - bound + 1) % bound;
+        loop {
+            let x = self.next_u64();
+            if x <= zone {
+                return x % bound;
+            }
+        }
+    }
+
+    /// Shuffles `items` in place with the Fisher-Yates algorithm.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        // Shrinks the slice instead of counting down, and clamps `j <= i`,
+        // so that the optimizer can see both indices are in bounds.
+        let mut items = items;
+        while items.len() > 1 {
+            let i = items.len() - 1;
+            let j = self.below(NonZeroU64::MIN.saturating_add(i as u64)) as usize;
+            items.swap(i, j.min(i));
+            items = &mut items[..i];
+        }
+    }
+
+    /// Fills `out` with distinct indices drawn uniformly from `0..n`.
+    ///
+    /// Uses Floyd's algorithm, so no buffer of size `n` is needed; the cost
+    /// is quadratic in `out.len()`, which suits committee-sized samples.
+    /// Fails without consuming output if more indices are requested than
+    /// `n` provides.
+    pub fn sample_indices(&mut self, n: u64, out: &mut [u64]) -> Result<(), Error> {
+        let k = out.len() as u64;
+        if k > n {
+            return Err(Error::LengthOutOfRange {
+                min: 0,
+                max: n as usize,
+                actual: out.len(),
+            });
+        }
+        for filled in 0..out.len() {
+            let j = n - k + filled as u64;
+            let t = self.below(NonZeroU64::MIN.saturating_add(j));
+            out[filled] = if out[..filled].contains(&t) { j } else { t };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed() -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        for (i, b) in seed.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        seed
+    }
+
+    #[test]
+    fn test_stream_vector() {
+        // First two blocks, computed with the PQClean SPHINCS+ haraka512
+        // loaded with the tweaked round constants.
+        let expect: [u8; 64] = [
+            0x11, 0x08, 0x37, 0xb5, 0xf7, 0x4f, 0xd6, 0xcd, 0x19, 0xfb, 0x86, 0xd7, 0x76, 0x2d,
+            0xcc, 0xab, 0x44, 0xf5, 0x2d, 0xf7, 0x45, 0x26, 0x64, 0x85, 0x13, 0xde, 0x77, 0xf7,
+            0xde, 0xa5, 0xdc, 0x73, 0xac, 0x1d, 0x22, 0xbd, 0xe5, 0x55, 0x1e, 0x9f, 0x7b, 0x64,
+            0xb2, 0x89, 0xbb, 0x9e, 0xb8, 0xe3, 0x35, 0x90, 0x21, 0x29, 0x28, 0x12, 0xae, 0x7e,
+            0x99, 0x07, 0x7d, 0xc1, 0xa2, 0x23, 0x27, 0xd7,
+        ];
+        let mut out = [0u8; 64];
+        HarakaDrbg::new(&seed()).fill_bytes(&mut out);
+        assert_eq!(&out as &[u8], &expect as &[u8]);
+    }
+
+    #[test]
+    fn test_split_reads() {
+        let mut expect = [0u8; 100];
+        HarakaDrbg::new(&seed()).fill_bytes(&mut expect);
+        let mut drbg = HarakaDrbg::new(&seed());
+        let mut out = [0u8; 100];
+        let (a, rest) = out.split_at_mut(5);
+        let (b, c) = rest.split_at_mut(40);
+        drbg.fill_bytes(a);
+        drbg.fill_bytes(b);
+        drbg.fill_bytes(c);
+        assert_eq!(&out as &[u8], &expect as &[u8]);
+    }
+
+    #[test]
+    fn test_gen_below_in_range() {
+        let mut drbg = HarakaDrbg::new(&seed());
+        let mut seen = [false; 10];
+        for _ in 0..200 {
+            let x = drbg.gen_below(10);
+            assert!(x < 10);
+            seen[x as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(drbg.gen_below(1), 0);
+        assert!(drbg.gen_below(u64::MAX) < u64::MAX);
+    }
+
+    #[test]
+    fn test_shuffle_is_permutation() {
+        let mut items = [0u32; 50];
+        for (i, x) in items.iter_mut().enumerate() {
+            *x = i as u32;
+        }
+        HarakaDrbg::new(&seed()).shuffle(&mut items);
+        let mut sorted = items;
+        sorted.sort_unstable();
+        for (i, x) in sorted.iter().enumerate() {
+            assert_eq!(*x, i as u32);
+        }
+        assert_ne!(sorted, items);
+
+        let mut again = sorted;
+        HarakaDrbg::new(&seed()).shuffle(&mut again);
+        assert_eq!(again, items);
+    }
+
+    #[test]
+    fn test_sample_indices_distinct() {
+        let mut out = [0u64; 20];
+        HarakaDrbg::new(&seed())
+            .sample_indices(100, &mut out)
+            .unwrap();
+        for (i, x) in out.iter().enumerate() {
+            assert!(*x < 100);
+            assert!(!out[..i].contains(x));
+        }
+    }
+
+    #[test]
+    fn test_sample_indices_all() {
+        let mut out = [0u64; 8];
+        HarakaDrbg::new(&seed())
+            .sample_indices(8, &mut out)
+            .unwrap();
+        out.sort_unstable();
+        assert_eq!(out, [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_sample_indices_too_many() {
+        let mut out = [0u64; 4];
+        assert_eq!(
+            HarakaDrbg::new(&seed()).sample_indices(3, &mut out),
+            Err(Error::LengthOutOfRange {
+                min: 0,
+                max: 3,
+                actual: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_ratchet_construction() {
+        let mut drbg = HarakaDrbg::new_ratcheting(&seed());
+        let mut first = [0u8; 40];
+        drbg.fill_bytes(&mut first);
+
+        // Blocks 0 and 1 under the seed, then the key hashed forward at
+        // counter 2.
+        let mut expect = [0u8; 40];
+        HarakaDrbg::new(&seed()).fill_bytes(&mut expect);
+        assert_eq!(first, expect);
+        let mut input = [0u8; 64];
+        input[..32].copy_from_slice(&seed());
+        input[32] = 2;
+        input[40] = RATCHET;
+        let mut key = [0u8; 32];
+        haraka512_domain::<ROUNDS, _>(&mut key, DRBG_TAG, &input);
+        assert_eq!(drbg.key, key);
+
+        let mut next = [0u8; 32];
+        drbg.fill_bytes(&mut next);
+        let mut resumed = HarakaDrbg::new(&key);
+        resumed.counter = 2;
+        assert_eq!(next, resumed.block(0));
+    }
+
+    #[test]
+    fn test_ratchet_protects_earlier_output() {
+        let mut drbg = HarakaDrbg::new_ratcheting(&seed());
+        let mut earlier = [0u8; 64];
+        drbg.fill_bytes(&mut earlier[..5]);
+        drbg.fill_bytes(&mut earlier[5..]);
+
+        // An attacker who captures the state holds neither the seed nor
+        // the unread rest of the last block, and running it forward never
+        // meets the earlier output.
+        let captured = drbg.clone();
+        assert_ne!(captured.key, seed());
+        assert_eq!((captured.block, captured.pos), ([0u8; 32], 32));
+        let mut later = [0u8; 256];
+        captured.clone().fill_bytes(&mut later);
+        assert!(!later.windows(8).any(|w| earlier.windows(8).any(|e| e == w)));
+
+        // What follows is still reproducible from the captured state.
+        let mut a = [0u8; 48];
+        let mut b = [0u8; 48];
+        drbg.fill_bytes(&mut a);
+        captured.clone().fill_bytes(&mut b);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_explicit_ratchet() {
+        let mut plain = HarakaDrbg::new(&seed());
+        let mut ratcheted = HarakaDrbg::new(&seed());
+        let mut a = [0u8; 10];
+        let mut b = [0u8; 10];
+        plain.fill_bytes(&mut a);
+        ratcheted.fill_bytes(&mut b);
+        assert_eq!(a, b);
+
+        ratcheted.ratchet();
+        plain.fill_bytes(&mut a);
+        ratcheted.fill_bytes(&mut b);
+        assert_ne!(a, b);
+        // The ratchet skips the unread 22 bytes of the first block.
+        let mut stream = [0u8; 64];
+        HarakaDrbg::new(&seed()).fill_bytes(&mut stream);
+        assert!(!stream.windows(10).any(|w| w == b));
+    }
+
+    #[test]
+    fn test_reseed_recovers_from_compromise() {
+        let mut drbg = HarakaDrbg::new(&seed());
+        drbg.next_u64();
+        let captured = drbg.clone();
+
+        drbg.reseed(b"fresh entropy");
+        let mut guessed = captured.clone();
+        guessed.reseed(b"other entropy");
+        let mut without = captured.clone();
+        let (mut a, mut b, mut c) = ([0u8; 32], [0u8; 32], [0u8; 32]);
+        drbg.fill_bytes(&mut a);
+        guessed.fill_bytes(&mut b);
+        without.fill_bytes(&mut c);
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+
+        // With the entropy, the state replays deterministically.
+        let mut replayed = captured;
+        replayed.reseed(b"fresh entropy");
+        replayed.fill_bytes(&mut b);
+        assert_eq!(a, b);
+        assert_eq!(drbg.counter, 1);
+    }
+
+    #[test]
+    fn test_reseed_encoding() {
+        let mut drbg = HarakaDrbg::new(&seed());
+        drbg.reseed(b"entropy");
+        let mut input = [0u8; 32 + 32 + 8 + 7];
+        input[..32].copy_from_slice(RESEED_DOMAIN);
+        input[32..64].copy_from_slice(&seed());
+        input[64] = 7;
+        input[72..].copy_from_slice(b"entropy");
+        let mut key = [0u8; 32];
+        crate::sponge::haraka_s(&mut key, &input);
+        let mut a = [0u8; 32];
+        drbg.fill_bytes(&mut a);
+        let mut b = [0u8; 32];
+        HarakaDrbg::new(&key).fill_bytes(&mut b);
+        assert_eq!(a, b);
+
+        // Empty and split entropy are distinct reseeds.
+        let mut once = HarakaDrbg::new(&seed());
+        once.reseed(b"entropy");
+        let mut twice = HarakaDrbg::new(&seed());
+        twice.reseed(b"entr");
+        twice.reseed(b"opy");
+        let mut empty = HarakaDrbg::new(&seed());
+        empty.reseed(b"");
+        assert_eq!(once.key, key);
+        assert_ne!(twice.key, key);
+        assert_ne!(empty.key, seed());
+    }
+
+    #[test]
+    fn test_derive_randomness_binds_inputs() {
+        let r = derive_randomness(&seed(), &[b"lottery", b"7"]);
+        assert_ne!(derive_randomness(&[0; 32], &[b"lottery", b"7"]), r);
+        assert_ne!(derive_randomness(&seed(), &[b"lottery", b"8"]), r);
+        assert_ne!(derive_randomness(&seed(), &[b"lottery7"]), r);
+        assert_ne!(derive_randomness(&seed(), &[b"lotter", b"y7"]), r);
+    }
+}
@@ -0,0 +1,197 @@
+//! Edge-case test corpus.
+//!
+//! The expected digests below were computed with the PQClean SPHINCS+
+//! Haraka code (5 rounds), not with this crate. Single-bit inputs set bit
+//! `i % 8` of byte `i / 8`; the chosen bits sit on either side of every
+//! 128-bit lane boundary, so a swapped or misaligned lane changes the
+//! result. The keyed variant feeds forward the state after the key XOR, so
+//! it equals unkeyed Haraka-512 of `state ^ key`: with `key == state` every
+//! input gives the digest of the all-zero block.
+
+use crate::haraka256::haraka256;
+use crate::haraka512::haraka512;
+use crate::haraka512_keyed::haraka512_keyed;
+use crate::Digest;
+
+fn digest(hex: &str) -> Digest {
+    Digest::decode_hex(hex).unwrap()
+}
+
+fn bit<const N: usize>(i: usize) -> [u8; N] {
+    let mut out = [0u8; N];
+    out[i / 8] = 1 << (i % 8);
+    out
+}
+
+fn sequential<const N: usize>() -> [u8; N] {
+    core::array::from_fn(|i| i as u8)
+}
+
+fn complement<const N: usize>(src: &[u8; N]) -> [u8; N] {
+    src.map(|b| !b)
+}
+
+fn h256(src: &[u8; 32]) -> Digest {
+    let mut dst = [0u8; 32];
+    haraka256::<5>(&mut dst, src);
+    Digest::new(dst)
+}
+
+fn h512(src: &[u8; 64]) -> Digest {
+    let mut dst = [0u8; 32];
+    haraka512::<5>(&mut dst, src);
+    Digest::new(dst)
+}
+
+fn h512_keyed(src: &[u8; 64], key: &[u8; 64]) -> Digest {
+    let mut dst = [0u8; 32];
+    haraka512_keyed::<5>(&mut dst, src, key);
+    Digest::new(dst)
+}
+
+const H256_BITS: [(usize, &str); 4] = [
+    (
+        0,
+        "e5a6e3e21aed2b6c06eeb3e7b12cf445d3c69ae2d962304b458ac3d1f50a0eb3",
+    ),
+    (
+        127,
+        "b67e065130dc74d9af3556a122c6f0c44a8212deacea08592e59eee94677c5e5",
+    ),
+    (
+        128,
+        "92250349f2ffeca5d0568e2e065a97433875ac4dcbe3cc576934170cf43cf87b",
+    ),
+    (
+        255,
+        "1bb733e19c7e74996cde0ad1ec78896334bef50b4ee36adc7110c41725feca9e",
+    ),
+];
+
+const H512_BITS: [(usize, &str); 8] = [
+    (
+        0,
+        "c99229c97e5a4b747198b507a4b2bee7cc6ab78a0503cadc7b7bdc6dbdc1aacd",
+    ),
+    (
+        127,
+        "93ed7e3817c492a606f946de88a07a0be82f0633db2ab5d736db76ac0a890d90",
+    ),
+    (
+        128,
+        "5d354f87128b9aa7cf8d85e68bb4ede526700f01b09da66b5dff0c2660d1154e",
+    ),
+    (
+        255,
+        "824e8660baf06cf79bbbf09e8ad4e66f69e2a55daf813635d94fddd56258132b",
+    ),
+    (
+        256,
+        "56f24bba13a7189663b10aed0296377d7d3c508c90a556de9a3ab53790350ce5",
+    ),
+    (
+        383,
+        "c99564bc5856bd85239237b298652c3da69241074cc4612a153206ddb9b0dc4e",
+    ),
+    (
+        384,
+        "1ab4dce55bbccd83a6da9ed2e632fcbb79456aa699e516f4817c48ce9ecb394f",
+    ),
+    (
+        511,
+        "d39c37a9ee7add89622dfe0f760da1f3abd8003b7c88c47d9d7a07c55ba904b0",
+    ),
+];
+
+#[test]
+fn test_uniform_inputs() {
+    assert_eq!(
+        h256(&[0; 32]),
+        digest("583066c7dd645eee22980f3c35971b702973d03a029eb246eb44eceb4a4f5863")
+    );
+    assert_eq!(
+        h256(&[0xff; 32]),
+        digest("ba0462889bf07f6206fafa23c26246b493a01dd87afd6392e4f07427f326998b")
+    );
+    assert_eq!(
+        h512(&[0; 64]),
+        digest("6165454b61dae9b53d086b1a01d6764a911b2a4707cd23640ab148b3db65caf3")
+    );
+    assert_eq!(
+        h512(&[0xff; 64]),
+        digest("ce3d242e6c0b0d1a3e5bb6bf47c7eea17e7cd140f7b7288413b9b41074a1a2b4")
+    );
+}
+
+#[test]
+fn test_single_bit_inputs() {
+    for (i, expect) in H256_BITS {
+        assert_eq!(h256(&bit(i)), digest(expect), "haraka256 bit {i}");
+    }
+    for (i, expect) in H512_BITS {
+        assert_eq!(h512(&bit(i)), digest(expect), "haraka512 bit {i}");
+    }
+}
+
+#[test]
+fn test_single_bit_inputs_are_distinct() {
+    let mut seen = [Digest::default(); 512];
+    for (i, slot) in seen.iter_mut().enumerate() {
+        *slot = h512(&bit(i));
+    }
+    seen.sort_unstable();
+    assert!(seen.windows(2).all(|w| w[0] != w[1]));
+
+    let mut seen = [Digest::default(); 256];
+    for (i, slot) in seen.iter_mut().enumerate() {
+        *slot = h256(&bit(i));
+    }
+    seen.sort_unstable();
+    assert!(seen.windows(2).all(|w| w[0] != w[1]));
+}
+
+#[test]
+fn test_complement_pairs() {
+    let seq32 = sequential::<32>();
+    assert_eq!(
+        h256(&seq32),
+        digest("8027ccb87949774b78d0545fb72bf70c695c2a0923cbd47bba1159efbf2b2c1c")
+    );
+    assert_eq!(
+        h256(&complement(&seq32)),
+        digest("40e3ca11a1762ab8c4c3cf5d55b88c2bf90f5481894071265cdbab7082b8371e")
+    );
+    let seq64 = sequential::<64>();
+    assert_eq!(
+        h512(&seq64),
+        digest("be7f723b4e80a99813b292287f306f625a6d57331cae5f34dd9277b0945be2aa")
+    );
+    assert_eq!(
+        h512(&complement(&seq64)),
+        digest("c693f999b3267b41fa709124c7d31fd9890824443f60f9bac9881f75e2c3d849")
+    );
+}
+
+#[test]
+fn test_keyed_key_equals_state() {
+    let zero = digest("6165454b61dae9b53d086b1a01d6764a911b2a4707cd23640ab148b3db65caf3");
+    let seq = sequential::<64>();
+    assert_eq!(h512_keyed(&seq, &seq), zero);
+    assert_eq!(h512_keyed(&[0xff; 64], &[0xff; 64]), zero);
+    for (i, _) in H512_BITS {
+        assert_eq!(h512_keyed(&bit(i), &bit(i)), zero, "bit {i}");
+    }
+}
+
+#[test]
+fn test_keyed_key_complements_state() {
+    // state ^ key is all ones.
+    let seq = sequential::<64>();
+    assert_eq!(
+        h512_keyed(&seq, &complement(&seq)),
+        digest("ce3d242e6c0b0d1a3e5bb6bf47c7eea17e7cd140f7b7288413b9b41074a1a2b4")
+    );
+    for (i, expect) in H512_BITS {
+        assert_eq!(h512_keyed(&[0; 64], &bit(i)), digest(expect), "bit {i}");
+    }
+}
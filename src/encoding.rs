@@ -0,0 +1,194 @@
+//! Hex and Base64 text encodings that work on caller-provided buffers.
+
+use crate::error::Error;
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// Writes the lowercase hex encoding of `bytes` into `out`, which must be
+/// twice as long, and returns it as a string.
+pub(crate) fn encode_hex<'a>(bytes: &[u8], out: &'a mut [u8]) -> &'a str {
+    debug_assert_eq!(out.len(), 2 * bytes.len());
+    for (pair, &b) in out.chunks_exact_mut(2).zip(bytes) {
+        pair[0] = HEX[(b >> 4) as usize];
+        pair[1] = HEX[(b & 0xf) as usize];
+    }
+    // Only ASCII digits and letters were written.
+    core::str::from_utf8(out).unwrap()
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decodes hex text (either case) that encodes exactly `out.len()` bytes.
+pub(crate) fn decode_hex(text: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    if text.len() != 2 * out.len() {
+        return Err(Error::InvalidLength {
+            expected: 2 * out.len(),
+            actual: text.len(),
+        });
+    }
+    for (i, (pair, b)) in text.chunks_exact(2).zip(out.iter_mut()).enumerate() {
+        let hi = hex_digit(pair[0]).ok_or(Error::InvalidCharacter { index: 2 * i })?;
+        let lo = hex_digit(pair[1]).ok_or(Error::InvalidCharacter { index: 2 * i + 1 })?;
+        *b = (hi << 4) | lo;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "base64")]
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Length of the padded Base64 encoding of `n` bytes.
+#[cfg(feature = "base64")]
+pub(crate) const fn base64_len(n: usize) -> usize {
+    n.div_ceil(3) * 4
+}
+
+/// Writes the padded standard Base64 encoding of `bytes` into `out`, which
+/// must be `base64_len(bytes.len())` long, and returns it as a string.
+#[cfg(feature = "base64")]
+pub(crate) fn encode_base64<'a>(bytes: &[u8], out: &'a mut [u8]) -> &'a str {
+    debug_assert_eq!(out.len(), base64_len(bytes.len()));
+    for (quad, group) in out.chunks_exact_mut(4).zip(bytes.chunks(3)) {
+        let b = [
+            group[0],
+            group.get(1).copied().unwrap_or(0),
+            group.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for (j, c) in quad.iter_mut().enumerate() {
+            *c = if j <= group.len() {
+                BASE64[(n >> (18 - 6 * j) & 0x3f) as usize]
+            } else {
+                b'='
+            };
+        }
+    }
+    // Only alphabet characters and padding were written.
+    core::str::from_utf8(out).unwrap()
+}
+
+#[cfg(feature = "base64")]
+fn base64_digit(c: u8) -> Option<u32> {
+    match c {
+        b'A'..=b'Z' => Some((c - b'A') as u32),
+        b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+        b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes padded standard Base64 that encodes exactly `out.len()` bytes.
+///
+/// Only the canonical encoding is accepted: the padding must match and the
+/// unused bits of the last character must be zero.
+#[cfg(feature = "base64")]
+pub(crate) fn decode_base64(text: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    if text.len() != base64_len(out.len()) {
+        return Err(Error::InvalidLength {
+            expected: base64_len(out.len()),
+            actual: text.len(),
+        });
+    }
+    for (i, (quad, group)) in text.chunks_exact(4).zip(out.chunks_mut(3)).enumerate() {
+        let mut n = 0u32;
+        for (j, &c) in quad.iter().enumerate() {
+            let index = 4 * i + j;
+            let digit = if j <= group.len() {
+                base64_digit(c)
+            } else {
+                (c == b'=').then_some(0)
+            };
+            n = n << 6 | digit.ok_or(Error::InvalidCharacter { index })?;
+        }
+        let b = n.to_be_bytes();
+        group.copy_from_slice(&b[1..1 + group.len()]);
+        // Bits below the decoded bytes must be zero in a canonical encoding.
+        if n & (0xffffff >> (8 * group.len())) != 0 {
+            return Err(Error::InvalidCharacter {
+                index: 4 * i + group.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let bytes = [0x00, 0x7f, 0xa5, 0xff];
+        let mut text = [0u8; 8];
+        assert_eq!(encode_hex(&bytes, &mut text), "007fa5ff");
+        let mut out = [0u8; 4];
+        decode_hex(b"007FA5ff", &mut out).unwrap();
+        assert_eq!(out, bytes);
+    }
+
+    #[test]
+    fn test_hex_errors() {
+        let mut out = [0u8; 2];
+        assert_eq!(
+            decode_hex(b"abc", &mut out),
+            Err(Error::InvalidLength {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            decode_hex(b"ab0g", &mut out),
+            Err(Error::InvalidCharacter { index: 3 })
+        );
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_base64_vectors() {
+        // RFC 4648, section 10.
+        for (bytes, text) in [
+            (&b"f"[..], "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"fooba", "Zm9vYmE="),
+            (b"foobar", "Zm9vYmFy"),
+        ] {
+            let mut buf = [0u8; 8];
+            let buf = &mut buf[..base64_len(bytes.len())];
+            assert_eq!(encode_base64(bytes, buf), text);
+            let mut out = [0u8; 6];
+            let out = &mut out[..bytes.len()];
+            decode_base64(text.as_bytes(), out).unwrap();
+            assert_eq!(out, bytes);
+        }
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_base64_rejects_non_canonical() {
+        let mut out = [0u8; 1];
+        assert_eq!(
+            decode_base64(b"Zh==", &mut out),
+            Err(Error::InvalidCharacter { index: 1 })
+        );
+        assert_eq!(
+            decode_base64(b"Zg=A", &mut out),
+            Err(Error::InvalidCharacter { index: 3 })
+        );
+        let mut out = [0u8; 3];
+        assert_eq!(
+            decode_base64(b"Zm9*", &mut out),
+            Err(Error::InvalidCharacter { index: 3 })
+        );
+    }
+}
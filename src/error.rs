@@ -0,0 +1,45 @@
+use core::fmt;
+
+/// Errors returned by the slice-based APIs of this crate.
+///
+/// The fixed-size array APIs cannot fail; these errors only arise when
+/// buffers are described by run-time lengths, offsets or strides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// An input does not have the exact length the operation requires.
+    InvalidLength { expected: usize, actual: usize },
+    /// An input length lies outside the range the operation accepts.
+    LengthOutOfRange {
+        min: usize,
+        max: usize,
+        actual: usize,
+    },
+    /// A buffer is shorter than the operation requires.
+    BufferTooSmall { required: usize, actual: usize },
+    /// A stride is smaller than the element it separates.
+    InvalidStride { stride: usize, min: usize },
+    /// Encoded text contains a character that is not valid at `index`.
+    InvalidCharacter { index: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength { expected, actual } => {
+                write!(f, "invalid length: {actual} bytes, expected {expected}")
+            }
+            Error::LengthOutOfRange { min, max, actual } => {
+                write!(f, "invalid length: {actual} bytes, expected {min} to {max}")
+            }
+            Error::BufferTooSmall { required, actual } => {
+                write!(f, "buffer too small: {actual} bytes, {required} required")
+            }
+            Error::InvalidStride { stride, min } => {
+                write!(f, "invalid stride: {stride} bytes, at least {min} required")
+            }
+            Error::InvalidCharacter { index } => {
+                write!(f, "invalid character at index {index}")
+            }
+        }
+    }
+}
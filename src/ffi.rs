@@ -0,0 +1,221 @@
+//! C ABI exports of the 5-round Haraka functions.
+//!
+//! The functions take raw pointers to fixed-size buffers and return
+//! [`HARAKA_OK`] on success or [`HARAKA_ERR_NULL`] if any pointer is null.
+//! Buffers may alias; inputs are fully read before the output is written.
+//!
+//! The same functions are also exported under the names of the Haraka
+//! reference implementation (`haraka256`, `haraka512`, `haraka512_perm`),
+//! with its `void` signatures, so existing C test harnesses can link
+//! against this crate unchanged.
+//!
+//! Linking from C or C++ goes through the `haraka-bpf-ffi` crate, which
+//! builds this module as a `cdylib`/`staticlib` and ships the generated
+//! header.
+
+use crate::params::DEFAULT_ROUNDS;
+use core::ffi::c_int;
+
+/// The call succeeded and the digest was written.
+pub const HARAKA_OK: c_int = 0;
+/// A pointer argument was null; nothing was written.
+pub const HARAKA_ERR_NULL: c_int = -1;
+
+/// Haraka-256 with 5 rounds: reads 32 bytes from `input`, writes 32 bytes
+/// to `out`.
+///
+/// # Safety
+///
+/// Unless null, `input` must be valid for reads of 32 bytes and `out` valid
+/// for writes of 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn haraka_bpf_haraka256(out: *mut u8, input: *const u8) -> c_int {
+    if out.is_null() || input.is_null() {
+        return HARAKA_ERR_NULL;
+    }
+    let src = input.cast::<[u8; 32]>().read_unaligned();
+    let mut dst = [0u8; 32];
+    crate::haraka256::haraka256::<DEFAULT_ROUNDS>(&mut dst, &src);
+    out.cast::<[u8; 32]>().write_unaligned(dst);
+    HARAKA_OK
+}
+
+/// Haraka-512 with 5 rounds: reads 64 bytes from `input`, writes 32 bytes
+/// to `out`.
+///
+/// # Safety
+///
+/// Unless null, `input` must be valid for reads of 64 bytes and `out` valid
+/// for writes of 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn haraka_bpf_haraka512(out: *mut u8, input: *const u8) -> c_int {
+    if out.is_null() || input.is_null() {
+        return HARAKA_ERR_NULL;
+    }
+    let src = input.cast::<[u8; 64]>().read_unaligned();
+    let mut dst = [0u8; 32];
+    crate::haraka512::haraka512::<DEFAULT_ROUNDS>(&mut dst, &src);
+    out.cast::<[u8; 32]>().write_unaligned(dst);
+    HARAKA_OK
+}
+
+/// Keyed Haraka-512 with 5 rounds: reads 64 bytes from each of `state` and
+/// `key`, writes 32 bytes to `out`.
+///
+/// # Safety
+///
+/// Unless null, `state` and `key` must be valid for reads of 64 bytes and
+/// `out` valid for writes of 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn haraka_bpf_haraka512_keyed(
+    out: *mut u8,
+    state: *const u8,
+    key: *const u8,
+) -> c_int {
+    if out.is_null() || state.is_null() || key.is_null() {
+        return HARAKA_ERR_NULL;
+    }
+    let state = state.cast::<[u8; 64]>().read_unaligned();
+    let key = key.cast::<[u8; 64]>().read_unaligned();
+    let mut dst = [0u8; 32];
+    crate::haraka512_keyed::haraka512_keyed::<DEFAULT_ROUNDS>(&mut dst, &state, &key);
+    out.cast::<[u8; 32]>().write_unaligned(dst);
+    HARAKA_OK
+}
+
+/// Reference-compatible Haraka-256 (5 rounds).
+///
+/// Does nothing if a pointer is null.
+///
+/// # Safety
+///
+/// See [`haraka_bpf_haraka256`].
+#[no_mangle]
+pub unsafe extern "C" fn haraka256(out: *mut u8, input: *const u8) {
+    haraka_bpf_haraka256(out, input);
+}
+
+/// Reference-compatible Haraka-512 (5 rounds).
+///
+/// Does nothing if a pointer is null.
+///
+/// # Safety
+///
+/// See [`haraka_bpf_haraka512`].
+#[no_mangle]
+pub unsafe extern "C" fn haraka512(out: *mut u8, input: *const u8) {
+    haraka_bpf_haraka512(out, input);
+}
+
+/// Reference-compatible Haraka-512 permutation (5 rounds): reads 64 bytes
+/// from `input`, writes the 64-byte permuted state to `out`.
+///
+/// Does nothing if a pointer is null.
+///
+/// # Safety
+///
+/// Unless null, `input` must be valid for reads of 64 bytes and `out` valid
+/// for writes of 64 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn haraka512_perm(out: *mut u8, input: *const u8) {
+    if out.is_null() || input.is_null() {
+        return;
+    }
+    let src = input.cast::<[u8; 64]>().read_unaligned();
+    let mut dst = [0u8; 64];
+    crate::haraka512::haraka512_perm::<DEFAULT_ROUNDS>(&mut dst, &src);
+    out.cast::<[u8; 64]>().write_unaligned(dst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrayref::array_ref;
+    use core::ptr;
+
+    #[test]
+    fn test_ffi_haraka256() {
+        let src = [0x21u8; 32];
+        let mut expect = [0u8; 32];
+        crate::haraka256::haraka256::<DEFAULT_ROUNDS>(&mut expect, &src);
+
+        let mut out = [0u8; 32];
+        let rc = unsafe { haraka_bpf_haraka256(out.as_mut_ptr(), src.as_ptr()) };
+        assert_eq!(rc, HARAKA_OK);
+        assert_eq!(out, expect);
+    }
+
+    #[test]
+    fn test_ffi_haraka512_in_place() {
+        // The output may alias the input.
+        let mut buf = [0u8; 64];
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut expect = [0u8; 32];
+        crate::haraka512::haraka512::<DEFAULT_ROUNDS>(&mut expect, &buf);
+
+        let rc = unsafe { haraka_bpf_haraka512(buf.as_mut_ptr(), buf.as_ptr()) };
+        assert_eq!(rc, HARAKA_OK);
+        assert_eq!(&buf[..32], &expect);
+    }
+
+    #[test]
+    fn test_ffi_haraka512_keyed() {
+        let state = [0x11u8; 64];
+        let key = [0x22u8; 64];
+        let mut expect = [0u8; 32];
+        crate::haraka512_keyed::haraka512_keyed::<DEFAULT_ROUNDS>(&mut expect, &state, &key);
+
+        let mut out = [0u8; 32];
+        let rc =
+            unsafe { haraka_bpf_haraka512_keyed(out.as_mut_ptr(), state.as_ptr(), key.as_ptr()) };
+        assert_eq!(rc, HARAKA_OK);
+        assert_eq!(out, expect);
+    }
+
+    #[test]
+    fn test_ffi_null_pointers() {
+        let buf = [0u8; 64];
+        let mut out = [0xaau8; 32];
+        unsafe {
+            assert_eq!(
+                haraka_bpf_haraka256(ptr::null_mut(), buf.as_ptr()),
+                HARAKA_ERR_NULL
+            );
+            assert_eq!(
+                haraka_bpf_haraka512(out.as_mut_ptr(), ptr::null()),
+                HARAKA_ERR_NULL
+            );
+            assert_eq!(
+                haraka_bpf_haraka512_keyed(out.as_mut_ptr(), buf.as_ptr(), ptr::null()),
+                HARAKA_ERR_NULL
+            );
+        }
+        assert_eq!(out, [0xaau8; 32]);
+    }
+
+    #[test]
+    fn test_ffi_reference_names() {
+        let src = [0x5au8; 64];
+        let mut expect = [0u8; 32];
+        let mut out = [0u8; 32];
+
+        crate::haraka256::haraka256::<DEFAULT_ROUNDS>(&mut expect, array_ref![src, 0, 32]);
+        unsafe { haraka256(out.as_mut_ptr(), src.as_ptr()) };
+        assert_eq!(out, expect);
+
+        crate::haraka512::haraka512::<DEFAULT_ROUNDS>(&mut expect, &src);
+        unsafe { haraka512(out.as_mut_ptr(), src.as_ptr()) };
+        assert_eq!(out, expect);
+
+        let mut perm_expect = [0u8; 64];
+        let mut perm_out = [0u8; 64];
+        crate::haraka512::haraka512_perm::<DEFAULT_ROUNDS>(&mut perm_expect, &src);
+        unsafe { haraka512_perm(perm_out.as_mut_ptr(), src.as_ptr()) };
+        assert_eq!(perm_out, perm_expect);
+
+        // Null pointers are ignored.
+        unsafe { haraka512_perm(ptr::null_mut(), src.as_ptr()) };
+    }
+}
@@ -0,0 +1,182 @@
+//! Buffer layout and reference outputs for GPU reimplementations.
+//!
+//! Off-chain miners and verifiers often port Haraka to CUDA or OpenCL. To
+//! guarantee that such ports agree byte-for-byte with the on-chain code,
+//! this module pins down:
+//!
+//! - the packed buffer layout: message `i` occupies bytes
+//!   `[i * MESSAGE_LEN, (i + 1) * MESSAGE_LEN)` of the input buffer and its
+//!   digest bytes `[i * DIGEST_LEN, (i + 1) * DIGEST_LEN)` of the output
+//!   buffer, with no padding in between (the layout of
+//!   [`haraka512_batch`](crate::haraka512_batch));
+//! - a message generator that is trivial to reimplement in a kernel: the
+//!   `j`-th little-endian 64-bit word of message `i` is `8 * i + j`;
+//! - reference 5-round Haraka-512 digests for a batch of
+//!   [`REFERENCE_BATCH_LEN`] such messages, as a few sampled digests plus
+//!   the XOR of all digests.
+
+/// Size in bytes of one packed input message.
+pub const MESSAGE_LEN: usize = 64;
+
+/// Size in bytes of one packed output digest.
+pub const DIGEST_LEN: usize = 32;
+
+/// Number of messages in the reference batch, starting at index 0.
+pub const REFERENCE_BATCH_LEN: u64 = 1 << 14;
+
+/// Sampled `(index, digest)` pairs of the reference batch (5 rounds).
+pub const REFERENCE_SAMPLES: [(u64, [u8; 32]); 7] = [
+    (
+        0,
+        [
+            0x88, 0xb2, 0x7a, 0x4a, 0xc6, 0x2a, 0xe8, 0xf7, 0x22, 0xda, 0x05, 0xff, 0xe2, 0x05,
+            0x7c, 0x00, 0xed, 0xf3, 0x32, 0xa5, 0xe6, 0xe2, 0xfc, 0xb9, 0xe8, 0x1d, 0x07, 0xf1,
+            0xbb, 0xd8, 0xc1, 0xe9,
+        ],
+    ),
+    (
+        1,
+        [
+            0x2c, 0x60, 0xa3, 0xeb, 0x6c, 0x4c, 0x9c, 0xc4, 0x49, 0x04, 0xd6, 0xae, 0x97, 0x68,
+            0x1d, 0x64, 0x68, 0x75, 0x33, 0xd8, 0x4b, 0x09, 0xae, 0xa0, 0xd1, 0x0c, 0x91, 0x2b,
+            0x4b, 0xcd, 0x78, 0x4d,
+        ],
+    ),
+    (
+        2,
+        [
+            0x52, 0x8c, 0x55, 0x25, 0xe2, 0x98, 0x2f, 0x0c, 0xe6, 0x52, 0xb3, 0x0b, 0x3f, 0x9c,
+            0xf0, 0x0d, 0x45, 0x39, 0x93, 0xc2, 0xcb, 0xc3, 0xdc, 0x09, 0x0c, 0xf4, 0xca, 0x61,
+            0xeb, 0x88, 0xc7, 0xd7,
+        ],
+    ),
+    (
+        255,
+        [
+            0x25, 0xf5, 0x85, 0x88, 0xa9, 0xe1, 0xa4, 0xc9, 0xa6, 0xc0, 0x7f, 0x08, 0x5f, 0x74,
+            0xbd, 0xb5, 0x3a, 0x56, 0xe9, 0x3a, 0x1c, 0xe0, 0xa0, 0x2b, 0x47, 0x2c, 0x6a, 0x3b,
+            0xd5, 0xbd, 0x9f, 0x9c,
+        ],
+    ),
+    (
+        256,
+        [
+            0x09, 0xd9, 0x4d, 0x50, 0xcd, 0xe4, 0x20, 0x68, 0x6b, 0x6b, 0x7d, 0x52, 0xb4, 0x10,
+            0x0e, 0x8c, 0xf6, 0x63, 0x00, 0xf7, 0x28, 0x38, 0x68, 0xea, 0x65, 0x3e, 0xb0, 0xe1,
+            0xe3, 0x5a, 0x21, 0x6c,
+        ],
+    ),
+    (
+        4095,
+        [
+            0x22, 0xe9, 0x8c, 0x1f, 0x90, 0xfc, 0x54, 0x80, 0xf4, 0xae, 0x60, 0xab, 0x44, 0x3c,
+            0x2b, 0x7d, 0x00, 0x96, 0x6d, 0x26, 0x2c, 0xd9, 0xad, 0xfc, 0x1a, 0xeb, 0x32, 0xdb,
+            0xfe, 0x1e, 0x86, 0x82,
+        ],
+    ),
+    (
+        16383,
+        [
+            0x83, 0x79, 0x0c, 0x3e, 0x3f, 0x0d, 0x58, 0xbd, 0x83, 0x34, 0xf6, 0x49, 0x3c, 0x3c,
+            0x95, 0x50, 0x39, 0xd8, 0x73, 0x19, 0xb3, 0xd5, 0xd8, 0x77, 0xfa, 0x37, 0xbb, 0x1b,
+            0xb9, 0x80, 0x29, 0x3b,
+        ],
+    ),
+];
+
+/// XOR of the 5-round digests of all messages of the reference batch.
+pub const REFERENCE_BATCH_XOR: [u8; 32] = [
+    0x0d, 0xc9, 0x80, 0xab, 0x04, 0x86, 0x03, 0xc2, 0xda, 0x09, 0xd1, 0xcf, 0xdb, 0xe7, 0x41, 0x0d,
+    0xbb, 0x45, 0xb4, 0x03, 0x43, 0x15, 0x41, 0xb9, 0xd4, 0x2a, 0xff, 0x71, 0xba, 0x49, 0xe4, 0xf2,
+];
+
+/// Returns reference message number `index`.
+#[inline(always)]
+pub fn reference_message(index: u64) -> [u8; MESSAGE_LEN] {
+    let mut msg = [0u8; MESSAGE_LEN];
+    for (j, word) in msg.chunks_exact_mut(8).enumerate() {
+        let value = index.wrapping_mul(8).wrapping_add(j as u64);
+        word.copy_from_slice(&value.to_le_bytes());
+    }
+    msg
+}
+
+/// Fills `buf` with consecutive packed reference messages, the first one
+/// being number `start`. A trailing partial message is left untouched.
+pub fn fill_reference_messages(buf: &mut [u8], start: u64) {
+    for (i, msg) in buf.chunks_exact_mut(MESSAGE_LEN).enumerate() {
+        msg.copy_from_slice(&reference_message(start.wrapping_add(i as u64)));
+    }
+}
+
+/// Checks a packed digest buffer, as produced by a reimplementation for the
+/// reference messages `start, start + 1, ...`.
+///
+/// Returns the index of the first message whose digest differs, or `None`
+/// if every digest matches. A trailing partial digest is reported as a
+/// mismatch.
+pub fn first_mismatch<const N_ROUNDS: usize>(digests: &[u8], start: u64) -> Option<u64> {
+    let mut expect = [0u8; DIGEST_LEN];
+    for (i, digest) in digests.chunks(DIGEST_LEN).enumerate() {
+        let index = start.wrapping_add(i as u64);
+        crate::haraka512::haraka512::<N_ROUNDS>(&mut expect, &reference_message(index));
+        if digest != expect {
+            return Some(index);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haraka512::haraka512;
+
+    #[test]
+    fn test_reference_message_layout() {
+        let msg = reference_message(3);
+        for j in 0..8 {
+            let word = u64::from_le_bytes(msg[8 * j..8 * j + 8].try_into().unwrap());
+            assert_eq!(word, 24 + j as u64);
+        }
+    }
+
+    #[test]
+    fn test_reference_samples() {
+        for (index, expect) in REFERENCE_SAMPLES.iter() {
+            let mut digest = [0u8; 32];
+            haraka512::<5>(&mut digest, &reference_message(*index));
+            assert_eq!(&digest, expect, "reference digest {index}");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under Miri")]
+    fn test_reference_batch_xor() {
+        let mut acc = [0u8; 32];
+        let mut digest = [0u8; 32];
+        for i in 0..REFERENCE_BATCH_LEN {
+            haraka512::<5>(&mut digest, &reference_message(i));
+            for (a, b) in acc.iter_mut().zip(digest) {
+                *a ^= b;
+            }
+        }
+        assert_eq!(acc, REFERENCE_BATCH_XOR);
+    }
+
+    #[test]
+    fn test_first_mismatch() {
+        let mut msgs = [0u8; 4 * MESSAGE_LEN];
+        fill_reference_messages(&mut msgs, 254);
+        let mut digests = [0u8; 4 * DIGEST_LEN];
+        crate::batch::haraka512_batch::<5>(&mut digests, &msgs, 4).unwrap();
+
+        assert_eq!(first_mismatch::<5>(&digests, 254), None);
+        // The packed output of reference message 255 is the second digest.
+        assert_eq!(&digests[32..64], &REFERENCE_SAMPLES[3].1);
+
+        digests[2 * DIGEST_LEN + 5] ^= 1;
+        assert_eq!(first_mismatch::<5>(&digests, 254), Some(256));
+        assert_eq!(first_mismatch::<5>(&digests[..40], 254), Some(255));
+    }
+}
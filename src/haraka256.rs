@@ -1,44 +1,162 @@
-use crate::constants;
+use crate::backend::{DefaultBackend, HarakaBackend};
+use crate::constants::{self, RoundConstants};
+use crate::error::Error;
+use crate::haraka512::swap_words;
 use crate::simd128::Simd128;
 use arrayref::{array_mut_ref, array_ref};
 
+/// The two 128-bit lanes of a Haraka-256 state on backend `B`, in message
+/// order.
+pub(crate) type State<B = DefaultBackend> = [<B as HarakaBackend>::Lane; 2];
+
 #[inline(always)]
-fn aes2(s0: &mut Simd128, s1: &mut Simd128, rci: usize) {
-    Simd128::aesenc(s0, &constants::HARAKA_CONSTANTS[rci]);
-    Simd128::aesenc(s1, &constants::HARAKA_CONSTANTS[rci + 1]);
-    Simd128::aesenc(s0, &constants::HARAKA_CONSTANTS[rci + 2]);
-    Simd128::aesenc(s1, &constants::HARAKA_CONSTANTS[rci + 3]);
+fn aes2<B: HarakaBackend>(s: &mut State<B>, rc: &[[u8; 16]; 4]) {
+    B::aesenc_bytes(&mut s[0], &rc[0]);
+    B::aesenc_bytes(&mut s[1], &rc[1]);
+    B::aesenc_bytes(&mut s[0], &rc[2]);
+    B::aesenc_bytes(&mut s[1], &rc[3]);
 }
 
+/// The MIX permutation of [`SoftBackend`](crate::backend::SoftBackend).
 #[inline(always)]
-fn mix2(s0: &mut Simd128, s1: &mut Simd128) {
+pub(crate) fn mix2(s: &mut [Simd128; 2]) {
+    let [s0, s1] = s;
     let mut tmp = *s0;
     Simd128::unpackhi_epi32(&mut tmp, s1);
     Simd128::unpacklo_epi32(s0, s1);
     *s1 = tmp;
 }
 
+// Constant time: no branch or memory index depends on the state; the
+// `ct_check` test verifies this on the compiled code. Out of line with
+// `small-code`, so that every variant shares one copy of the round.
+#[cfg_attr(not(feature = "small-code"), inline(always))]
+#[cfg_attr(feature = "small-code", inline(never))]
+pub(crate) fn aes_mix2<B: HarakaBackend>(s: &mut State<B>, rc: &[[u8; 16]; 4]) {
+    aes2::<B>(s, rc);
+    B::mix2(s);
+}
+
+/// Loads a 32-byte block into the two lanes.
 #[inline(always)]
-fn aes_mix2(s0: &mut Simd128, s1: &mut Simd128, rci: usize) {
-    aes2(s0, s1, rci);
-    mix2(s0, s1);
+pub(crate) fn load2<B: HarakaBackend>(src: &[u8; 32]) -> State<B> {
+    [
+        B::load(array_ref![src, 0, 16]),
+        B::load(array_ref![src, 16, 16]),
+    ]
 }
 
-pub fn haraka256<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 32]) {
-    let mut s0 = Simd128::read(array_ref![src, 0, 16]);
-    let mut s1 = Simd128::read(array_ref![src, 16, 16]);
+/// Stores the two lanes as a 32-byte block.
+#[inline(always)]
+pub(crate) fn store2<B: HarakaBackend>(dst: &mut [u8; 32], s: &State<B>) {
+    B::store(&s[0], array_mut_ref![dst, 0, 16]);
+    B::store(&s[1], array_mut_ref![dst, 16, 16]);
+}
 
-    for i in 0..N_ROUNDS {
-        aes_mix2(&mut s0, &mut s1, 4 * i);
+/// XORs `t` into `s`, lane by lane.
+#[inline(always)]
+pub(crate) fn pxor2<B: HarakaBackend>(s: &mut State<B>, t: &State<B>) {
+    B::xor(&mut s[0], &t[0]);
+    B::xor(&mut s[1], &t[1]);
+}
+
+/// Applies Haraka-256 (rounds and feed-forward) to already loaded lanes,
+/// leaving the untruncated digest in them.
+#[inline(always)]
+pub(crate) fn haraka256_lanes<const N_ROUNDS: usize>(s: &mut State) {
+    haraka256_lanes_with::<N_ROUNDS>(s, constants::HARAKA_CONSTANTS);
+}
+
+/// Like [`haraka256_lanes`], but with the round constants taken from `rc`.
+#[inline(always)]
+pub(crate) fn haraka256_lanes_with<const N_ROUNDS: usize>(s: &mut State, rc: &RoundConstants) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_256,
+            "Haraka-256 supports at most 12 rounds"
+        )
+    };
+    haraka256_lanes_rounds::<DefaultBackend>(s, rc, N_ROUNDS);
+}
+
+/// Like [`haraka256_lanes_with`] on backend `B`, with the number of rounds
+/// passed at run time. Counts above the number of constants in `rc` are
+/// capped.
+#[inline(always)]
+pub(crate) fn haraka256_lanes_rounds<B: HarakaBackend>(
+    s: &mut State<B>,
+    rc: &RoundConstants,
+    rounds: usize,
+) {
+    let t = *s;
+
+    for i in 0..rounds.min(constants::MAX_ROUNDS_256) {
+        aes_mix2::<B>(s, constants::round256(rc, i));
     }
 
-    let t0 = Simd128::read(array_ref![src, 0, 16]);
-    let t1 = Simd128::read(array_ref![src, 16, 16]);
-    Simd128::pxor(&mut s0, &t0);
-    Simd128::pxor(&mut s1, &t1);
+    pxor2::<B>(s, &t);
+}
 
-    s0.write(array_mut_ref![dst, 0, 16]);
-    s1.write(array_mut_ref![dst, 16, 16]);
+pub fn haraka256<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 32]) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_256,
+            "Haraka-256 supports at most 12 rounds"
+        )
+    };
+    haraka256_rounds::<DefaultBackend>(dst, src, N_ROUNDS);
+}
+
+/// The body of [`haraka256`] on backend `B`, shared by all round counts.
+/// With `small-code` it stays out of line, so a program using several round
+/// counts carries one copy instead of one per count.
+#[cfg_attr(not(feature = "small-code"), inline(always))]
+#[cfg_attr(feature = "small-code", inline(never))]
+pub(crate) fn haraka256_rounds<B: HarakaBackend>(
+    dst: &mut [u8; 32],
+    src: &[u8; 32],
+    rounds: usize,
+) {
+    let mut s = load2::<B>(src);
+    haraka256_lanes_rounds::<B>(&mut s, constants::HARAKA_CONSTANTS, rounds);
+    store2::<B>(dst, &s);
+}
+
+/// Computes Haraka-256 and writes the digest to `buf[offset..offset + 32]`.
+///
+/// The bounds are checked once up front; the lanes are then stored straight
+/// into `buf`, without an intermediate digest array.
+pub fn haraka256_into<const N_ROUNDS: usize>(
+    buf: &mut [u8],
+    offset: usize,
+    src: &[u8; 32],
+) -> Result<(), Error> {
+    let dst = digest_slot(buf, offset)?;
+    let mut s = load2::<DefaultBackend>(src);
+    haraka256_lanes::<N_ROUNDS>(&mut s);
+    store2::<DefaultBackend>(dst, &s);
+    Ok(())
+}
+
+/// Returns `buf[offset..offset + 32]`, or the error describing why it does
+/// not exist.
+#[inline(always)]
+pub(crate) fn digest_slot(buf: &mut [u8], offset: usize) -> Result<&mut [u8; 32], Error> {
+    let actual = buf.len();
+    match offset.checked_add(32) {
+        Some(end) if end <= actual => Ok(array_mut_ref![buf, offset, 32]),
+        end => Err(Error::BufferTooSmall {
+            required: end.unwrap_or(usize::MAX),
+            actual,
+        }),
+    }
+}
+
+/// Computes Haraka-256 and stores the digest as four big-endian 64-bit
+/// words.
+pub fn haraka256_be<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 32]) {
+    haraka256::<N_ROUNDS>(dst, src);
+    swap_words(dst);
 }
 
 #[cfg(test)]
@@ -46,11 +164,10 @@ mod tests {
     use super::*;
 
     fn mix2_slice(s0: &mut [u8; 16], s1: &mut [u8; 16]) {
-        let mut s0_xmm = Simd128::read(s0);
-        let mut s1_xmm = Simd128::read(s1);
-        mix2(&mut s0_xmm, &mut s1_xmm);
-        s0_xmm.write(s0);
-        s1_xmm.write(s1);
+        let mut s = [Simd128::read(s0), Simd128::read(s1)];
+        mix2(&mut s);
+        s[0].write(s0);
+        s[1].write(s1);
     }
 
     #[test]
@@ -66,12 +183,13 @@ mod tests {
         assert_eq!(dst1, expect1);
     }
 
-    fn aes2_slice(state: &mut [u8; 32], rci: usize) {
-        let mut s0_xmm = Simd128::read(array_ref![state, 0, 16]);
-        let mut s1_xmm = Simd128::read(array_ref![state, 16, 16]);
-        aes2(&mut s0_xmm, &mut s1_xmm, rci);
-        s0_xmm.write(array_mut_ref![state, 0, 16]);
-        s1_xmm.write(array_mut_ref![state, 16, 16]);
+    fn aes2_slice(state: &mut [u8; 32], round: usize) {
+        let mut s = load2::<DefaultBackend>(state);
+        aes2::<DefaultBackend>(
+            &mut s,
+            constants::round256(constants::HARAKA_CONSTANTS, round),
+        );
+        store2::<DefaultBackend>(state, &s);
     }
 
     #[test]
@@ -91,12 +209,13 @@ mod tests {
         assert_eq!(state, expect);
     }
 
-    fn aes_mix2_slice(state: &mut [u8; 32], rci: usize) {
-        let mut s0_xmm = Simd128::read(array_ref![state, 0, 16]);
-        let mut s1_xmm = Simd128::read(array_ref![state, 16, 16]);
-        aes_mix2(&mut s0_xmm, &mut s1_xmm, rci);
-        s0_xmm.write(array_mut_ref![state, 0, 16]);
-        s1_xmm.write(array_mut_ref![state, 16, 16]);
+    fn aes_mix2_slice(state: &mut [u8; 32], round: usize) {
+        let mut s = load2::<DefaultBackend>(state);
+        aes_mix2::<DefaultBackend>(
+            &mut s,
+            constants::round256(constants::HARAKA_CONSTANTS, round),
+        );
+        store2::<DefaultBackend>(state, &s);
     }
 
     #[test]
@@ -128,10 +247,50 @@ mod tests {
                        \x78\xd0\x54\x5f\xb7\x2b\xf7\x0c\
                        \x69\x5c\x2a\x09\x23\xcb\xd4\x7b\
                        \xba\x11\x59\xef\xbf\x2b\x2c\x1c";
-        haraka256::<5>(&mut dst, &src);
+        haraka256::<5>(&mut dst, src);
         assert_eq!(&dst, expect);
     }
 
+    #[test]
+    fn test_haraka256_into() {
+        let src = [3u8; 32];
+        let mut expect = [0u8; 32];
+        haraka256::<5>(&mut expect, &src);
+
+        let mut buf = [0xaau8; 45];
+        haraka256_into::<5>(&mut buf, 13, &src).unwrap();
+        assert_eq!(&buf[13..], &expect[..]);
+        assert!(buf[..13].iter().all(|&b| b == 0xaa));
+
+        assert_eq!(
+            haraka256_into::<5>(&mut buf, 14, &src),
+            Err(Error::BufferTooSmall {
+                required: 46,
+                actual: 45
+            })
+        );
+        assert_eq!(
+            haraka256_into::<5>(&mut buf, usize::MAX - 8, &src),
+            Err(Error::BufferTooSmall {
+                required: usize::MAX,
+                actual: 45
+            })
+        );
+    }
+
+    #[test]
+    fn test_haraka256_be() {
+        let src: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let mut le = [0u8; 32];
+        let mut be = [0u8; 32];
+        haraka256::<5>(&mut le, &src);
+        haraka256_be::<5>(&mut be, &src);
+        // The digest starts 80 27 cc b8 79 49 77 4b.
+        assert_eq!(be[..8], [0x4b, 0x77, 0x49, 0x79, 0xb8, 0xcc, 0x27, 0x80]);
+        swap_words(&mut be);
+        assert_eq!(be, le);
+    }
+
     #[test]
     fn test_haraka256_6round() {
         let mut dst = [0; 32];
@@ -143,7 +302,7 @@ mod tests {
                        \xff\xf8\xcc\xf4\x69\x03\xd1\xc8\
                        \x18\x4b\x40\x4c\xc8\x37\x35\x55\
                        \x1c\x80\xa7\x2b\x5f\xb3\x20\x45";
-        haraka256::<6>(&mut dst, &src);
+        haraka256::<6>(&mut dst, src);
         assert_eq!(&dst, expect);
     }
 }
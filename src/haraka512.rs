@@ -1,86 +1,290 @@
-use crate::constants;
-use crate::simd128::Simd128;
-use arrayref::{array_mut_ref, array_ref};
+use crate::backend::{DefaultBackend, HarakaBackend};
+use crate::constants::{self, Round, RoundConstants};
+use crate::error::Error;
+use crate::simd128::{Lanes, Simd128};
+use crate::simd256::Simd256;
+use arrayref::{array_mut_ref, array_ref, mut_array_refs};
+
+/// The four 128-bit lanes of a Haraka-512 state on backend `B`, in message
+/// order.
+pub(crate) type State<B = DefaultBackend> = [<B as HarakaBackend>::Lane; 4];
 
 #[inline(always)]
-pub(crate) fn aes4(
-    s0: &mut Simd128,
-    s1: &mut Simd128,
-    s2: &mut Simd128,
-    s3: &mut Simd128,
-    rci: usize,
-) {
-    Simd128::aesenc(s0, &constants::HARAKA_CONSTANTS[rci]);
-    Simd128::aesenc(s1, &constants::HARAKA_CONSTANTS[rci + 1]);
-    Simd128::aesenc(s2, &constants::HARAKA_CONSTANTS[rci + 2]);
-    Simd128::aesenc(s3, &constants::HARAKA_CONSTANTS[rci + 3]);
-    Simd128::aesenc(s0, &constants::HARAKA_CONSTANTS[rci + 4]);
-    Simd128::aesenc(s1, &constants::HARAKA_CONSTANTS[rci + 5]);
-    Simd128::aesenc(s2, &constants::HARAKA_CONSTANTS[rci + 6]);
-    Simd128::aesenc(s3, &constants::HARAKA_CONSTANTS[rci + 7]);
+fn aes4<B: HarakaBackend>(s: &mut State<B>, rc: &Round) {
+    B::aesenc4_bytes(s, array_ref![rc, 0, 4]);
+    B::aesenc4_bytes(s, array_ref![rc, 4, 4]);
 }
 
+/// The MIX permutation of [`SoftBackend`](crate::backend::SoftBackend), and
+/// of the paired lanes of [`haraka512_x2`].
 #[inline(always)]
-pub(crate) fn mix4(s0: &mut Simd128, s1: &mut Simd128, s2: &mut Simd128, s3: &mut Simd128) {
+pub(crate) fn mix4<V: Lanes>(s: &mut [V; 4]) {
+    let [s0, s1, s2, s3] = s;
     let mut tmp = *s0;
-    Simd128::unpacklo_epi32(&mut tmp, s1);
-    Simd128::unpackhi_epi32(s0, s1);
+    V::unpacklo_epi32(&mut tmp, s1);
+    V::unpackhi_epi32(s0, s1);
     *s1 = *s2;
-    Simd128::unpacklo_epi32(s1, s3);
-    Simd128::unpackhi_epi32(s2, s3);
+    V::unpacklo_epi32(s1, s3);
+    V::unpackhi_epi32(s2, s3);
 
     *s3 = *s0;
-    Simd128::unpacklo_epi32(s3, s2);
-    Simd128::unpackhi_epi32(s0, s2);
+    V::unpacklo_epi32(s3, s2);
+    V::unpackhi_epi32(s0, s2);
     *s2 = *s1;
-    Simd128::unpackhi_epi32(s2, &tmp);
-    Simd128::unpacklo_epi32(s1, &tmp);
+    V::unpackhi_epi32(s2, &tmp);
+    V::unpacklo_epi32(s1, &tmp);
+}
+
+// Constant time: no branch or memory index depends on the state; the
+// `ct_check` test verifies this on the compiled code. Out of line with
+// `small-code`, so that every variant shares one copy of the round.
+#[cfg_attr(not(feature = "small-code"), inline(always))]
+#[cfg_attr(feature = "small-code", inline(never))]
+pub(crate) fn aes_mix4<B: HarakaBackend>(s: &mut State<B>, rc: &Round) {
+    aes4::<B>(s, rc);
+    B::mix4(s);
+}
+
+/// XORs `t` into `s`, lane by lane.
+#[inline(always)]
+pub(crate) fn pxor4<B: HarakaBackend>(s: &mut State<B>, t: &State<B>) {
+    for (lane, t) in s.iter_mut().zip(t) {
+        B::xor(lane, t);
+    }
+}
+
+/// Loads a 64-byte block into the four lanes.
+#[inline(always)]
+pub(crate) fn load4<B: HarakaBackend>(src: &[u8; 64]) -> State<B> {
+    [
+        B::load(array_ref![src, 0, 16]),
+        B::load(array_ref![src, 16, 16]),
+        B::load(array_ref![src, 32, 16]),
+        B::load(array_ref![src, 48, 16]),
+    ]
 }
 
+/// Stores the four lanes as a 64-byte block.
 #[inline(always)]
-pub(crate) fn aes_mix4(
-    s0: &mut Simd128,
-    s1: &mut Simd128,
-    s2: &mut Simd128,
-    s3: &mut Simd128,
-    rci: usize,
+pub(crate) fn store4<B: HarakaBackend>(dst: &mut [u8; 64], s: &State<B>) {
+    B::store(&s[0], array_mut_ref![dst, 0, 16]);
+    B::store(&s[1], array_mut_ref![dst, 16, 16]);
+    B::store(&s[2], array_mut_ref![dst, 32, 16]);
+    B::store(&s[3], array_mut_ref![dst, 48, 16]);
+}
+
+/// Stores the truncated digest: the high half of lanes 0 and 1, the low
+/// half of lanes 2 and 3.
+#[inline(always)]
+pub(crate) fn truncstore<B: HarakaBackend>(dst: &mut [u8; 32], s: &State<B>) {
+    let [a, b, c, d] = s.map(|lane| {
+        let mut bytes = [0u8; 16];
+        B::store(&lane, &mut bytes);
+        bytes
+    });
+    let (hi_a, hi_b, lo_c, lo_d) = mut_array_refs![dst, 8, 8, 8, 8];
+    hi_a.copy_from_slice(&a[8..]);
+    hi_b.copy_from_slice(&b[8..]);
+    lo_c.copy_from_slice(&c[..8]);
+    lo_d.copy_from_slice(&d[..8]);
+}
+
+/// Like [`truncstore`], but stores each 8-byte word of the truncated lanes
+/// in big-endian byte order.
+#[inline(always)]
+pub(crate) fn truncstore_be<B: HarakaBackend>(dst: &mut [u8; 32], s: &State<B>) {
+    truncstore::<B>(dst, s);
+    swap_words(dst);
+}
+
+/// Reverses the byte order of each 8-byte word of `dst`.
+#[inline(always)]
+pub(crate) fn swap_words(dst: &mut [u8; 32]) {
+    for word in dst.chunks_exact_mut(8) {
+        word.reverse();
+    }
+}
+
+/// Runs the Haraka-512 rounds on already loaded lanes, applies the
+/// feed-forward with the same lanes and stores the truncated result.
+#[inline(always)]
+pub(crate) fn haraka512_lanes<const N_ROUNDS: usize>(dst: &mut [u8; 32], t: &State) {
+    haraka512_lanes_with::<N_ROUNDS>(dst, t, constants::HARAKA_CONSTANTS);
+}
+
+/// Like [`haraka512_lanes`], but with the round constants taken from `rc`.
+#[inline(always)]
+pub(crate) fn haraka512_lanes_with<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    t: &State,
+    rc: &RoundConstants,
 ) {
-    aes4(s0, s1, s2, s3, rci);
-    mix4(s0, s1, s2, s3);
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_512,
+            "Haraka-512 supports at most 6 rounds"
+        )
+    };
+    haraka512_lanes_rounds::<DefaultBackend>(dst, t, rc, N_ROUNDS);
 }
 
+/// Like [`haraka512_lanes_with`] on backend `B`, with the number of rounds
+/// passed at run time. Counts above the number of constants in `rc` are
+/// capped.
 #[inline(always)]
-pub(crate) fn truncstore(
+pub(crate) fn haraka512_lanes_rounds<B: HarakaBackend>(
     dst: &mut [u8; 32],
-    s0: &Simd128,
-    s1: &Simd128,
-    s2: &Simd128,
-    s3: &Simd128,
+    t: &State<B>,
+    rc: &RoundConstants,
+    rounds: usize,
 ) {
-    Simd128::unpackhi_epi64(s0, s1).write(array_mut_ref![dst, 0, 16]);
-    Simd128::unpacklo_epi64(s2, s3).write(array_mut_ref![dst, 16, 16]);
+    let mut s = *t;
+    for round in rc.iter().take(rounds) {
+        aes_mix4::<B>(&mut s, round);
+    }
+    pxor4::<B>(&mut s, t);
+    truncstore::<B>(dst, &s);
+}
+
+/// Applies the Haraka-512 permutation only: no feed-forward, no truncation.
+pub fn haraka512_perm<const N_ROUNDS: usize>(dst: &mut [u8; 64], src: &[u8; 64]) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_512,
+            "Haraka-512 supports at most 6 rounds"
+        )
+    };
+    haraka512_perm_rounds(dst, src, N_ROUNDS);
+}
+
+/// The body of [`haraka512_perm`], shared by all round counts; see
+/// [`haraka512_rounds`].
+#[cfg_attr(not(feature = "small-code"), inline(always))]
+#[cfg_attr(feature = "small-code", inline(never))]
+fn haraka512_perm_rounds(dst: &mut [u8; 64], src: &[u8; 64], rounds: usize) {
+    let mut s = load4::<DefaultBackend>(src);
+    for round in constants::HARAKA_CONSTANTS.iter().take(rounds) {
+        aes_mix4::<DefaultBackend>(&mut s, round);
+    }
+    store4::<DefaultBackend>(dst, &s);
 }
 
 pub fn haraka512<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 64]) {
-    let mut s0 = Simd128::read(array_ref![src, 0, 16]);
-    let mut s1 = Simd128::read(array_ref![src, 16, 16]);
-    let mut s2 = Simd128::read(array_ref![src, 32, 16]);
-    let mut s3 = Simd128::read(array_ref![src, 48, 16]);
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_512,
+            "Haraka-512 supports at most 6 rounds"
+        )
+    };
+    haraka512_rounds::<DefaultBackend>(dst, src, N_ROUNDS);
+}
+
+/// The body of [`haraka512`] on backend `B`, shared by all round counts.
+/// With `small-code` it stays out of line, so a program using several round
+/// counts carries one copy instead of one per count.
+#[cfg_attr(not(feature = "small-code"), inline(always))]
+#[cfg_attr(feature = "small-code", inline(never))]
+pub(crate) fn haraka512_rounds<B: HarakaBackend>(
+    dst: &mut [u8; 32],
+    src: &[u8; 64],
+    rounds: usize,
+) {
+    haraka512_lanes_rounds::<B>(dst, &load4::<B>(src), constants::HARAKA_CONSTANTS, rounds);
+}
+
+/// Computes Haraka-512 of the message `a || b || c || d`, loading each
+/// 16-byte limb straight into its lane.
+pub fn haraka512_limbs<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    a: &[u8; 16],
+    b: &[u8; 16],
+    c: &[u8; 16],
+    d: &[u8; 16],
+) {
+    let t = [a, b, c, d].map(DefaultBackend::load);
+    haraka512_lanes::<N_ROUNDS>(dst, &t);
+}
 
-    for i in 0..N_ROUNDS {
-        aes_mix4(&mut s0, &mut s1, &mut s2, &mut s3, 8 * i);
+/// Computes Haraka-512 of the concatenation of `parts`, which must total
+/// 64 bytes, copying each part straight into the lanes.
+pub fn haraka512_gather<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    parts: &[&[u8]],
+) -> Result<(), Error> {
+    let actual = parts
+        .iter()
+        .fold(0usize, |total, part| total.saturating_add(part.len()));
+    if actual != 64 {
+        return Err(Error::InvalidLength {
+            expected: 64,
+            actual,
+        });
+    }
+    let mut lanes = [[0u8; 16]; 4];
+    let flat = lanes.as_flattened_mut();
+    let mut offset = 0;
+    for part in parts {
+        if let Some(slot) = flat.get_mut(offset..offset + part.len()) {
+            slot.copy_from_slice(part);
+        }
+        offset += part.len();
     }
+    let t = lanes.map(|lane| DefaultBackend::load(&lane));
+    haraka512_lanes::<N_ROUNDS>(dst, &t);
+    Ok(())
+}
 
-    let t0 = Simd128::read(array_ref![src, 0, 16]);
-    let t1 = Simd128::read(array_ref![src, 16, 16]);
-    let t2 = Simd128::read(array_ref![src, 32, 16]);
-    let t3 = Simd128::read(array_ref![src, 48, 16]);
-    Simd128::pxor(&mut s0, &t0);
-    Simd128::pxor(&mut s1, &t1);
-    Simd128::pxor(&mut s2, &t2);
-    Simd128::pxor(&mut s3, &t3);
+/// Computes Haraka-512 of two messages at once, with the two halves of
+/// each [`Simd256`] holding the corresponding lanes of the two messages.
+pub(crate) fn haraka512_x2<const N_ROUNDS: usize>(dst: [&mut [u8; 32]; 2], src: [&[u8; 64]; 2]) {
+    haraka512_x2_with::<N_ROUNDS>(dst, src, constants::HARAKA_CONSTANTS);
+}
 
-    truncstore(dst, &s0, &s1, &s2, &s3);
+/// Like [`haraka512_x2`], but with the round constants taken from `rc`.
+#[inline(always)]
+pub(crate) fn haraka512_x2_with<const N_ROUNDS: usize>(
+    dst: [&mut [u8; 32]; 2],
+    src: [&[u8; 64]; 2],
+    rc: &RoundConstants,
+) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_512,
+            "Haraka-512 supports at most 6 rounds"
+        )
+    };
+    let load = |off| {
+        Simd256::from_lanes(
+            Simd128::read(array_ref![src[0], off, 16]),
+            Simd128::read(array_ref![src[1], off, 16]),
+        )
+    };
+    let t = [load(0), load(16), load(32), load(48)];
+    let mut s = t;
+
+    for round in &rc[..N_ROUNDS] {
+        Simd256::aesenc4(&mut s, array_ref![round, 0, 4]);
+        Simd256::aesenc4(&mut s, array_ref![round, 4, 4]);
+        mix4(&mut s);
+    }
+    for (lane, t) in s.iter_mut().zip(&t) {
+        Simd256::pxor(lane, t);
+    }
+
+    let [lo0, lo1] = Simd256::unpackhi_epi64(&s[0], &s[1]).lanes();
+    let [hi0, hi1] = Simd256::unpacklo_epi64(&s[2], &s[3]).lanes();
+    let [dst0, dst1] = dst;
+    lo0.write(array_mut_ref![dst0, 0, 16]);
+    hi0.write(array_mut_ref![dst0, 16, 16]);
+    lo1.write(array_mut_ref![dst1, 0, 16]);
+    hi1.write(array_mut_ref![dst1, 16, 16]);
+}
+
+/// Computes Haraka-512 and stores the digest as four big-endian 64-bit
+/// words.
+pub fn haraka512_be<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 64]) {
+    haraka512::<N_ROUNDS>(dst, src);
+    swap_words(dst);
 }
 
 #[cfg(test)]
@@ -88,15 +292,12 @@ mod tests {
     use super::*;
 
     fn mix4_slice(s0: &mut [u8; 16], s1: &mut [u8; 16], s2: &mut [u8; 16], s3: &mut [u8; 16]) {
-        let mut s0_xmm = Simd128::read(s0);
-        let mut s1_xmm = Simd128::read(s1);
-        let mut s2_xmm = Simd128::read(s2);
-        let mut s3_xmm = Simd128::read(s3);
-        mix4(&mut s0_xmm, &mut s1_xmm, &mut s2_xmm, &mut s3_xmm);
-        s0_xmm.write(s0);
-        s1_xmm.write(s1);
-        s2_xmm.write(s2);
-        s3_xmm.write(s3);
+        let mut s = [&*s0, &*s1, &*s2, &*s3].map(Simd128::read);
+        mix4(&mut s);
+        s[0].write(s0);
+        s[1].write(s1);
+        s[2].write(s2);
+        s[3].write(s3);
     }
 
     #[test]
@@ -124,16 +325,10 @@ mod tests {
         assert_eq!(dst3, expect3);
     }
 
-    fn aes4_slice(state: &mut [u8; 64], rci: usize) {
-        let mut s0_xmm = Simd128::read(array_ref![state, 0, 16]);
-        let mut s1_xmm = Simd128::read(array_ref![state, 16, 16]);
-        let mut s2_xmm = Simd128::read(array_ref![state, 32, 16]);
-        let mut s3_xmm = Simd128::read(array_ref![state, 48, 16]);
-        aes4(&mut s0_xmm, &mut s1_xmm, &mut s2_xmm, &mut s3_xmm, rci);
-        s0_xmm.write(array_mut_ref![state, 0, 16]);
-        s1_xmm.write(array_mut_ref![state, 16, 16]);
-        s2_xmm.write(array_mut_ref![state, 32, 16]);
-        s3_xmm.write(array_mut_ref![state, 48, 16]);
+    fn aes4_slice(state: &mut [u8; 64], round: usize) {
+        let mut s = load4::<DefaultBackend>(state);
+        aes4::<DefaultBackend>(&mut s, &constants::HARAKA_CONSTANTS[round]);
+        store4::<DefaultBackend>(state, &s);
     }
 
     #[test]
@@ -158,16 +353,10 @@ mod tests {
         assert_eq!(&state as &[u8], &expect as &[u8]);
     }
 
-    fn aes_mix4_slice(state: &mut [u8; 64], rci: usize) {
-        let mut s0_xmm = Simd128::read(array_ref![state, 0, 16]);
-        let mut s1_xmm = Simd128::read(array_ref![state, 16, 16]);
-        let mut s2_xmm = Simd128::read(array_ref![state, 32, 16]);
-        let mut s3_xmm = Simd128::read(array_ref![state, 48, 16]);
-        aes_mix4(&mut s0_xmm, &mut s1_xmm, &mut s2_xmm, &mut s3_xmm, rci);
-        s0_xmm.write(array_mut_ref![state, 0, 16]);
-        s1_xmm.write(array_mut_ref![state, 16, 16]);
-        s2_xmm.write(array_mut_ref![state, 32, 16]);
-        s3_xmm.write(array_mut_ref![state, 48, 16]);
+    fn aes_mix4_slice(state: &mut [u8; 64], round: usize) {
+        let mut s = load4::<DefaultBackend>(state);
+        aes_mix4::<DefaultBackend>(&mut s, &constants::HARAKA_CONSTANTS[round]);
+        store4::<DefaultBackend>(state, &s);
     }
 
     #[test]
@@ -193,11 +382,7 @@ mod tests {
     }
 
     fn truncstore_slice(dst: &mut [u8; 32], state: &[u8; 64]) {
-        let s0_xmm = Simd128::read(array_ref![state, 0, 16]);
-        let s1_xmm = Simd128::read(array_ref![state, 16, 16]);
-        let s2_xmm = Simd128::read(array_ref![state, 32, 16]);
-        let s3_xmm = Simd128::read(array_ref![state, 48, 16]);
-        truncstore(dst, &s0_xmm, &s1_xmm, &s2_xmm, &s3_xmm);
+        truncstore::<DefaultBackend>(dst, &load4::<DefaultBackend>(state));
     }
 
     #[test]
@@ -236,7 +421,7 @@ mod tests {
                        \x13\xb2\x92\x28\x7f\x30\x6f\x62\
                        \x5a\x6d\x57\x33\x1c\xae\x5f\x34\
                        \xdd\x92\x77\xb0\x94\x5b\xe2\xaa";
-        haraka512::<5>(&mut dst, &src);
+        haraka512::<5>(&mut dst, src);
         assert_eq!(&dst, expect);
     }
 
@@ -255,7 +440,133 @@ mod tests {
                        \x15\x3c\x9a\x54\x13\xfb\x1e\x98\
                        \x4a\x91\x4f\x5b\x6f\xea\x17\x22\
                        \x85\x41\xce\x17\x07\xfc\x4e\x64";
-        haraka512::<6>(&mut dst, &src);
+        haraka512::<6>(&mut dst, src);
         assert_eq!(&dst, expect);
     }
+
+    #[test]
+    fn test_truncstore_be() {
+        let state: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let mut dst = [0u8; 32];
+        truncstore_be::<DefaultBackend>(&mut dst, &load4::<DefaultBackend>(&state));
+        let expect = [
+            0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a, 0x09, 0x08, 0x1f, 0x1e, 0x1d, 0x1c, 0x1b, 0x1a,
+            0x19, 0x18, 0x27, 0x26, 0x25, 0x24, 0x23, 0x22, 0x21, 0x20, 0x37, 0x36, 0x35, 0x34,
+            0x33, 0x32, 0x31, 0x30,
+        ];
+        assert_eq!(dst, expect);
+    }
+
+    #[test]
+    fn test_haraka512_be() {
+        let src: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let mut le = [0u8; 32];
+        let mut be = [0u8; 32];
+        haraka512::<5>(&mut le, &src);
+        haraka512_be::<5>(&mut be, &src);
+        for (l, b) in le.chunks_exact(8).zip(be.chunks_exact(8)) {
+            assert_eq!(
+                u64::from_le_bytes(l.try_into().unwrap()),
+                u64::from_be_bytes(b.try_into().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_haraka512_limbs() {
+        let mut src = [0u8; 64];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = (7 * i) as u8;
+        }
+        let mut expect = [0u8; 32];
+        haraka512::<5>(&mut expect, &src);
+        let mut dst = [0u8; 32];
+        haraka512_limbs::<5>(
+            &mut dst,
+            array_ref![src, 0, 16],
+            array_ref![src, 16, 16],
+            array_ref![src, 32, 16],
+            array_ref![src, 48, 16],
+        );
+        assert_eq!(dst, expect);
+    }
+
+    #[test]
+    fn test_haraka512_gather() {
+        let owner = [0x11u8; 32];
+        let mint = [0x22u8; 24];
+        let amount = 1_000u64.to_le_bytes();
+        let mut src = [0u8; 64];
+        src[..32].copy_from_slice(&owner);
+        src[32..56].copy_from_slice(&mint);
+        src[56..].copy_from_slice(&amount);
+        let mut expect = [0u8; 32];
+        haraka512::<5>(&mut expect, &src);
+
+        let mut dst = [0u8; 32];
+        haraka512_gather::<5>(&mut dst, &[&owner, &mint, &amount]).unwrap();
+        assert_eq!(dst, expect);
+        // Empty parts and parts straddling lanes are fine.
+        haraka512_gather::<5>(&mut dst, &[&src[..7], &[], &src[7..50], &src[50..]]).unwrap();
+        assert_eq!(dst, expect);
+
+        assert_eq!(
+            haraka512_gather::<5>(&mut dst, &[&owner, &mint]),
+            Err(Error::InvalidLength {
+                expected: 64,
+                actual: 56
+            })
+        );
+        assert_eq!(
+            haraka512_gather::<5>(&mut dst, &[&src, &amount]),
+            Err(Error::InvalidLength {
+                expected: 64,
+                actual: 72
+            })
+        );
+    }
+
+    #[test]
+    fn test_haraka512_x2() {
+        let mut a = [0u8; 64];
+        let mut b = [0u8; 64];
+        for i in 0..64 {
+            a[i] = i as u8;
+            b[i] = 0xff - i as u8;
+        }
+        let mut expect_a = [0u8; 32];
+        let mut expect_b = [0u8; 32];
+        haraka512::<5>(&mut expect_a, &a);
+        haraka512::<5>(&mut expect_b, &b);
+
+        let mut dst_a = [0u8; 32];
+        let mut dst_b = [0u8; 32];
+        haraka512_x2::<5>([&mut dst_a, &mut dst_b], [&a, &b]);
+        assert_eq!(dst_a, expect_a);
+        assert_eq!(dst_b, expect_b);
+    }
+
+    #[test]
+    fn test_haraka512_perm_feed_forward() {
+        // Feed-forward and truncation of the permutation output must give
+        // the Haraka-512 digest.
+        let mut src = [0u8; 64];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut perm = [0u8; 64];
+        haraka512_perm::<5>(&mut perm, &src);
+        for (p, s) in perm.iter_mut().zip(src.iter()) {
+            *p ^= s;
+        }
+        let mut truncated = [0u8; 32];
+        truncated[..8].copy_from_slice(&perm[8..16]);
+        truncated[8..16].copy_from_slice(&perm[24..32]);
+        truncated[16..24].copy_from_slice(&perm[32..40]);
+        truncated[24..].copy_from_slice(&perm[48..56]);
+
+        let mut expect = [0u8; 32];
+        haraka512::<5>(&mut expect, &src);
+        assert_eq!(truncated, expect);
+    }
 }
@@ -1,73 +1,103 @@
-use crate::haraka512::{aes_mix4, truncstore}; // Reuse helpers
-use crate::simd128::Simd128;
-use arrayref::array_ref;
+use crate::backend::DefaultBackend;
+use crate::constants;
+use crate::haraka512::{aes_mix4, load4, pxor4, truncstore, State}; // Reuse helpers
 
 /// Computes the keyed Haraka-512 permutation.
 ///
 /// 1. Reads the 64-byte input state and 64-byte key.
-/// 2. Creates a copy of the original input state for the feed-forward step.
-/// 3. XORs the key into the state.
+/// 2. XORs the key into the state.
+/// 3. Keeps a copy of the keyed state for the feed-forward step.
 /// 4. Applies `N_ROUNDS` of the Haraka permutation (AES rounds + Mix).
-/// 5. XORs the permuted state with the original input state (feed-forward).
+/// 5. XORs the permuted state with the keyed state (feed-forward).
 /// 6. Truncates the result to 32 bytes and writes it to `dst`.
+///
+/// The result is therefore unkeyed Haraka-512 of `state ^ key`, which is
+/// how the SPHINCS+ robust tweakable hashes apply their bitmasks.
 #[inline(always)]
 pub fn haraka512_keyed<const N_ROUNDS: usize>(
     dst: &mut [u8; 32],
     state: &[u8; 64],
     key: &[u8; 64],
 ) {
-    // --- Load initial state and key ---
-    let mut s0 = Simd128::read(array_ref![state, 0, 16]);
-    let mut s1 = Simd128::read(array_ref![state, 16, 16]);
-    let mut s2 = Simd128::read(array_ref![state, 32, 16]);
-    let mut s3 = Simd128::read(array_ref![state, 48, 16]);
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_512,
+            "Haraka-512 supports at most 6 rounds"
+        )
+    };
+    haraka512_keyed_rounds(dst, state, key, N_ROUNDS);
+}
 
-    let k0 = Simd128::read(array_ref![key, 0, 16]);
-    let k1 = Simd128::read(array_ref![key, 16, 16]);
-    let k2 = Simd128::read(array_ref![key, 32, 16]);
-    let k3 = Simd128::read(array_ref![key, 48, 16]);
+/// The body of [`haraka512_keyed`], shared by all round counts. With
+/// `small-code` it stays out of line, like the unkeyed functions.
+#[cfg_attr(not(feature = "small-code"), inline(always))]
+#[cfg_attr(feature = "small-code", inline(never))]
+fn haraka512_keyed_rounds(dst: &mut [u8; 32], state: &[u8; 64], key: &[u8; 64], rounds: usize) {
+    haraka512_keyed_lanes(dst, state, &load4::<DefaultBackend>(key), rounds);
+}
+
+/// Keyed Haraka-512 with the key already loaded into lanes.
+#[inline(always)]
+fn haraka512_keyed_lanes(dst: &mut [u8; 32], state: &[u8; 64], key: &State, rounds: usize) {
+    // --- Load initial state ---
+    let mut s = load4::<DefaultBackend>(state);
 
     // --- XOR key into state ---
-    Simd128::pxor(&mut s0, &k0);
-    Simd128::pxor(&mut s1, &k1);
-    Simd128::pxor(&mut s2, &k2);
-    Simd128::pxor(&mut s3, &k3);
+    pxor4::<DefaultBackend>(&mut s, key);
 
     // --- Keep state *after* key XOR for feed-forward (matches C ref) ---
 
-    let t0 = s0;
-    let t1 = s1;
-    let t2 = s2;
-    let t3 = s3;
+    let t = s;
 
     // --- Apply Haraka rounds ---
-    // Ensure N_ROUNDS doesn't exceed the available constants (5 rounds * 8 constants/round = 40)
-    // HARAKA_CONSTANTS has 48 elements, so max rounds is 48 / 8 = 6.
-    // However, the reference implementation uses 5 rounds.
-    debug_assert!(
-        N_ROUNDS <= 5,
-        "N_ROUNDS cannot exceed 5 for keyed Haraka-512"
-    );
-    for i in 0..N_ROUNDS {
-        aes_mix4(&mut s0, &mut s1, &mut s2, &mut s3, 8 * i);
+    for round in constants::HARAKA_CONSTANTS.iter().take(rounds) {
+        aes_mix4::<DefaultBackend>(&mut s, round);
     }
 
     // --- Feed-forward ---
 
-    Simd128::pxor(&mut s0, &t0);
-    Simd128::pxor(&mut s1, &t1);
-    Simd128::pxor(&mut s2, &t2);
-    Simd128::pxor(&mut s3, &t3);
+    pxor4::<DefaultBackend>(&mut s, &t);
 
     // --- Truncate and store ---
-    truncstore(dst, &s0, &s1, &s2, &s3);
+    truncstore::<DefaultBackend>(dst, &s);
+}
+
+/// A key for [`haraka512_keyed`](crate::haraka512_keyed), loaded once and
+/// reused for many states.
+///
+/// The key is kept in lane form, so each hash skips loading it.
+#[derive(Clone)]
+pub struct Haraka512Keyed {
+    key: State,
+}
+
+impl Haraka512Keyed {
+    /// Loads `key`.
+    pub fn new(key: &[u8; 64]) -> Self {
+        Self {
+            key: load4::<DefaultBackend>(key),
+        }
+    }
+
+    /// Computes keyed Haraka-512 with N_ROUNDS rounds of `state`.
+    pub fn hash<const N_ROUNDS: usize>(&self, state: &[u8; 64]) -> [u8; 32] {
+        const {
+            assert!(
+                N_ROUNDS <= constants::MAX_ROUNDS_512,
+                "Haraka-512 supports at most 6 rounds"
+            )
+        };
+        let mut dst = [0u8; 32];
+        haraka512_keyed_lanes(&mut dst, state, &self.key, N_ROUNDS);
+        dst
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::haraka512; // Import the un-keyed version for comparison
-    use arrayref::array_mut_ref; // Import the missing macro
+    use crate::haraka512::store4;
     #[allow(unused_imports)]
     // Example test structure if you can get intermediate values
     #[test]
@@ -76,36 +106,41 @@ mod tests {
         let key = [0x22u8; 64];
         let expected_xor_state = [0x33u8; 64]; // 0x11 ^ 0x22 = 0x33
 
-        let mut s0 = Simd128::read(array_ref![state, 0, 16]);
-        let mut s1 = Simd128::read(array_ref![state, 16, 16]);
-        let mut s2 = Simd128::read(array_ref![state, 32, 16]);
-        let mut s3 = Simd128::read(array_ref![state, 48, 16]);
-
-        let k0 = Simd128::read(array_ref![key, 0, 16]);
-        let k1 = Simd128::read(array_ref![key, 16, 16]);
-        let k2 = Simd128::read(array_ref![key, 32, 16]);
-        let k3 = Simd128::read(array_ref![key, 48, 16]);
-
-        Simd128::pxor(&mut s0, &k0);
-        Simd128::pxor(&mut s1, &k1);
-        Simd128::pxor(&mut s2, &k2);
-        Simd128::pxor(&mut s3, &k3);
+        let mut s = load4::<DefaultBackend>(&state);
+        pxor4::<DefaultBackend>(&mut s, &load4::<DefaultBackend>(&key));
 
         let mut xor_state_result = [0u8; 64];
-        s0.write(array_mut_ref![xor_state_result, 0, 16]); // Use the imported macro
-        s1.write(array_mut_ref![xor_state_result, 16, 16]); // Use the imported macro
-        s2.write(array_mut_ref![xor_state_result, 32, 16]); // Use the imported macro
-        s3.write(array_mut_ref![xor_state_result, 48, 16]); // Use the imported macro
+        store4::<DefaultBackend>(&mut xor_state_result, &s);
 
         assert_eq!(xor_state_result, expected_xor_state);
     }
 
+    #[test]
+    fn test_haraka512_keyed_6round() {
+        // Computed with the PQClean SPHINCS+ haraka512, extended to six
+        // rounds, on the input XORed with the key.
+        let mut msg = [0u8; 64];
+        let mut key = [0u8; 64];
+        for i in 0..64 {
+            msg[i] = i as u8;
+            key[i] = 0xa5u8.wrapping_add(3 * i as u8);
+        }
+        let expect = [
+            0x54, 0x36, 0x1b, 0xe6, 0xf3, 0x5b, 0x42, 0x9f, 0xbc, 0x1a, 0x8c, 0x1f, 0x36, 0x31,
+            0x05, 0xb4, 0x89, 0x78, 0xfe, 0xf8, 0xe4, 0x37, 0xd1, 0x22, 0x63, 0xeb, 0xfb, 0x83,
+            0xed, 0xef, 0x3a, 0x1d,
+        ];
+        let mut dst = [0u8; 32];
+        haraka512_keyed::<6>(&mut dst, &msg, &key);
+        assert_eq!(dst, expect);
+    }
+
     #[test]
     fn keyed_equals_unkeyed_with_zero_key() {
         // 1) pick a deterministic 64-byte message
         let mut msg = [0u8; 64];
-        for i in 0..64 {
-            msg[i] = i as u8; // 00 01 02 … 3f
+        for (i, b) in msg.iter_mut().enumerate() {
+            *b = i as u8; // 00 01 02 … 3f
         }
 
         // 2) an all-zero 64-byte key
@@ -125,4 +160,93 @@ mod tests {
             "keyed digest should equal un-keyed when key = 0"
         );
     }
+
+    #[test]
+    fn keyed_equals_unkeyed_of_xor() {
+        // The feed-forward uses the state after the key XOR. The fuzz target
+        // `keyed_relation` checks the same identity on arbitrary inputs.
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..16 {
+            let mut msg = [0u8; 64];
+            let mut key = [0u8; 64];
+            for b in msg.iter_mut().chain(key.iter_mut()) {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                *b = seed as u8;
+            }
+            let mut mixed = [0u8; 64];
+            for (m, (a, b)) in mixed.iter_mut().zip(msg.iter().zip(&key)) {
+                *m = a ^ b;
+            }
+
+            let mut keyed = [0u8; 32];
+            let mut unkeyed = [0u8; 32];
+            haraka512_keyed::<5>(&mut keyed, &msg, &key);
+            haraka512::haraka512::<5>(&mut unkeyed, &mixed);
+            assert_eq!(keyed, unkeyed);
+        }
+    }
+
+    /// `(state, key, digest)`, with the digest computed by the PQClean
+    /// SPHINCS+-Haraka `haraka512` (5 rounds) on `state ^ key`. The third
+    /// entry has the shape of a SPHINCS+ robust `F` call: a 32-byte address
+    /// followed by the message, with the bitmask over the message only.
+    const SPHINCS_KATS: [(&str, &str, &str); 4] = [
+        (
+            "4d6a60dfe6426e9ddbc9c0e99fc01047c02d9c6f28adfc92fa5363cc77886ea2\
+             fca7c683356d5664d71dcd3978624e012d062d9bf198ab357cdbf095996ee1f6",
+            "0000000000000000000000000000000000000000000000000000000000000000\
+             0000000000000000000000000000000000000000000000000000000000000000",
+            "4703334ab1528e9c37f20a13544747fdf2907acfbe5381fa010734fd136b8c50",
+        ),
+        (
+            "be470c123f1d6605dc4da3e054c8c268a03dc1d84ae9da7810a6a036a2e17f90\
+             c7b45dbf1b3dd790a74cafe17ab161995e8f8abb0ccf9622dd40adf44e0fabeb",
+            "7e365f775657dc0eb960e6295a1f44b3f67479f54d5d12c5d87e6db234f072e3\
+             7e1cc557da4bca14577112565444ff3f9cc1fc8e3358c8635914af5ed99a79c4",
+            "2699e8c2c51b9ce4132d700ba29bff52fa3dd20a960422e9cd926d441693894f",
+        ),
+        (
+            "00000000000000000000000000000001000000000000000300000000000000a5\
+             e46b320165eec91e6344fa10340d5b3208304d6cad29d0d5aed18466d1d9d80e",
+            "0000000000000000000000000000000000000000000000000000000000000000\
+             48bf9b4f142ab45cac6f33cf99415bca6ca8e07608a031237aa76560fec2204b",
+            "d97890e4d546a1bf9b503ca8fc25672aba32edb9321c04f7e999a6509ae31d35",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+             202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+             0000000000000000000000000000000000000000000000000000000000000000",
+            "b59b1e2a08449b65ff387d63a7ab1cf24b7ca21bd843a3f3c100f38177f7a286",
+        ),
+    ];
+
+    #[test]
+    fn test_sphincs_kats() {
+        for (i, (state, key, digest)) in SPHINCS_KATS.iter().enumerate() {
+            let (mut state_bytes, mut key_bytes, mut expect) = ([0u8; 64], [0u8; 64], [0u8; 32]);
+            crate::decode_hex(state, &mut state_bytes).unwrap();
+            crate::decode_hex(key, &mut key_bytes).unwrap();
+            crate::decode_hex(digest, &mut expect).unwrap();
+            let mut dst = [0u8; 32];
+            haraka512_keyed::<5>(&mut dst, &state_bytes, &key_bytes);
+            assert_eq!(dst, expect, "vector {i}");
+        }
+    }
+
+    #[test]
+    fn test_keyed_object() {
+        let key = [0x5au8; 64];
+        let keyed = Haraka512Keyed::new(&key);
+        for fill in [0x00, 0x42, 0xff] {
+            let state = [fill; 64];
+            let mut expect = [0u8; 32];
+            haraka512_keyed::<5>(&mut expect, &state, &key);
+            assert_eq!(keyed.hash::<5>(&state), expect);
+            haraka512_keyed::<6>(&mut expect, &state, &key);
+            assert_eq!(keyed.hash::<6>(&state), expect);
+        }
+    }
 }
@@ -0,0 +1,178 @@
+//! Keyed `core::hash::Hasher` built on Haraka-512.
+//!
+//! The hasher keeps a 32-byte chaining value, initialized with the key, and
+//! compresses each 32-byte block of input as `H512(chain || block)`. The
+//! last, zero-padded block is compressed with the total input length XORed
+//! into the chaining value, so inputs that only differ by trailing zeros do
+//! not collide. The first 8 bytes of the final digest are the hash.
+//!
+//! Without knowledge of the key, an attacker cannot predict which inputs
+//! collide in a hash table, which is the property `RandomState` provides
+//! for SipHash in `std`.
+
+use crate::haraka512::haraka512;
+use arrayref::array_ref;
+use core::hash::{BuildHasher, Hasher};
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// Builds [`HarakaHasher`]s sharing a 32-byte key.
+#[derive(Clone)]
+pub struct HarakaBuildHasher {
+    key: [u8; 32],
+}
+
+impl HarakaBuildHasher {
+    /// Creates a builder with a fixed key.
+    ///
+    /// The key must be secret and unpredictable for the resulting maps to
+    /// resist collision flooding, e.g. derived from a recent slot hash.
+    pub const fn with_key(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    /// Creates a builder with a key drawn from the standard library's
+    /// per-process random seed.
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        let state = std::collections::hash_map::RandomState::new();
+        let mut key = [0u8; 32];
+        for (i, chunk) in key.chunks_exact_mut(8).enumerate() {
+            let mut hasher = state.build_hasher();
+            hasher.write_usize(i);
+            chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+        }
+        Self { key }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for HarakaBuildHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for HarakaBuildHasher {
+    type Hasher = HarakaHasher;
+
+    fn build_hasher(&self) -> HarakaHasher {
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(&self.key);
+        HarakaHasher {
+            block,
+            pos: 0,
+            len: 0,
+        }
+    }
+}
+
+/// Keyed hasher created by [`HarakaBuildHasher`].
+///
+/// Output only depends on the concatenation of the written bytes, not on
+/// how they were split across `write` calls.
+#[derive(Clone)]
+pub struct HarakaHasher {
+    /// Chaining value followed by the pending input block.
+    block: [u8; 64],
+    /// Number of bytes in the pending input block.
+    pos: usize,
+    /// Total number of bytes written.
+    len: u64,
+}
+
+impl HarakaHasher {
+    fn compress(&mut self) {
+        let mut chain = [0u8; 32];
+        haraka512::<ROUNDS>(&mut chain, &self.block);
+        self.block[..32].copy_from_slice(&chain);
+        self.block[32..].fill(0);
+        self.pos = 0;
+    }
+}
+
+impl Hasher for HarakaHasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.len = self.len.wrapping_add(bytes.len() as u64);
+        while !bytes.is_empty() {
+            // Compress lazily, so that the last block is always pending.
+            if self.pos == 32 {
+                self.compress();
+            }
+            let take = (32 - self.pos).min(bytes.len());
+            let (head, rest) = bytes.split_at(take);
+            for (b, d) in self.block.iter_mut().skip(32 + self.pos).zip(head) {
+                *b = *d;
+            }
+            self.pos += take;
+            bytes = rest;
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut block = self.block;
+        for (b, l) in block[24..32].iter_mut().zip(self.len.to_le_bytes()) {
+            *b ^= l;
+        }
+        let mut digest = [0u8; 32];
+        haraka512::<ROUNDS>(&mut digest, &block);
+        u64::from_le_bytes(*array_ref![digest, 0, 8])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(key: [u8; 32], parts: &[&[u8]]) -> u64 {
+        let mut hasher = HarakaBuildHasher::with_key(key).build_hasher();
+        for part in parts {
+            hasher.write(part);
+        }
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_split_writes() {
+        let data = [0x17u8; 100];
+        let expect = hash([1; 32], &[&data]);
+        for split in [0, 1, 31, 32, 33, 64, 99] {
+            let (a, b) = data.split_at(split);
+            assert_eq!(hash([1; 32], &[a, b]), expect, "split {}", split);
+        }
+    }
+
+    #[test]
+    fn test_key_changes_hash() {
+        assert_ne!(hash([1; 32], &[b"abc"]), hash([2; 32], &[b"abc"]));
+    }
+
+    #[test]
+    fn test_trailing_zeros() {
+        let key = [3u8; 32];
+        assert_ne!(hash(key, &[b""]), hash(key, &[b"\0"]));
+        assert_ne!(hash(key, &[&[0u8; 32]]), hash(key, &[&[0u8; 33]]));
+        assert_ne!(hash(key, &[b"a"]), hash(key, &[b"a\0"]));
+    }
+
+    #[test]
+    fn test_finish_is_idempotent() {
+        let mut hasher = HarakaBuildHasher::with_key([4; 32]).build_hasher();
+        hasher.write_u64(42);
+        assert_eq!(hasher.finish(), hasher.finish());
+        let before = hasher.finish();
+        hasher.write_u8(0);
+        assert_ne!(hasher.finish(), before);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hash_map() {
+        let mut map = std::collections::HashMap::with_hasher(HarakaBuildHasher::new());
+        for i in 0..100u32 {
+            map.insert(i, i * 2);
+        }
+        assert_eq!(map.len(), 100);
+        assert_eq!(map[&21], 42);
+    }
+}
@@ -0,0 +1,195 @@
+//! HORST few-time signature verification.
+//!
+//! A HORST key is a Merkle tree over `2^TAU` secret values, with leaves
+//! `haraka256_domain(LEAF, secret)` and the inner nodes of
+//! [`verify_merkle_proof`](crate::verify_merkle_proof); the public key is
+//! the root. The 32-byte message is read as `K` indices of `TAU` bits
+//! each, most significant bit first, and a signature reveals the secret of
+//! each indexed leaf with its authentication path:
+//!
+//! ```text
+//! secret_0 || TAU siblings || ... || secret_(K-1) || TAU siblings
+//! ```
+//!
+//! Every signature reveals `K` of the `2^TAU` secrets, so a key signs only
+//! a few messages before forgeries become feasible; schemes such as
+//! SPHINCS use it at the bottom of a hypertree, signing each key once.
+//! Verification always hashes all `K` paths, `K * (TAU + 1)` Haraka calls.
+//!
+//! With the `std` feature, `public_key` and `sign` build the tree from a
+//! 32-byte secret seed, deriving secret `i` as
+//!
+//! ```text
+//! haraka512_domain(SECRET_TAG, secret_seed || u64le(i) || 0^24)
+//! ```
+
+use crate::domain::{haraka256_domain, LEAF};
+#[cfg(feature = "std")]
+use crate::merkle::hash_node;
+use crate::merkle::verify_merkle_proof;
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// Domain tag of the secret values.
+#[cfg(feature = "std")]
+const SECRET_TAG: &[u8; 16] = b"haraka-bpf/horst";
+
+/// Returns the length of a signature with `k` leaves of a tree of height
+/// `tau`.
+pub const fn signature_len(tau: usize, k: usize) -> usize {
+    k * 32 * (1 + tau)
+}
+
+/// Returns `true` if `sig` is a signature of `msg` under the public key
+/// `root`, for a tree of height `TAU` and `K` revealed leaves.
+///
+/// `TAU` must be 1 to 32 and `K * TAU` at most 256. Signatures that are
+/// not [`signature_len(TAU, K)`](signature_len) bytes long are rejected.
+pub fn verify<const TAU: usize, const K: usize>(
+    root: &[u8; 32],
+    msg: &[u8; 32],
+    sig: &[u8],
+) -> bool {
+    check_params::<TAU, K>();
+    if sig.len() != signature_len(TAU, K) {
+        return false;
+    }
+    let (values, _) = sig.as_chunks::<32>();
+    let mut valid = true;
+    for (j, part) in values.chunks_exact(1 + TAU).enumerate() {
+        let (secret, siblings) = (&part[0], &part[1..]);
+        let mut leaf = [0u8; 32];
+        haraka256_domain::<ROUNDS, _>(&mut leaf, LEAF, secret);
+        valid &= verify_merkle_proof(root, &leaf, index::<TAU>(msg, j), siblings);
+    }
+    valid
+}
+
+/// Derives the public key, the root of the tree, from `secret_seed`.
+#[cfg(feature = "std")]
+pub fn public_key<const TAU: usize>(secret_seed: &[u8; 32]) -> [u8; 32] {
+    check_params::<TAU, 1>();
+    levels::<TAU>(secret_seed).pop().unwrap()[0]
+}
+
+/// Signs `msg` with the key of `secret_seed`.
+#[cfg(feature = "std")]
+pub fn sign<const TAU: usize, const K: usize>(
+    secret_seed: &[u8; 32],
+    msg: &[u8; 32],
+) -> std::vec::Vec<u8> {
+    check_params::<TAU, K>();
+    let levels = levels::<TAU>(secret_seed);
+    let mut sig = std::vec::Vec::with_capacity(signature_len(TAU, K));
+    for j in 0..K {
+        let mut i = index::<TAU>(msg, j) as usize;
+        sig.extend_from_slice(&secret(secret_seed, i as u64));
+        for level in &levels[..TAU] {
+            sig.extend_from_slice(&level[i ^ 1]);
+            i /= 2;
+        }
+    }
+    sig
+}
+
+/// All levels of the tree, leaves first, root last.
+#[cfg(feature = "std")]
+fn levels<const TAU: usize>(secret_seed: &[u8; 32]) -> std::vec::Vec<std::vec::Vec<[u8; 32]>> {
+    let leaves: std::vec::Vec<[u8; 32]> = (0..1u64 << TAU)
+        .map(|i| {
+            let mut leaf = [0u8; 32];
+            haraka256_domain::<ROUNDS, _>(&mut leaf, LEAF, &secret(secret_seed, i));
+            leaf
+        })
+        .collect();
+    let mut levels = std::vec![leaves];
+    while levels[levels.len() - 1].len() > 1 {
+        let next = levels[levels.len() - 1]
+            .chunks_exact(2)
+            .map(|pair| hash_node(&pair[0], &pair[1]))
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Secret value of leaf `i`.
+#[cfg(feature = "std")]
+fn secret(secret_seed: &[u8; 32], i: u64) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    block[..32].copy_from_slice(secret_seed);
+    block[32..40].copy_from_slice(&i.to_le_bytes());
+    let mut out = [0u8; 32];
+    crate::domain::haraka512_domain::<ROUNDS, _>(&mut out, SECRET_TAG, &block);
+    out
+}
+
+#[inline(always)]
+fn check_params<const TAU: usize, const K: usize>() {
+    const {
+        assert!(1 <= TAU && TAU <= 32, "HORST trees are 1 to 32 levels high");
+        assert!(K * TAU <= 256, "K indices of TAU bits must fit in 256 bits");
+    }
+}
+
+/// Index `j`: bits `j * TAU` to `(j + 1) * TAU` of `msg`, most significant
+/// first.
+#[inline(always)]
+fn index<const TAU: usize>(msg: &[u8; 32], j: usize) -> u64 {
+    let mut index = 0;
+    for bit in j * TAU..(j + 1) * TAU {
+        index = index << 1 | (msg[bit / 8] >> (7 - bit % 8)) as u64 & 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    const SECRET_SEED: [u8; 32] = [0x96; 32];
+
+    #[test]
+    fn test_index() {
+        let mut msg = [0u8; 32];
+        msg[0] = 0b1011_0010;
+        msg[1] = 0b0111_0000;
+        assert_eq!(index::<4>(&msg, 0), 0b1011);
+        assert_eq!(index::<4>(&msg, 1), 0b0010);
+        assert_eq!(index::<3>(&msg, 2), 0b100);
+        assert_eq!(index::<12>(&msg, 0), 0b1011_0010_0111);
+        assert_eq!(index::<8>(&[0xff; 32], 31), 0xff);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_sign_verify() {
+        let root = public_key::<4>(&SECRET_SEED);
+        for msg in [[0u8; 32], [0xff; 32], *b"an arbitrary 32-byte message...."] {
+            let sig = sign::<4, 8>(&SECRET_SEED, &msg);
+            assert_eq!(sig.len(), signature_len(4, 8));
+            assert!(verify::<4, 8>(&root, &msg, &sig));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_rejects_tampering() {
+        let root = public_key::<4>(&SECRET_SEED);
+        let msg = *b"an arbitrary 32-byte message....";
+        let sig = sign::<4, 8>(&SECRET_SEED, &msg);
+
+        let mut other = msg;
+        other[3] ^= 0x10;
+        assert!(!verify::<4, 8>(&root, &other, &sig));
+        assert!(!verify::<4, 8>(&public_key::<4>(&[0; 32]), &msg, &sig));
+        // Flip a bit of the first secret, a sibling and the last sibling.
+        for i in [0, 40, sig.len() - 1] {
+            let mut forged = sig.clone();
+            forged[i] ^= 1;
+            assert!(!verify::<4, 8>(&root, &msg, &forged));
+        }
+        assert!(!verify::<4, 8>(&root, &msg, &sig[..sig.len() - 32]));
+    }
+}
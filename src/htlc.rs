@@ -0,0 +1,78 @@
+//! Hash locks for hashed timelock contracts.
+//!
+//! A lock is the Haraka-256 digest of a 32-byte preimage, with round
+//! constants tweaked by a fixed domain tag:
+//!
+//! ```text
+//! hashlock(preimage) = haraka256_domain(HASHLOCK_TAG, preimage)
+//! ```
+//!
+//! Programs that agree on this tag recognize each other's locks, so a
+//! swap can lock funds on one side and release them on the other with the
+//! same preimage. The tag keeps locks apart from plain Haraka-256 digests
+//! and from the crate's other domains: revealing a preimage never opens a
+//! commitment or a Merkle leaf made from the same bytes.
+
+use crate::ct;
+use crate::domain::haraka256_domain;
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// Domain tag of every hash lock.
+const HASHLOCK_TAG: &[u8; 15] = b"haraka-bpf/htlc";
+
+/// Returns the lock that `preimage` opens.
+pub fn hashlock(preimage: &[u8; 32]) -> [u8; 32] {
+    let mut lock = [0u8; 32];
+    haraka256_domain::<ROUNDS, _>(&mut lock, HASHLOCK_TAG, preimage);
+    lock
+}
+
+/// Checks that `preimage` opens `lock`.
+///
+/// The digest comparison is constant-time.
+pub fn verify_preimage(lock: &[u8; 32], preimage: &[u8; 32]) -> bool {
+    ct::eq(lock, &hashlock(preimage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector() {
+        // This crate's own vector.
+        let expect = [
+            0x43, 0x72, 0xb6, 0x82, 0xa7, 0xec, 0x9d, 0xeb, 0xe3, 0x7c, 0xdf, 0xb1, 0xb8, 0xc7,
+            0xe0, 0xd8, 0xc0, 0x31, 0x3f, 0xd6, 0xf4, 0x12, 0x3c, 0xc5, 0x93, 0x5e, 0xdd, 0x0d,
+            0xf4, 0xdb, 0x51, 0x52,
+        ];
+        assert_eq!(hashlock(&[0x42; 32]), expect);
+    }
+
+    #[test]
+    fn test_verify_preimage() {
+        let preimage = *b"the secret that releases a swap.";
+        let lock = hashlock(&preimage);
+        assert!(verify_preimage(&lock, &preimage));
+        let mut other = preimage;
+        other[31] ^= 1;
+        assert!(!verify_preimage(&lock, &other));
+        let mut other_lock = lock;
+        other_lock[0] ^= 1;
+        assert!(!verify_preimage(&other_lock, &preimage));
+    }
+
+    #[test]
+    fn test_separated_from_other_digests() {
+        let preimage = [7u8; 32];
+        let lock = hashlock(&preimage);
+        let mut plain = [0u8; 32];
+        crate::haraka256::haraka256::<ROUNDS>(&mut plain, &preimage);
+        assert_ne!(lock, plain);
+        let mut leaf = [0u8; 32];
+        haraka256_domain::<ROUNDS, _>(&mut leaf, crate::domain::LEAF, &preimage);
+        assert_ne!(lock, leaf);
+        assert_ne!(lock, crate::commit::commit(&[], &preimage));
+    }
+}
@@ -0,0 +1,166 @@
+//! `std::io` adapters around the Haraka-S sponge.
+
+use crate::sponge::HarakaS;
+use std::io;
+
+/// An [`io::Write`] sink that absorbs everything written to it into a
+/// [`HarakaS`] hasher.
+///
+/// This lets serializers that write to a stream, such as
+/// `serde_json::to_writer` or `bincode::serialize_into`, be hashed without
+/// building the encoding in memory first. Writes never fail.
+#[derive(Clone, Default)]
+pub struct HarakaWriter {
+    hasher: HarakaS,
+}
+
+impl HarakaWriter {
+    /// Creates a writer around a fresh hasher.
+    pub const fn new() -> Self {
+        Self {
+            hasher: HarakaS::new(),
+        }
+    }
+
+    /// Returns the 32-byte Haraka-S digest of everything written so far.
+    pub fn finalize(self) -> [u8; 32] {
+        self.hasher.finalize()
+    }
+
+    /// Returns the underlying hasher, e.g. to squeeze more than 32 bytes.
+    pub fn into_inner(self) -> HarakaS {
+        self.hasher
+    }
+}
+
+impl From<HarakaS> for HarakaWriter {
+    fn from(hasher: HarakaS) -> Self {
+        Self { hasher }
+    }
+}
+
+impl io::Write for HarakaWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.hasher.update(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Size of the stack buffer used by [`hash_reader`].
+const READ_BUF_LEN: usize = 4096;
+
+/// Streams `reader` to the end through a [`HarakaS`] hasher and returns the
+/// 32-byte digest.
+///
+/// Data is copied through a fixed 4 KiB stack buffer, so memory use does
+/// not grow with the input. Interrupted reads are retried; any other read
+/// error is returned.
+pub fn hash_reader<R: io::Read>(mut reader: R) -> io::Result<[u8; 32]> {
+    let mut hasher = HarakaS::new();
+    let mut buf = [0u8; READ_BUF_LEN];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(hasher.finalize()),
+            Ok(n) => hasher.update(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_writer_matches_hasher() {
+        let mut writer = HarakaWriter::new();
+        write!(writer, "haraka-{}", 512).unwrap();
+        writer.write_all(&[0u8; 70]).unwrap();
+
+        let mut hasher = HarakaS::new();
+        hasher.update(b"haraka-512");
+        hasher.update(&[0u8; 70]);
+        assert_eq!(writer.finalize(), hasher.finalize());
+    }
+
+    #[test]
+    fn test_writer_from_hasher() {
+        let mut hasher = HarakaS::new();
+        hasher.update(b"prefix");
+        let mut writer = HarakaWriter::from(hasher);
+        writer.write_all(b"suffix").unwrap();
+
+        let mut expect = [0u8; 32];
+        crate::sponge::haraka_s(&mut expect, b"prefixsuffix");
+        assert_eq!(writer.finalize(), expect);
+    }
+
+    #[test]
+    fn test_hash_reader_matches_one_shot() {
+        let data: std::vec::Vec<u8> = (0..3 * READ_BUF_LEN + 17).map(|i| i as u8).collect();
+        let mut expect = [0u8; 32];
+        crate::sponge::haraka_s(&mut expect, &data);
+        assert_eq!(hash_reader(&data[..]).unwrap(), expect);
+    }
+
+    #[test]
+    fn test_hash_reader_empty() {
+        let mut expect = [0u8; 32];
+        crate::sponge::haraka_s(&mut expect, &[]);
+        assert_eq!(hash_reader(io::empty()).unwrap(), expect);
+    }
+
+    /// Returns one byte per call and fails with `Interrupted` in between.
+    struct Flaky<'a> {
+        data: &'a [u8],
+        interrupt: bool,
+    }
+
+    impl io::Read for Flaky<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            let n = self.data.len().min(1).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_hash_reader_retries_interrupted() {
+        let data = [0x5au8; 45];
+        let reader = Flaky {
+            data: &data,
+            interrupt: false,
+        };
+        let mut expect = [0u8; 32];
+        crate::sponge::haraka_s(&mut expect, &data);
+        assert_eq!(hash_reader(reader).unwrap(), expect);
+    }
+
+    #[test]
+    fn test_hash_reader_propagates_errors() {
+        struct Broken;
+        impl io::Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+        }
+        let err = hash_reader(Broken).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
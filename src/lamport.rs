@@ -0,0 +1,133 @@
+//! Lamport one-time signatures over Haraka-256.
+//!
+//! A key signs one 32-byte message. Its secret is 512 preimages, two per
+//! message bit, and its public key their 512 Haraka-256 digests: value
+//! `2 * i + b` belongs to bit `i` set to `b`, where bit `i` of the message
+//! is bit `i % 8` of byte `i / 8`. A signature reveals the preimage of
+//! every bit's value, so verification is exactly 256 Haraka-256 calls,
+//! whatever the message and whether or not the signature is valid.
+//!
+//! Public keys are 16 KiB and signatures 8 KiB, too large for a
+//! transaction but not for account data; the verifier borrows both as
+//! arrays. Key generation and signing derive the preimages from a 32-byte
+//! secret seed as
+//!
+//! ```text
+//! haraka512_domain(KEY, secret_seed || u16le(2 * i + b) || 0^30)
+//! ```
+//!
+//! and need the `std` feature.
+
+use crate::haraka256::haraka256;
+use arrayref::array_ref;
+
+/// Number of message bits.
+const BITS: usize = 256;
+/// Length of a public key: two digests per message bit.
+pub const PUBLIC_KEY_LEN: usize = 2 * 32 * BITS;
+/// Length of a signature: one preimage per message bit.
+pub const SIGNATURE_LEN: usize = 32 * BITS;
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// Returns `true` if `sig` is a signature of `msg` under `public_key`.
+///
+/// Every preimage is hashed and compared in constant time, so the cost
+/// does not depend on where a forged signature goes wrong.
+pub fn verify(
+    public_key: &[u8; PUBLIC_KEY_LEN],
+    msg: &[u8; 32],
+    sig: &[u8; SIGNATURE_LEN],
+) -> bool {
+    let mut valid = true;
+    for i in 0..BITS {
+        let value = 2 * i + bit(msg, i);
+        let mut digest = [0u8; 32];
+        haraka256::<ROUNDS>(&mut digest, array_ref![sig, 32 * i, 32]);
+        valid &= crate::ct::eq(&digest, array_ref![public_key, 32 * value, 32]);
+    }
+    valid
+}
+
+/// Derives the public key of `secret_seed`.
+#[cfg(feature = "std")]
+pub fn public_key(secret_seed: &[u8; 32]) -> std::boxed::Box<[u8; PUBLIC_KEY_LEN]> {
+    let mut public_key = std::boxed::Box::new([0u8; PUBLIC_KEY_LEN]);
+    for value in 0..2 * BITS {
+        let digest = arrayref::array_mut_ref![public_key, 32 * value, 32];
+        haraka256::<ROUNDS>(digest, &secret(secret_seed, value));
+    }
+    public_key
+}
+
+/// Signs `msg` with the key of `secret_seed`.
+///
+/// A key must sign a single message: a second signature reveals preimages
+/// of both values of every bit where the messages differ.
+#[cfg(feature = "std")]
+pub fn sign(secret_seed: &[u8; 32], msg: &[u8; 32]) -> std::boxed::Box<[u8; SIGNATURE_LEN]> {
+    let mut sig = std::boxed::Box::new([0u8; SIGNATURE_LEN]);
+    for (i, preimage) in sig.chunks_exact_mut(32).enumerate() {
+        preimage.copy_from_slice(&secret(secret_seed, 2 * i + bit(msg, i)));
+    }
+    sig
+}
+
+/// Preimage of value `value`.
+#[cfg(feature = "std")]
+fn secret(secret_seed: &[u8; 32], value: usize) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    block[..32].copy_from_slice(secret_seed);
+    block[32..34].copy_from_slice(&(value as u16).to_le_bytes());
+    let mut out = [0u8; 32];
+    crate::domain::haraka512_domain::<ROUNDS, _>(&mut out, crate::domain::KEY, &block);
+    out
+}
+
+/// Bit `i` of `msg`, as 0 or 1.
+#[inline(always)]
+fn bit(msg: &[u8; 32], i: usize) -> usize {
+    (msg[i / 8] >> (i % 8)) as usize & 1
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    const SECRET_SEED: [u8; 32] = [0x3c; 32];
+
+    #[test]
+    fn test_sign_verify() {
+        let pk = public_key(&SECRET_SEED);
+        for msg in [[0u8; 32], [0xff; 32], *b"an arbitrary 32-byte message...."] {
+            assert!(verify(&pk, &msg, &sign(&SECRET_SEED, &msg)));
+        }
+    }
+
+    #[test]
+    fn test_signature_reveals_chosen_preimages() {
+        let mut msg = [0u8; 32];
+        msg[0] = 0b10;
+        let sig = sign(&SECRET_SEED, &msg);
+        // Bit 0 is clear and bit 1 set: values 0 and 3.
+        assert_eq!(array_ref![sig, 0, 32], &secret(&SECRET_SEED, 0));
+        assert_eq!(array_ref![sig, 32, 32], &secret(&SECRET_SEED, 3));
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let pk = public_key(&SECRET_SEED);
+        let msg = *b"an arbitrary 32-byte message....";
+        let sig = sign(&SECRET_SEED, &msg);
+        for i in [0, 100, 255] {
+            let mut other = msg;
+            other[i / 8] ^= 1 << (i % 8);
+            assert!(!verify(&pk, &other, &sig));
+
+            let mut forged = sig.clone();
+            forged[32 * i] ^= 1;
+            assert!(!verify(&pk, &msg, &forged));
+        }
+        assert!(!verify(&public_key(&[0; 32]), &msg, &sig));
+    }
+}
@@ -1,26 +1,361 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "experimental")]
+pub mod aead;
+mod algorithm;
+pub mod backend;
+#[cfg(feature = "base58")]
+mod base58;
+mod batch;
+mod block;
+pub mod bloom;
+mod chain;
+mod commit;
+pub mod compressed;
 mod constants;
+mod ct;
+#[cfg(all(
+    test,
+    feature = "ct-check",
+    target_arch = "x86_64",
+    not(feature = "sbox-table"),
+    not(feature = "small-code")
+))]
+mod ct_check;
+pub mod cuckoo;
+mod digest;
+#[cfg(all(
+    test,
+    any(
+        feature = "ct-check",
+        feature = "no-panic-check",
+        feature = "word-ops-check"
+    ),
+    target_arch = "x86_64"
+))]
+mod disasm;
+pub mod domain;
+mod drbg;
+#[cfg(test)]
+mod edge_cases;
+mod encoding;
+mod error;
+mod event;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod gpu;
 mod haraka256;
 mod haraka512;
 mod haraka512_keyed; // Add new module
+mod hasher;
+pub mod horst;
+mod htlc;
+mod instruction;
+#[cfg(feature = "std")]
+mod io;
+mod kdf;
+#[cfg(feature = "experimental")]
+pub mod keystream;
+pub mod lamport;
+mod merkle;
+mod message;
+#[cfg(all(test, feature = "no-panic-check", target_arch = "x86_64"))]
+mod no_panic;
+mod nonce;
+mod params;
+mod personal;
+mod pow;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(test)]
+mod reference;
+mod scalar;
+#[cfg(feature = "scratchpad")]
+pub mod scratchpad;
+mod short;
 mod simd128;
+mod simd256;
+pub mod sphincs;
+mod sponge;
+mod state;
+#[cfg(all(test, not(miri)))]
+mod stats;
+#[cfg(feature = "trace")]
+pub mod trace;
+mod transcript;
+#[cfg(feature = "testvectors")]
+pub mod vectors;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(all(test, feature = "word-ops-check", target_arch = "x86_64"))]
+mod word_ops;
+pub mod wots;
+pub mod xmss;
+
+pub use algorithm::Haraka;
+pub use backend::HarakaBackend;
+#[cfg(feature = "base58")]
+pub use base58::Base58;
+pub use block::{Block32, Block64};
+pub use digest::Digest;
+pub use domain::DomainTag;
+pub use drbg::HarakaDrbg;
+pub use error::Error;
+pub use haraka512_keyed::Haraka512Keyed;
+pub use hasher::{HarakaBuildHasher, HarakaHasher};
+#[cfg(feature = "std")]
+pub use io::HarakaWriter;
+pub use merkle::MerkleVerifyState;
+pub use params::{HarakaV2, DEFAULT_ROUNDS};
+pub use personal::Personalization;
+pub use sponge::{HarakaS, HarakaSReader};
+pub use state::Haraka512State;
+pub use transcript::Transcript;
 
 pub fn haraka256<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 32]) {
     haraka256::haraka256::<{ N_ROUNDS }>(dst, src)
 }
 
+/// Computes Haraka-256 and returns the digest as four big-endian 64-bit words.
+///
+/// This saves SBF programs from re-assembling words out of the digest bytes
+/// when they only need to compare or inspect them. See [`Digest::to_words`].
+pub fn haraka256_words<const N_ROUNDS: usize>(src: &[u8; 32]) -> [u64; 4] {
+    let mut dst = [0u8; 32];
+    haraka256::haraka256::<{ N_ROUNDS }>(&mut dst, src);
+    digest::words(&dst)
+}
+
 pub fn haraka512<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 64]) {
     haraka512::haraka512::<{ N_ROUNDS }>(dst, src)
 }
 
+/// Computes Haraka-256 and writes the digest into `buf` at `offset`, such as
+/// a field of account data, without a temporary digest and copy. Fails if
+/// the 32 bytes at `offset` do not fit in `buf`, leaving it untouched.
+/// See `haraka256::haraka256_into`.
+pub fn haraka256_into<const N_ROUNDS: usize>(
+    buf: &mut [u8],
+    offset: usize,
+    src: &[u8; 32],
+) -> Result<(), Error> {
+    haraka256::haraka256_into::<{ N_ROUNDS }>(buf, offset, src)
+}
+
+/// Computes Haraka-256 with each 8-byte word of the digest stored
+/// big-endian, for implementations that serialize the lanes as 64-bit
+/// integers in the opposite byte order.
+/// See `haraka256::haraka256_be`.
+pub fn haraka256_be<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 32]) {
+    haraka256::haraka256_be::<{ N_ROUNDS }>(dst, src)
+}
+
+/// Computes Haraka-512 with each 8-byte word of the truncated output stored
+/// big-endian.
+/// See `haraka512::haraka512_be`.
+pub fn haraka512_be<const N_ROUNDS: usize>(dst: &mut [u8; 32], src: &[u8; 64]) {
+    haraka512::haraka512_be::<{ N_ROUNDS }>(dst, src)
+}
+
+/// Computes Haraka-256 with N_ROUNDS rounds under an 8 to 16 byte
+/// personalization string.
+///
+/// The string is folded into the round constants, so independent protocols
+/// get unrelated hash functions. Fails if `personal` has an unsupported
+/// length. Use [`Personalization`] to reuse the derived constants.
+/// See `personal::Personalization::haraka256`.
+pub fn haraka256_personal<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    src: &[u8; 32],
+    personal: &[u8],
+) -> Result<(), Error> {
+    Personalization::new(personal)?.haraka256::<{ N_ROUNDS }>(dst, src);
+    Ok(())
+}
+
+/// Computes Haraka-512 with N_ROUNDS rounds under an 8 to 16 byte
+/// personalization string. See [`haraka256_personal`].
+pub fn haraka512_personal<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    src: &[u8; 64],
+    personal: &[u8],
+) -> Result<(), Error> {
+    Personalization::new(personal)?.haraka512::<{ N_ROUNDS }>(dst, src);
+    Ok(())
+}
+
+/// Computes Haraka-256 with N_ROUNDS rounds in the domain `tag`.
+///
+/// The tag, a byte or an array of at most 16 bytes, is folded into the
+/// round constants, so digests from different domains (e.g. the
+/// [`domain::LEAF`] and [`domain::NODE`] levels of a Merkle tree) can never
+/// be confused. See `domain::haraka256_domain`.
+pub fn haraka256_domain<const N_ROUNDS: usize, T: DomainTag>(
+    dst: &mut [u8; 32],
+    tag: T,
+    src: &[u8; 32],
+) {
+    domain::haraka256_domain::<{ N_ROUNDS }, T>(dst, tag, src)
+}
+
+/// Computes Haraka-512 with N_ROUNDS rounds in the domain `tag`.
+/// See [`haraka256_domain`].
+pub fn haraka512_domain<const N_ROUNDS: usize, T: DomainTag>(
+    dst: &mut [u8; 32],
+    tag: T,
+    src: &[u8; 64],
+) {
+    domain::haraka512_domain::<{ N_ROUNDS }, T>(dst, tag, src)
+}
+
+/// Computes a 16-byte Haraka-256 digest with its own domain separation.
+///
+/// Meant for space-constrained account fields; the output is not a prefix
+/// of the [`haraka256`] digest. See `short::haraka256_short`.
+pub fn haraka256_short<const N_ROUNDS: usize>(src: &[u8; 32]) -> [u8; 16] {
+    short::haraka256_short::<{ N_ROUNDS }>(src)
+}
+
+/// Computes an 8-byte Haraka-256 tag with its own domain separation, e.g.
+/// for deduplication keys. See `short::haraka_tag64`.
+pub fn haraka_tag64<const N_ROUNDS: usize>(src: &[u8; 32]) -> [u8; 8] {
+    short::haraka_tag64::<{ N_ROUNDS }>(src)
+}
+
+/// Computes Haraka-512 with N_ROUNDS rounds of the 64-byte message
+/// `a || b || c || d`.
+///
+/// Each 16-byte limb is loaded directly into its lane, so inputs that are
+/// already split (e.g. four field elements) need no 64-byte buffer.
+/// See `haraka512::haraka512_limbs`.
+pub fn haraka512_limbs<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    a: &[u8; 16],
+    b: &[u8; 16],
+    c: &[u8; 16],
+    d: &[u8; 16],
+) {
+    haraka512::haraka512_limbs::<{ N_ROUNDS }>(dst, a, b, c, d)
+}
+
+/// Computes Haraka-512 with N_ROUNDS rounds of the concatenation of
+/// `parts`, e.g. `owner || mint || amount` taken straight from account
+/// fields.
+///
+/// Fails without writing `dst` unless the parts total exactly 64 bytes.
+/// See `haraka512::haraka512_gather`.
+pub fn haraka512_gather<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    parts: &[&[u8]],
+) -> Result<(), Error> {
+    haraka512::haraka512_gather::<{ N_ROUNDS }>(dst, parts)
+}
+
+/// Applies the Haraka-512 permutation with N_ROUNDS rounds to `src`.
+///
+/// Unlike [`haraka512`], the full 64-byte state is written to `dst`, with
+/// neither feed-forward nor truncation. This is the `haraka512_perm`
+/// primitive of the SPHINCS+ reference code, used to build sponges.
+pub fn haraka512_perm<const N_ROUNDS: usize>(dst: &mut [u8; 64], src: &[u8; 64]) {
+    haraka512::haraka512_perm::<{ N_ROUNDS }>(dst, src)
+}
+
+/// Hashes a message of any length with the Haraka-S sponge and fills `out`
+/// with as many output bytes as it can hold.
+///
+/// This is the `haraka_S` function of SPHINCS+ with the standard round
+/// constants. Use [`HarakaS`] to absorb the input incrementally.
+/// See `sponge::haraka_s`.
+pub fn haraka_s(out: &mut [u8], input: &[u8]) {
+    sponge::haraka_s(out, input)
+}
+
+/// Returns the 32-byte Haraka-S digest of `msg` as a vector, for host-side
+/// tooling. See `sponge::haraka_s_vec`.
+#[cfg(feature = "alloc")]
+pub fn haraka_s_vec(msg: &[u8]) -> alloc::vec::Vec<u8> {
+    sponge::haraka_s_vec(msg)
+}
+
+/// Hashes each message of any length with Haraka-S, returning the 32-byte
+/// digests in order. See `sponge::hash_many`.
+#[cfg(feature = "alloc")]
+pub fn hash_many(msgs: &[alloc::vec::Vec<u8>]) -> alloc::vec::Vec<[u8; 32]> {
+    sponge::hash_many(msgs)
+}
+
+/// Reads `reader` to the end and returns its Haraka-S digest.
+///
+/// The input is streamed through a fixed-size buffer, which suits large
+/// off-chain artifacts such as files or sockets. See `io::hash_reader`.
+#[cfg(feature = "std")]
+pub fn hash_reader<R: std::io::Read>(reader: R) -> std::io::Result<[u8; 32]> {
+    io::hash_reader(reader)
+}
+
+/// Hashes `msg` to a uniformly distributed curve25519 scalar.
+///
+/// 64 bytes of domain-separated Haraka-S output are reduced modulo the
+/// group order `l` in constant time. The scalar is returned as 32
+/// little-endian bytes, the encoding used by ed25519 and ristretto255.
+/// See `scalar::hash_to_scalar`.
+pub fn hash_to_scalar(msg: &[u8]) -> [u8; 32] {
+    scalar::hash_to_scalar(msg)
+}
+
+/// Hashes `count` tightly packed 64-byte messages from `src` into tightly
+/// packed 32-byte digests in `dst`.
+///
+/// Fails without writing anything if either buffer is too short.
+/// See `batch::haraka512_batch`.
+pub fn haraka512_batch<const N_ROUNDS: usize>(
+    dst: &mut [u8],
+    src: &[u8],
+    count: usize,
+) -> Result<(), Error> {
+    batch::haraka512_batch::<{ N_ROUNDS }>(dst, src, count)
+}
+
+/// Like [`haraka512_batch`], but message `i` starts at `i * src_stride` and
+/// digest `i` at `i * dst_stride`.
+///
+/// This matches interleaved layouts such as GPU result buffers that carry a
+/// nonce or status word next to each digest. Strides must be at least the
+/// element size (64 bytes for messages, 32 bytes for digests).
+/// See `batch::haraka512_batch_strided`.
+pub fn haraka512_batch_strided<const N_ROUNDS: usize>(
+    dst: &mut [u8],
+    dst_stride: usize,
+    src: &[u8],
+    src_stride: usize,
+    count: usize,
+) -> Result<(), Error> {
+    batch::haraka512_batch_strided::<{ N_ROUNDS }>(dst, dst_stride, src, src_stride, count)
+}
+
+/// Hashes each 64-byte message with Haraka-512, spreading the work over the
+/// rayon thread pool.
+///
+/// Digests are returned in the same order as the messages.
+/// See `batch::par_hash_many`.
+#[cfg(feature = "rayon")]
+pub fn par_hash_many<const N_ROUNDS: usize>(msgs: &[[u8; 64]]) -> alloc::vec::Vec<[u8; 32]> {
+    batch::par_hash_many::<{ N_ROUNDS }>(msgs)
+}
+
 /// Computes the keyed Haraka-512 permutation with N_ROUNDS rounds.
 ///
 /// The 64-byte `state` is XORed with the 64-byte `key`, permuted using
 /// `N_ROUNDS` of the Haraka-512 round function, then XORed with the
-/// *post-key* state (feed-forward step). The result is truncated to 32 bytes
-/// and written to `dst` in little-endian format (specifically, the high 64 bits
-/// of the first two 128-bit lanes after permutation and feed-forward).
+/// *post-key* state (feed-forward step), so the digest equals
+/// `haraka512(state ^ key)`. The result is truncated to 32 bytes exactly as in
+/// `haraka512`: the high 64 bits of the first two 128-bit lanes followed by
+/// the low 64 bits of the last two.
 ///
 /// See `haraka512_keyed::haraka512_keyed` for implementation details.
 pub fn haraka512_keyed<const N_ROUNDS: usize>(
@@ -30,3 +365,215 @@ pub fn haraka512_keyed<const N_ROUNDS: usize>(
 ) {
     haraka512_keyed::haraka512_keyed::<{ N_ROUNDS }>(dst, state, key)
 }
+
+/// Returns the number of leading zero bits of `digest`.
+///
+/// The digest is interpreted as a big-endian 256-bit integer, which is the
+/// usual convention for proof-of-work difficulty. See `pow::difficulty`.
+pub fn difficulty(digest: &[u8; 32]) -> u32 {
+    pow::difficulty(digest)
+}
+
+/// Checks whether `digest` meets a proof-of-work `target`.
+///
+/// Returns `true` if `digest <= target`, comparing both as big-endian 256-bit
+/// integers. The comparison runs in constant time with respect to the digest
+/// and target values. See `pow::meets_target`.
+pub fn meets_target(digest: &[u8; 32], target: &[u8; 32]) -> bool {
+    pow::meets_target(digest, target)
+}
+
+/// Searches `count` consecutive nonces, starting at `start_nonce`, for one
+/// whose Haraka-512 digest of `prefix || nonce_le` satisfies `predicate`.
+///
+/// Returns the first matching nonce, if any. The prefix lanes are loaded
+/// once and only the nonce bytes are updated between attempts, which makes
+/// this considerably cheaper than rebuilding the 64-byte message each time.
+/// See `pow::grind`.
+pub fn grind<const N_ROUNDS: usize, F>(
+    prefix: &[u8; 56],
+    start_nonce: u64,
+    count: u64,
+    predicate: F,
+) -> Option<u64>
+where
+    F: FnMut(&[u8; 32]) -> bool,
+{
+    pow::grind::<{ N_ROUNDS }, F>(prefix, start_nonce, count, predicate)
+}
+
+/// Hashes a variable-length message `msg` into `dst`.
+///
+/// The message is padded with a domain byte and its length, then hashed
+/// 32 bytes at a time with Haraka-512 as a Merkle-Damgard compression
+/// function. This suits short inputs such as memos or names; use
+/// [`HarakaS`] for long or incrementally produced ones.
+/// See `message::haraka256_msg`.
+pub fn haraka256_msg<const N_ROUNDS: usize>(dst: &mut [u8; 32], msg: &[u8]) {
+    message::haraka256_msg::<{ N_ROUNDS }>(dst, msg)
+}
+
+/// Folds 32-byte chunks, e.g. a list of votes or digests, into one digest
+/// with `acc = haraka512(acc || chunk)` from an all-zero accumulator.
+///
+/// Works on any iterator without allocating. See `chain::haraka_fold`.
+pub fn haraka_fold<const N_ROUNDS: usize>(chunks: impl Iterator<Item = [u8; 32]>) -> [u8; 32] {
+    chain::haraka_fold::<{ N_ROUNDS }>(chunks)
+}
+
+/// Applies Haraka-256 to `start` `n` times in a row and returns the result.
+///
+/// This is the building block of hash locks and iterated key stretching.
+/// The intermediate values are never written back to memory, so long chains
+/// are noticeably cheaper than calling [`haraka256`] in a loop.
+/// See `chain::hash_chain`.
+pub fn hash_chain<const N_ROUNDS: usize>(start: &[u8; 32], n: u64) -> [u8; 32] {
+    chain::hash_chain::<{ N_ROUNDS }>(start, n)
+}
+
+/// Checks that iterating Haraka-256 `steps` times from `from` yields `to`.
+///
+/// Long chains can be verified piecewise between checkpoints, amortizing the
+/// cost across instructions or transactions. The digest comparison is
+/// constant-time. See `chain::verify_chain_segment`.
+pub fn verify_chain_segment<const N_ROUNDS: usize>(
+    from: &[u8; 32],
+    to: &[u8; 32],
+    steps: u64,
+) -> bool {
+    chain::verify_chain_segment::<{ N_ROUNDS }>(from, to, steps)
+}
+
+/// Stretches `password_or_seed` with `salt` into a 32-byte key.
+///
+/// The secret is hashed into a key for keyed Haraka-512, which is then
+/// iterated `iterations` times from a start value binding the salt and
+/// the count. The result is reproducible everywhere, so a seed stretched
+/// off-chain can be re-derived by an on-chain program at one Haraka-512
+/// call per iteration. See `kdf::kdf_iter`.
+pub fn kdf_iter(password_or_seed: &[u8], salt: &[u8], iterations: u64) -> [u8; 32] {
+    kdf::kdf_iter(password_or_seed, salt, iterations)
+}
+
+/// Returns the hash lock that `preimage` opens, for hashed timelock
+/// contracts.
+///
+/// The lock is Haraka-256 of the preimage under a fixed domain tag, so
+/// every program using this crate computes the same lock and locks never
+/// collide with the crate's other digests. See `htlc::hashlock`.
+pub fn hashlock(preimage: &[u8; 32]) -> [u8; 32] {
+    htlc::hashlock(preimage)
+}
+
+/// Checks that `preimage` opens the [`hashlock`] `lock`, comparing digests
+/// in constant time. See `htlc::verify_preimage`.
+pub fn verify_preimage(lock: &[u8; 32], preimage: &[u8; 32]) -> bool {
+    htlc::verify_preimage(lock, preimage)
+}
+
+/// Commits to `value` under the secret 32-byte `blinding` factor.
+///
+/// The commitment is `HarakaS(domain || blinding || value)` with a fixed
+/// 32-byte domain block, so every program using this crate computes the
+/// same commitment for the same opening. See `commit::commit`.
+pub fn commit(value: &[u8], blinding: &[u8; 32]) -> [u8; 32] {
+    commit::commit(value, blinding)
+}
+
+/// Checks that `commitment` was produced by [`commit`] from `value` and
+/// `blinding`, comparing digests in constant time.
+/// See `commit::verify_commit`.
+pub fn verify_commit(commitment: &[u8; 32], value: &[u8], blinding: &[u8; 32]) -> bool {
+    commit::verify_commit(commitment, value, blinding)
+}
+
+/// Hashes instruction `data` for the program `program_id`.
+///
+/// The digest is `HarakaS(domain || program_id || data)` with a fixed
+/// 32-byte domain block, giving programs that commit to instructions (e.g.
+/// governance proposals or multisig transactions) one shared encoding.
+/// See `instruction::hash_instruction`.
+pub fn hash_instruction(program_id: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    instruction::hash_instruction(program_id, data)
+}
+
+/// Commits to an event `payload` of type `discriminator`.
+///
+/// The commitment is `HarakaS(domain || discriminator || payload)` with a
+/// fixed 32-byte domain block. A program can log the 32-byte commitment of
+/// a large event, and an off-chain indexer holding the event recomputes the
+/// same bytes with this function. See `event::commit_event`.
+pub fn commit_event(discriminator: [u8; 8], payload: &[u8]) -> [u8; 32] {
+    event::commit_event(discriminator, payload)
+}
+
+/// Derives the replay-protection commitment of `nonce`, used by `user` at
+/// `slot`.
+///
+/// The commitment is `HarakaS(domain || user || slot || nonce)` with a
+/// fixed 32-byte domain block and little-endian integers, so every program
+/// records replayed requests under the same key. See `nonce::commit_nonce`.
+pub fn commit_nonce(user: &[u8; 32], slot: u64, nonce: u64) -> [u8; 32] {
+    nonce::commit_nonce(user, slot, nonce)
+}
+
+/// Checks that `commitment` was produced by [`commit_nonce`] from `user`,
+/// `slot` and `nonce`, comparing digests in constant time.
+/// See `nonce::check_nonce`.
+pub fn check_nonce(commitment: &[u8; 32], user: &[u8; 32], slot: u64, nonce: u64) -> bool {
+    nonce::check_nonce(commitment, user, slot, nonce)
+}
+
+/// Derives 32 bytes of randomness from a recent blockhash and program
+/// `seeds`, e.g. a lottery id and round.
+///
+/// The output is `HarakaS(domain || recent_blockhash || seeds)` with a fixed
+/// 32-byte domain block and each seed prefixed by its length. It is only as
+/// unpredictable as the blockhash: the leader producing that block can
+/// choose between candidate values, so it suits low-stakes draws, or as a
+/// [`HarakaDrbg`] seed mixed with a commit-reveal. See
+/// `drbg::derive_randomness`.
+pub fn derive_randomness(recent_blockhash: &[u8; 32], seeds: &[&[u8]]) -> [u8; 32] {
+    drbg::derive_randomness(recent_blockhash, seeds)
+}
+
+/// Checks that `siblings`, ordered from the leaf up, prove `leaf` at
+/// position `index` of the Merkle tree with root `root`.
+///
+/// Inner nodes are `haraka512_domain(domain::NODE, left || right)`. Use
+/// [`MerkleVerifyState`] to spread a long proof over several instructions.
+/// See `merkle::verify_merkle_proof`.
+pub fn verify_merkle_proof(
+    root: &[u8; 32],
+    leaf: &[u8; 32],
+    index: u64,
+    siblings: &[[u8; 32]],
+) -> bool {
+    merkle::verify_merkle_proof(root, leaf, index, siblings)
+}
+
+/// Fills `out` with distinct indices drawn uniformly from `0..n`, using a
+/// [`HarakaDrbg`] seeded with `seed`.
+///
+/// On-chain and off-chain callers with the same seed get the same
+/// selection, e.g. for committee or lottery draws. Fails if `out` is longer
+/// than `n`. See `drbg::HarakaDrbg::sample_indices`.
+pub fn sample_indices(seed: &[u8; 32], n: u64, out: &mut [u64]) -> Result<(), Error> {
+    HarakaDrbg::new(seed).sample_indices(n, out)
+}
+
+/// Shuffles `items` with Fisher-Yates, driven by a [`HarakaDrbg`] seeded
+/// with `seed`. See `drbg::HarakaDrbg::shuffle`.
+pub fn shuffle<T>(seed: &[u8; 32], items: &mut [T]) {
+    HarakaDrbg::new(seed).shuffle(items)
+}
+
+/// Decodes hex text of either case into `out`, which must be half as long
+/// as `text`.
+///
+/// Fails with [`Error::InvalidLength`] on any other length and with
+/// [`Error::InvalidCharacter`] on a byte that is not a hex digit, including
+/// every byte of a non-ASCII character. See `encoding::decode_hex`.
+pub fn decode_hex(text: &str, out: &mut [u8]) -> Result<(), Error> {
+    encoding::decode_hex(text.as_bytes(), out)
+}
@@ -0,0 +1,242 @@
+//! Merkle proof verification that can be split across instructions.
+//!
+//! Inner nodes are `haraka512_domain(NODE, left || right)` (see
+//! [`domain::NODE`](crate::domain::NODE)); leaves are 32-byte digests that
+//! the caller computes, e.g. with `haraka256_domain(LEAF, ..)`. Bit `i` of
+//! the leaf index says whether the node at level `i` is a right child.
+//!
+//! [`MerkleVerifyState`] holds the current node, the remaining index bits
+//! and the number of levels consumed. Each [`advance`](MerkleVerifyState::advance)
+//! hashes in some siblings, and the 41-byte [`to_bytes`](MerkleVerifyState::to_bytes)
+//! form can be stored in an account between instructions.
+
+use crate::ct;
+use crate::domain::{haraka512_domain, NODE};
+use crate::error::Error;
+use arrayref::{array_refs, mut_array_refs};
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// The deepest supported tree: one level per bit of the leaf index.
+const MAX_DEPTH: usize = 64;
+
+/// Partial verification of a Merkle proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MerkleVerifyState {
+    node: [u8; 32],
+    index: u64,
+    progress: u8,
+}
+
+impl MerkleVerifyState {
+    /// Length of the [`to_bytes`](Self::to_bytes) encoding.
+    pub const LEN: usize = 41;
+
+    /// Starts verifying the proof of `leaf` at position `index`.
+    pub const fn new(leaf: &[u8; 32], index: u64) -> Self {
+        Self {
+            node: *leaf,
+            index,
+            progress: 0,
+        }
+    }
+
+    /// Hashes in the next `siblings`, from the bottom of the tree up.
+    ///
+    /// Fails without changing the state if the proof would exceed 64
+    /// levels.
+    pub fn advance(&mut self, siblings: &[[u8; 32]]) -> Result<(), Error> {
+        let max = MAX_DEPTH.saturating_sub(self.progress as usize);
+        if siblings.len() > max {
+            return Err(Error::LengthOutOfRange {
+                min: 0,
+                max,
+                actual: siblings.len(),
+            });
+        }
+        let mut block = [0u8; 64];
+        for sibling in siblings {
+            let (left, right) = mut_array_refs![&mut block, 32, 32];
+            if self.index & 1 == 0 {
+                *left = self.node;
+                *right = *sibling;
+            } else {
+                *left = *sibling;
+                *right = self.node;
+            }
+            haraka512_domain::<ROUNDS, _>(&mut self.node, NODE, &block);
+            self.index >>= 1;
+        }
+        // At most 64 levels in total, so this fits.
+        self.progress += siblings.len() as u8;
+        Ok(())
+    }
+
+    /// Returns the number of siblings hashed in so far.
+    pub const fn progress(&self) -> u8 {
+        self.progress
+    }
+
+    /// Returns the current node: the root, once every sibling is in.
+    pub const fn node(&self) -> &[u8; 32] {
+        &self.node
+    }
+
+    /// Checks that the proof is complete for a tree of `depth` levels and
+    /// ends at `root`.
+    ///
+    /// The leaf index must be below `2^depth`. The root comparison is
+    /// constant-time.
+    pub fn verify(&self, root: &[u8; 32], depth: u8) -> bool {
+        self.progress == depth && self.index == 0 && ct::eq(&self.node, root)
+    }
+
+    /// Encodes the state as node, remaining index (little-endian) and
+    /// progress.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut out = [0u8; Self::LEN];
+        let (node, index, progress) = mut_array_refs![&mut out, 32, 8, 1];
+        *node = self.node;
+        *index = self.index.to_le_bytes();
+        progress[0] = self.progress;
+        out
+    }
+
+    /// Decodes a state written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Self {
+        let (node, index, progress) = array_refs![bytes, 32, 8, 1];
+        Self {
+            node: *node,
+            index: u64::from_le_bytes(*index),
+            progress: progress[0],
+        }
+    }
+}
+
+/// Inner node over `left` and `right`, for code that builds trees.
+#[cfg(feature = "std")]
+pub(crate) fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    block[..32].copy_from_slice(left);
+    block[32..].copy_from_slice(right);
+    let mut node = [0u8; 32];
+    haraka512_domain::<ROUNDS, _>(&mut node, NODE, &block);
+    node
+}
+
+/// Checks in one go that `siblings` prove `leaf` at `index` under `root`.
+pub fn verify_merkle_proof(
+    root: &[u8; 32],
+    leaf: &[u8; 32],
+    index: u64,
+    siblings: &[[u8; 32]],
+) -> bool {
+    let mut state = MerkleVerifyState::new(leaf, index);
+    state.advance(siblings).is_ok() && state.verify(root, siblings.len() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(left);
+        block[32..].copy_from_slice(right);
+        let mut out = [0u8; 32];
+        haraka512_domain::<5, _>(&mut out, NODE, &block);
+        out
+    }
+
+    /// An 8-leaf tree as its 15 nodes, leaves first.
+    fn tree() -> [[u8; 32]; 15] {
+        let mut nodes = [[0u8; 32]; 15];
+        for (i, node) in nodes[..8].iter_mut().enumerate() {
+            *node = [i as u8; 32];
+        }
+        for i in 0..7 {
+            nodes[8 + i] = hash_node(&nodes[2 * i], &nodes[2 * i + 1]);
+        }
+        nodes
+    }
+
+    fn proof(nodes: &[[u8; 32]; 15], index: usize) -> [[u8; 32]; 3] {
+        let (mut i, mut base, mut width) = (index, 0, 8);
+        let mut siblings = [[0u8; 32]; 3];
+        for sibling in &mut siblings {
+            *sibling = nodes[base + (i ^ 1)];
+            base += width;
+            width /= 2;
+            i /= 2;
+        }
+        siblings
+    }
+
+    #[test]
+    fn test_verify_every_leaf() {
+        let nodes = tree();
+        let root = nodes[14];
+        for i in 0..8 {
+            let siblings = proof(&nodes, i);
+            assert!(verify_merkle_proof(&root, &nodes[i], i as u64, &siblings));
+            assert!(!verify_merkle_proof(
+                &root,
+                &nodes[i],
+                (i ^ 1) as u64,
+                &siblings
+            ));
+            // An index past the tree wraps onto the same path.
+            assert!(!verify_merkle_proof(
+                &root,
+                &nodes[i],
+                i as u64 + 8,
+                &siblings
+            ));
+        }
+    }
+
+    #[test]
+    fn test_split_across_instructions() {
+        let nodes = tree();
+        let siblings = proof(&nodes, 5);
+        let mut state = MerkleVerifyState::new(&nodes[5], 5);
+        state.advance(&siblings[..1]).unwrap();
+        assert!(!state.verify(&nodes[14], 3));
+
+        // Store the state between instructions.
+        let mut state = MerkleVerifyState::from_bytes(&state.to_bytes());
+        assert_eq!(state.progress(), 1);
+        state.advance(&siblings[1..]).unwrap();
+        assert_eq!(state.node(), &nodes[14]);
+        assert!(state.verify(&nodes[14], 3));
+        assert!(!state.verify(&nodes[14], 4));
+    }
+
+    #[test]
+    fn test_rejects_tampered_sibling() {
+        let nodes = tree();
+        let mut siblings = proof(&nodes, 2);
+        siblings[1][0] ^= 1;
+        assert!(!verify_merkle_proof(&nodes[14], &nodes[2], 2, &siblings));
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let siblings = [[0u8; 32]; 65];
+        let mut state = MerkleVerifyState::new(&[0; 32], 0);
+        state.advance(&siblings[..60]).unwrap();
+        let before = state;
+        assert_eq!(
+            state.advance(&siblings[..5]),
+            Err(Error::LengthOutOfRange {
+                min: 0,
+                max: 4,
+                actual: 5
+            })
+        );
+        assert_eq!(state, before);
+        state.advance(&siblings[..4]).unwrap();
+        assert_eq!(state.progress(), 64);
+        assert!(!verify_merkle_proof(&[0; 32], &[0; 32], 0, &siblings));
+    }
+}
@@ -0,0 +1,127 @@
+//! One-shot hashing of short variable-length messages.
+//!
+//! The message is padded with a domain byte, zeros and its length in bytes
+//! as a little-endian `u64`, to a multiple of 32 bytes:
+//!
+//! ```text
+//! msg || 0x01 || 0^k || u64le(len(msg))
+//! ```
+//!
+//! with the smallest such `k`. Each 32-byte block `m_i` of the padded
+//! message is then compressed into a 32-byte chaining value, starting from
+//! zero: `h_{i+1} = haraka512(h_i || m_i)`. The last chaining value is the
+//! digest. Putting the length in the last block rules out collisions
+//! between messages of different lengths (Merkle-Damgard strengthening).
+
+use crate::haraka512::haraka512;
+use arrayref::mut_array_refs;
+
+/// Domain byte appended to every message.
+const DOMAIN: u8 = 0x01;
+
+/// Hashes `msg` with N_ROUNDS-round Haraka-512 as the compression function.
+pub fn haraka256_msg<const N_ROUNDS: usize>(dst: &mut [u8; 32], msg: &[u8]) {
+    let mut state = [0u8; 64];
+    let (blocks, tail) = msg.as_chunks::<32>();
+    for block in blocks {
+        compress::<N_ROUNDS>(&mut state, block);
+    }
+
+    // The tail, the domain byte and the length fill one or two blocks.
+    let mut last = [0u8; 64];
+    for (dst, src) in last.iter_mut().zip(tail) {
+        *dst = *src;
+    }
+    let (first, second) = mut_array_refs![&mut last, 32, 32];
+    let len = (msg.len() as u64).to_le_bytes();
+    first[tail.len() % 32] = DOMAIN;
+    if tail.len() < 24 {
+        first[24..].copy_from_slice(&len);
+        compress::<N_ROUNDS>(&mut state, first);
+    } else {
+        second[24..].copy_from_slice(&len);
+        compress::<N_ROUNDS>(&mut state, first);
+        compress::<N_ROUNDS>(&mut state, second);
+    }
+    dst.copy_from_slice(&state[..32]);
+}
+
+/// Replaces the chaining value in `state[..32]` with
+/// `haraka512(state[..32] || block)`.
+#[inline(always)]
+fn compress<const N_ROUNDS: usize>(state: &mut [u8; 64], block: &[u8; 32]) {
+    state[32..].copy_from_slice(block);
+    let mut next = [0u8; 32];
+    haraka512::<N_ROUNDS>(&mut next, state);
+    state[..32].copy_from_slice(&next);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h512(chain: &[u8; 32], block: &[u8; 32]) -> [u8; 32] {
+        let mut src = [0u8; 64];
+        src[..32].copy_from_slice(chain);
+        src[32..].copy_from_slice(block);
+        let mut out = [0u8; 32];
+        haraka512::<5>(&mut out, &src);
+        out
+    }
+
+    fn msg_hash(msg: &[u8]) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        haraka256_msg::<5>(&mut dst, msg);
+        dst
+    }
+
+    #[test]
+    fn test_empty_message() {
+        let mut block = [0u8; 32];
+        block[0] = DOMAIN;
+        assert_eq!(msg_hash(b""), h512(&[0; 32], &block));
+    }
+
+    #[test]
+    fn test_padding_boundaries() {
+        let msg = [0xabu8; 100];
+
+        // 23 bytes: the domain byte and length still fit in one block.
+        let mut block = [0u8; 32];
+        block[..23].copy_from_slice(&msg[..23]);
+        block[23] = DOMAIN;
+        block[24] = 23;
+        assert_eq!(msg_hash(&msg[..23]), h512(&[0; 32], &block));
+
+        // 24 bytes: the length spills into a second block.
+        let mut first = [0u8; 32];
+        first[..24].copy_from_slice(&msg[..24]);
+        first[24] = DOMAIN;
+        let mut second = [0u8; 32];
+        second[24] = 24;
+        let chain = h512(&[0; 32], &first);
+        assert_eq!(msg_hash(&msg[..24]), h512(&chain, &second));
+
+        // 32 bytes: one full block, then the padding block.
+        let mut pad = [0u8; 32];
+        pad[0] = DOMAIN;
+        pad[24] = 32;
+        let chain = h512(&[0; 32], msg[..32].try_into().unwrap());
+        assert_eq!(msg_hash(&msg[..32]), h512(&chain, &pad));
+    }
+
+    #[test]
+    fn test_lengths_are_distinct() {
+        let msg = [0u8; 80];
+        let mut seen = [[0u8; 32]; 81];
+        for (len, slot) in seen.iter_mut().enumerate() {
+            *slot = msg_hash(&msg[..len]);
+        }
+        seen.sort_unstable();
+        assert!(seen.windows(2).all(|w| w[0] != w[1]));
+        // The padding itself is not a valid message suffix.
+        let mut padded = [0u8; 32];
+        padded[0] = DOMAIN;
+        assert_ne!(msg_hash(b""), msg_hash(&padded[..1]));
+    }
+}
@@ -0,0 +1,399 @@
+//! Machine check that the public API cannot panic.
+//!
+//! Each probe is a non-inlined call of a public function on inputs the
+//! optimizer cannot see. The test disassembles the test binary and fails
+//! if any panic function is reachable from a probe, so a bounds check or
+//! `unwrap` that the optimizer cannot prove away breaks the build of this
+//! test rather than an on-chain transaction. Functions documented with a
+//! `# Panics` section are not probed.
+//!
+//! The same probes bound the call depth: the rounds, lane operations and
+//! mixes are all `#[inline(always)]`, so no public function is more than a
+//! few frames deep, and none recurses. The x86-64 depth stands in for SBF,
+//! whose inlining decisions come from the same LLVM passes.
+//!
+//! The feature selects the table AES round, which SBF programs use by
+//! default; the `aes` crate's bitsliced round keeps bounds checks of its
+//! own that the optimizer cannot remove. Run with
+//!
+//! ```text
+//! cargo test --release --features no-panic-check no_panic
+//! ```
+
+use crate::disasm::{callees, is_panic, Binary};
+use crate::{Block32, Block64, Digest, Error, HarakaS};
+use core::hint::black_box;
+use std::string::String;
+
+macro_rules! probes {
+    ($($(#[$attr:meta])* $name:ident($($arg:ident: $ty:ty),*) -> $ret:ty $body:block)*) => {
+        $(
+            $(#[$attr])*
+            #[inline(never)]
+            fn $name($($arg: $ty),*) -> $ret $body
+        )*
+
+        const PROBES: &[&str] = &[$($(#[$attr])* stringify!($name)),*];
+
+        /// Calls every probe once, so that none is removed from the binary.
+        fn run_probes() {
+            $(
+                $(#[$attr])*
+                let _ = black_box($name($(black_box(<$ty>::default())),*));
+            )*
+        }
+    };
+}
+
+probes! {
+    probe_haraka256(dst: [u8; 32], src: [u8; 32]) -> [u8; 32] {
+        let mut dst = dst;
+        crate::haraka256::<5>(&mut dst, &src);
+        dst
+    }
+    probe_haraka256_6(dst: [u8; 32], src: [u8; 32]) -> [u8; 32] {
+        let mut dst = dst;
+        crate::haraka256::<6>(&mut dst, &src);
+        dst
+    }
+    probe_haraka256_be(dst: [u8; 32], src: [u8; 32]) -> [u8; 32] {
+        let mut dst = dst;
+        crate::haraka256_be::<5>(&mut dst, &src);
+        dst
+    }
+    probe_haraka256_words(src: [u8; 32]) -> [u64; 4] {
+        crate::haraka256_words::<5>(&src)
+    }
+    probe_haraka256_short(src: [u8; 32]) -> [u8; 16] {
+        crate::haraka256_short::<5>(&src)
+    }
+    probe_haraka_tag64(src: [u8; 32]) -> [u8; 8] {
+        crate::haraka_tag64::<5>(&src)
+    }
+    probe_haraka256_into(buf: &mut [u8], offset: usize) -> Result<(), Error> {
+        crate::haraka256_into::<5>(buf, offset, &[0; 32])
+    }
+    probe_haraka512(src: Block64) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        crate::haraka512::<5>(&mut dst, src.as_bytes());
+        dst
+    }
+    probe_haraka512_be(src: Block64) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        crate::haraka512_be::<5>(&mut dst, src.as_bytes());
+        dst
+    }
+    probe_haraka512_perm(src: Block64) -> Block64 {
+        let mut dst = Block64::default();
+        crate::haraka512_perm::<5>(dst.as_mut_bytes(), src.as_bytes());
+        dst
+    }
+    probe_haraka512_keyed(src: Block64, key: Block64) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        crate::haraka512_keyed::<5>(&mut dst, src.as_bytes(), key.as_bytes());
+        dst
+    }
+    probe_keyed_object(src: Block64, key: Block64) -> [u8; 32] {
+        crate::Haraka512Keyed::new(key.as_bytes()).hash::<5>(src.as_bytes())
+    }
+    probe_block32_haraka256(src: Block32) -> (Digest, Block32) {
+        // Returns the block too, so that the probe is not merged with
+        // `probe_haraka256`, which compiles to the same code otherwise.
+        (src.haraka256::<5>(), src)
+    }
+    probe_haraka_s(out: &mut [u8], input: &[u8]) -> () {
+        crate::haraka_s(out, input)
+    }
+    probe_haraka_s_update(input: &[u8]) -> [u8; 32] {
+        let mut h = HarakaS::new();
+        h.update(input);
+        h.finalize()
+    }
+    probe_hash_to_scalar(msg: &[u8]) -> [u8; 32] {
+        crate::hash_to_scalar(msg)
+    }
+    probe_difficulty(digest: [u8; 32]) -> u32 {
+        crate::difficulty(&digest)
+    }
+    probe_meets_target(digest: [u8; 32], target: [u8; 32]) -> bool {
+        crate::meets_target(&digest, &target)
+    }
+    probe_hash_chain(start: [u8; 32], n: u64) -> [u8; 32] {
+        crate::hash_chain::<5>(&start, n)
+    }
+    probe_kdf_iter(secret: &[u8], salt: &[u8], iterations: u64) -> [u8; 32] {
+        crate::kdf_iter(secret, salt, iterations)
+    }
+    probe_verify_preimage(lock: [u8; 32], preimage: [u8; 32]) -> bool {
+        crate::verify_preimage(&lock, &preimage)
+    }
+    probe_commit(value: &[u8], blinding: [u8; 32]) -> [u8; 32] {
+        crate::commit(value, &blinding)
+    }
+    probe_verify_commit(commitment: [u8; 32], value: &[u8], blinding: [u8; 32]) -> bool {
+        crate::verify_commit(&commitment, value, &blinding)
+    }
+    probe_hash_instruction(program_id: [u8; 32], data: &[u8]) -> [u8; 32] {
+        crate::hash_instruction(&program_id, data)
+    }
+    probe_commit_event(discriminator: [u8; 8], payload: &[u8]) -> [u8; 32] {
+        crate::commit_event(discriminator, payload)
+    }
+    probe_check_nonce(commitment: [u8; 32], user: [u8; 32], slot: u64, nonce: u64) -> bool {
+        crate::check_nonce(&commitment, &user, slot, nonce)
+    }
+    probe_derive_randomness(blockhash: [u8; 32], seed: &[u8]) -> [u8; 32] {
+        crate::derive_randomness(&blockhash, &[seed, seed])
+    }
+    probe_merkle_advance(leaf: [u8; 32], index: u64, siblings: &[[u8; 32]]) -> Result<bool, Error> {
+        let state = crate::MerkleVerifyState::new(&leaf, index);
+        let mut state = crate::MerkleVerifyState::from_bytes(&state.to_bytes());
+        state.advance(siblings)?;
+        Ok(state.verify(&leaf, 1))
+    }
+    probe_haraka256_msg(msg: &[u8]) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        crate::haraka256_msg::<5>(&mut dst, msg);
+        dst
+    }
+    probe_haraka512_gather(a: &[u8], b: &[u8]) -> Result<[u8; 32], Error> {
+        let mut dst = [0u8; 32];
+        crate::haraka512_gather::<5>(&mut dst, &[a, b])?;
+        Ok(dst)
+    }
+    probe_algorithm(input: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        crate::Haraka::from_name("haraka512-keyed-6")
+            .unwrap_or(crate::Haraka::H256_5)
+            .hash(input, out)
+    }
+    probe_wots_verify(seed: [u8; 16], msg: [u8; 32], sig: &[u8]) -> Option<bool> {
+        let sig = <&[u8; crate::wots::SIGNATURE_LEN]>::try_from(sig).ok()?;
+        Some(crate::wots::Wots::new(&seed).verify(&msg, 3, &msg, sig))
+    }
+    probe_xmss_verify(root: [u8; 32], msg: [u8; 32], sig: &[u8]) -> bool {
+        crate::xmss::Xmss::<10>::new(&[1; 16]).verify(&root, &msg, sig)
+    }
+    probe_lamport_verify(public_key: &[u8], msg: [u8; 32], sig: &[u8]) -> Option<bool> {
+        let public_key = <&[u8; crate::lamport::PUBLIC_KEY_LEN]>::try_from(public_key).ok()?;
+        let sig = <&[u8; crate::lamport::SIGNATURE_LEN]>::try_from(sig).ok()?;
+        Some(crate::lamport::verify(public_key, &msg, sig))
+    }
+    probe_base_w(out: &mut [u8], input: &[u8]) -> Result<u64, Error> {
+        crate::wots::base_w::<2>(out, input)?;
+        let checksum = crate::wots::checksum::<2>(out);
+        crate::wots::checksum_digits::<2>(out, checksum)?;
+        Ok(checksum)
+    }
+    probe_horst_verify(root: [u8; 32], msg: [u8; 32], sig: &[u8]) -> bool {
+        crate::horst::verify::<16, 16>(&root, &msg, sig)
+    }
+    probe_wots_verify_state(state: &[u8], values: &[[u8; 32]]) -> Result<bool, Error> {
+        let wots = crate::wots::Wots::new(&[2; 16]);
+        let state = <&[u8; crate::wots::WotsVerifyState::LEN]>::try_from(state)
+            .map_err(|_| Error::InvalidLength {
+                expected: crate::wots::WotsVerifyState::LEN,
+                actual: state.len(),
+            })?;
+        let mut state = crate::wots::WotsVerifyState::from_bytes(state);
+        state.advance(&wots, values)?;
+        Ok(state.verify(&[0; 32]))
+    }
+    probe_wots_chain_ends(starts: &[u8], values: &mut [[u8; 32]]) -> Result<(), Error> {
+        crate::wots::Wots::new(&[3; 16]).chain_ends(1, 2, starts, values)
+    }
+    probe_sphincs_parse(sig: &[u8], i: usize) -> Result<usize, Error> {
+        let sig = crate::sphincs::SphincsSignatureRef::parse::<crate::sphincs::Haraka128f>(sig)?;
+        let fors = sig.fors_tree(i).map_or(0, |tree| tree.auth_path().count());
+        let layer = sig.layer(i).map_or(0, |layer| layer.wots_chains().count());
+        Ok(sig.randomizer().len() + fors + layer)
+    }
+    probe_sphincs_verify(pk: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+        use crate::sphincs::{Haraka128f, SphincsPublicKeyRef, SphincsSignatureRef};
+        let (Ok(pk), Ok(sig)) = (
+            SphincsPublicKeyRef::parse::<Haraka128f>(pk),
+            SphincsSignatureRef::parse::<Haraka128f>(sig),
+        ) else {
+            return false;
+        };
+        crate::sphincs::verify(&pk, msg, &sig)
+    }
+    probe_haraka512_limbs(a: [u8; 16], b: [u8; 16], c: [u8; 16], d: [u8; 16]) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        crate::haraka512_limbs::<5>(&mut dst, &a, &b, &c, &d);
+        dst
+    }
+    probe_haraka256_domain(tag: [u8; 16], src: [u8; 32]) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        crate::haraka256_domain::<5, _>(&mut dst, &tag, &src);
+        dst
+    }
+    probe_haraka512_domain(tag: u8, src: Block64) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        crate::haraka512_domain::<5, _>(&mut dst, tag, src.as_bytes());
+        dst
+    }
+    probe_haraka256_personal(src: [u8; 32], personal: &[u8]) -> Result<[u8; 32], Error> {
+        let mut dst = [0u8; 32];
+        crate::haraka256_personal::<5>(&mut dst, &src, personal)?;
+        crate::Personalization::new(personal)?.haraka512::<5>(&mut dst, &[0; 64]);
+        Ok(dst)
+    }
+    probe_hasher(key: [u8; 32], bytes: &[u8]) -> u64 {
+        use core::hash::{BuildHasher, Hasher};
+        let mut hasher = crate::HarakaBuildHasher::with_key(key).build_hasher();
+        hasher.write(bytes);
+        hasher.write_u64(bytes.len() as u64);
+        hasher.finish()
+    }
+    probe_grind(prefix: &[u8], start_nonce: u64, count: u64) -> Option<u64> {
+        let prefix = <&[u8; 56]>::try_from(prefix).ok()?;
+        crate::grind::<5, _>(prefix, start_nonce, count, |digest| digest[0] == 0)
+    }
+    probe_haraka_fold(chunks: &[[u8; 32]]) -> [u8; 32] {
+        crate::haraka_fold::<5>(chunks.iter().copied())
+    }
+    probe_verify_chain_segment(from: [u8; 32], to: [u8; 32], steps: u64) -> bool {
+        crate::verify_chain_segment::<5>(&from, &to, steps)
+    }
+    probe_transcript(message: &[u8], x: u64, out: &mut [u8]) -> () {
+        let mut transcript = crate::Transcript::new(b"probe");
+        transcript.append_message(b"message", message);
+        transcript.append_u64(b"x", x);
+        transcript.challenge_bytes(b"challenge", out)
+    }
+    probe_drbg(
+        seed: [u8; 32],
+        entropy: &[u8],
+        out: &mut [u8],
+        n: u64,
+        indices: &mut [u64]
+    ) -> Result<u64, Error> {
+        let mut drbg = crate::HarakaDrbg::new_ratcheting(&seed);
+        drbg.reseed(entropy);
+        drbg.fill_bytes(out);
+        drbg.shuffle(out);
+        drbg.sample_indices(n, indices)?;
+        Ok(drbg.next_u64())
+    }
+    #[cfg(feature = "experimental")]
+    probe_keystream(key: [u8; 32], nonce: [u8; 16], data: &mut [u8]) -> () {
+        crate::keystream::encrypt(&key, &nonce, data);
+        crate::keystream::decrypt(&key, &nonce, data);
+        crate::keystream::Keystream::new(&key, &nonce).apply(data)
+    }
+    #[cfg(feature = "experimental")]
+    probe_aead(key: [u8; 32], nonce: [u8; 16], ad: &[u8], data: &mut [u8]) -> bool {
+        let tag = crate::aead::encrypt(&key, &nonce, ad, data);
+        crate::aead::decrypt(&key, &nonce, ad, data, &tag)
+    }
+    probe_batch(dst: &mut [u8], src: &[u8], n: usize) -> Result<(), Error> {
+        crate::haraka512_batch::<5>(dst, src, n)
+    }
+    probe_digest_decode_hex(text: &str) -> Result<Digest, Error> {
+        Digest::decode_hex(text)
+    }
+    probe_digest_from_slice(bytes: &[u8]) -> Result<Digest, Error> {
+        Digest::try_from(bytes)
+    }
+    probe_block64_from_slice(bytes: &[u8]) -> Result<Block64, Error> {
+        Block64::from_slice(bytes)
+    }
+}
+
+/// Most stack frames a public function may use, counting the probe, leaf
+/// C library calls and tail calls. SBF allows 64; staying far below leaves
+/// the rest to programs that call in from deep code of their own.
+const MAX_CALL_DEPTH: usize = 8;
+
+/// Returns the first function reachable from `probe` that calls a panic.
+fn check(binary: &Binary, probe: &str) -> Result<(), String> {
+    binary.walk(
+        &std::format!("no_panic::{probe}"),
+        |name, body| match callees(body, &binary.got).find(|callee| is_panic(callee)) {
+            Some(callee) if !is_panic(name) => Err(std::format!("{name} calls {callee}")),
+            _ => Ok(()),
+        },
+    )
+}
+
+/// A deliberately panicking probe: an unchecked index.
+#[inline(never)]
+fn probe_indexing(buf: &[u8], i: usize) -> u8 {
+    buf[i]
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_public_api_cannot_panic() {
+    run_probes();
+    let binary = Binary::current();
+    let mut failures = String::new();
+    for probe in PROBES {
+        if let Err(err) = check(&binary, probe) {
+            failures += &std::format!("{probe}: {err}\n");
+        }
+    }
+    assert!(failures.is_empty(), "{failures}");
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_flags_reachable_panics() {
+    black_box(probe_indexing(black_box(&[1]), black_box(0)));
+    let err = check(&Binary::current(), "probe_indexing").unwrap_err();
+    assert!(err.contains("panic_bounds_check"), "{err}");
+}
+
+/// A deliberately recursive probe, whose call depth is unbounded.
+#[inline(never)]
+fn probe_recursive(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        black_box(probe_recursive(black_box(n - 1))) + 1
+    }
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_call_depth() {
+    run_probes();
+    let binary = Binary::current();
+    let mut failures = String::new();
+    for probe in PROBES {
+        let chain = binary
+            .deepest_chain(&std::format!("no_panic::{probe}"))
+            .unwrap();
+        if chain.len() > MAX_CALL_DEPTH {
+            failures += &std::format!("{probe}: {} frames: {chain:?}\n", chain.len());
+        }
+    }
+    assert!(failures.is_empty(), "{failures}");
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_flags_recursion() {
+    black_box(probe_recursive(black_box(3)));
+    let err = Binary::current()
+        .deepest_chain("probe_recursive")
+        .unwrap_err();
+    assert!(err.contains("is recursive"), "{err}");
+}
+
+#[test]
+#[cfg(feature = "small-code")]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_round_counts_share_code() {
+    run_probes();
+    let binary = Binary::current();
+    let body = |probe: &str, core: &str| {
+        let chain = binary
+            .deepest_chain(&std::format!("no_panic::{probe}"))
+            .unwrap();
+        chain.into_iter().find(|name| name.contains(core))
+    };
+    let five = body("probe_haraka256::", "haraka256_rounds");
+    assert!(five.is_some());
+    assert_eq!(five, body("probe_haraka256_6::", "haraka256_rounds"));
+}
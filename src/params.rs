@@ -0,0 +1,62 @@
+//! Named Haraka parameter sets.
+
+use crate::haraka256::haraka256;
+use crate::haraka512::haraka512;
+use crate::haraka512_keyed::haraka512_keyed;
+
+/// Round count of Haraka v2, used by every function of this crate that does
+/// not take the count as a parameter (Haraka-S, the hashers, the DRBG, the
+/// Merkle and commitment helpers, the C and WebAssembly bindings).
+pub const DEFAULT_ROUNDS: usize = HarakaV2::ROUNDS;
+
+/// Haraka v2, the revision specified in ePrint 2016/098 and used by
+/// SPHINCS+: 5 rounds of 2 AES rounds each, with the published constants.
+///
+/// Code that names the preset, e.g. `haraka512::<{ HarakaV2::ROUNDS }>`,
+/// keeps its meaning if a later revision gets a preset of its own and
+/// becomes the default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HarakaV2;
+
+impl HarakaV2 {
+    /// Number of rounds.
+    pub const ROUNDS: usize = 5;
+
+    /// Computes Haraka-256 with this preset.
+    pub fn haraka256(dst: &mut [u8; 32], src: &[u8; 32]) {
+        haraka256::<{ Self::ROUNDS }>(dst, src)
+    }
+
+    /// Computes Haraka-512 with this preset.
+    pub fn haraka512(dst: &mut [u8; 32], src: &[u8; 64]) {
+        haraka512::<{ Self::ROUNDS }>(dst, src)
+    }
+
+    /// Computes keyed Haraka-512 with this preset.
+    pub fn haraka512_keyed(dst: &mut [u8; 32], state: &[u8; 64], key: &[u8; 64]) {
+        haraka512_keyed::<{ Self::ROUNDS }>(dst, state, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_haraka_v2_matches_5_rounds() {
+        let src: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+
+        HarakaV2::haraka256(&mut a, src[..32].try_into().unwrap());
+        haraka256::<5>(&mut b, src[..32].try_into().unwrap());
+        assert_eq!(a, b);
+
+        HarakaV2::haraka512(&mut a, &src);
+        haraka512::<5>(&mut b, &src);
+        assert_eq!(a, b);
+
+        HarakaV2::haraka512_keyed(&mut a, &src, &[0x5a; 64]);
+        haraka512_keyed::<5>(&mut b, &src, &[0x5a; 64]);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::backend::{DefaultBackend, HarakaBackend};
+use crate::ct;
+use crate::digest::words;
+use crate::haraka512::haraka512_lanes;
+use arrayref::array_ref;
+
+/// Counts the number of leading zero bits of a digest.
+///
+/// The digest is read as a big-endian 256-bit integer, i.e. the most
+/// significant bit of `digest[0]` comes first. The count is computed one
+/// 64-bit word at a time with `leading_zeros`, rather than bit by bit.
+#[inline(always)]
+pub fn difficulty(digest: &[u8; 32]) -> u32 {
+    let mut total = 0;
+    for word in words(digest) {
+        let zeros = word.leading_zeros();
+        total += zeros;
+        if zeros != 64 {
+            break;
+        }
+    }
+    total
+}
+
+/// Returns `true` if `digest <= target`, both read as big-endian integers.
+///
+/// The comparison walks all four 64-bit words and folds the per-word
+/// results with masks, so its running time does not depend on the position
+/// of the first differing word.
+#[inline(always)]
+pub fn meets_target(digest: &[u8; 32], target: &[u8; 32]) -> bool {
+    let mut lt = 0u64;
+    let mut gt = 0u64;
+    for (d, t) in words(digest).into_iter().zip(words(target)) {
+        // Only the first differing word decides the outcome.
+        let undecided = 1 ^ (lt | gt);
+        lt |= ct::lt_u64(d, t) & undecided;
+        gt |= ct::lt_u64(t, d) & undecided;
+    }
+    gt == 0
+}
+
+/// Searches for a nonce whose Haraka-512 digest satisfies `predicate`.
+///
+/// The hashed message is `prefix || nonce`, with the nonce encoded as 8
+/// little-endian bytes. The first three lanes only depend on the prefix and
+/// are loaded once; each iteration only patches the nonce half of the last
+/// lane. Nonces `start_nonce, start_nonce + 1, ...` are tried (wrapping on
+/// overflow) until `count` candidates have been hashed.
+pub fn grind<const N_ROUNDS: usize, F>(
+    prefix: &[u8; 56],
+    start_nonce: u64,
+    count: u64,
+    mut predicate: F,
+) -> Option<u64>
+where
+    F: FnMut(&[u8; 32]) -> bool,
+{
+    let mut t = [
+        DefaultBackend::load(array_ref![prefix, 0, 16]),
+        DefaultBackend::load(array_ref![prefix, 16, 16]),
+        DefaultBackend::load(array_ref![prefix, 32, 16]),
+        DefaultBackend::load(&[0; 16]),
+    ];
+
+    let mut tail = [0u8; 16];
+    tail[..8].copy_from_slice(array_ref![prefix, 48, 8]);
+
+    let mut digest = [0u8; 32];
+    let mut nonce = start_nonce;
+    for _ in 0..count {
+        tail[8..].copy_from_slice(&nonce.to_le_bytes());
+        t[3] = DefaultBackend::load(&tail);
+        haraka512_lanes::<N_ROUNDS>(&mut digest, &t);
+        if predicate(&digest) {
+            return Some(nonce);
+        }
+        nonce = nonce.wrapping_add(1);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haraka512::haraka512;
+
+    #[test]
+    fn test_difficulty_zero_digest() {
+        assert_eq!(difficulty(&[0u8; 32]), 256);
+    }
+
+    #[test]
+    fn test_difficulty_full_digest() {
+        assert_eq!(difficulty(&[0xffu8; 32]), 0);
+    }
+
+    #[test]
+    fn test_difficulty_bit_positions() {
+        // Setting a single bit at position `i` (counting from the MSB of
+        // the first byte) must yield exactly `i` leading zeros.
+        for i in 0..256 {
+            let mut digest = [0u8; 32];
+            digest[i / 8] = 0x80 >> (i % 8);
+            assert_eq!(difficulty(&digest), i as u32);
+        }
+    }
+
+    #[test]
+    fn test_difficulty_ignores_trailing_bits() {
+        let mut digest = [0xffu8; 32];
+        digest[0] = 0x00;
+        digest[1] = 0x00;
+        digest[2] = 0x01;
+        assert_eq!(difficulty(&digest), 23);
+    }
+
+    #[test]
+    fn test_meets_target_equal() {
+        let digest = [0x5au8; 32];
+        assert!(meets_target(&digest, &digest));
+    }
+
+    #[test]
+    fn test_meets_target_ordering() {
+        let mut target = [0u8; 32];
+        target[1] = 0x10;
+
+        let mut below = [0xffu8; 32];
+        below[0] = 0x00;
+        below[1] = 0x0f;
+        assert!(meets_target(&below, &target));
+
+        let mut above = [0u8; 32];
+        above[1] = 0x10;
+        above[31] = 0x01;
+        assert!(!meets_target(&above, &target));
+    }
+
+    #[test]
+    fn test_meets_target_later_words() {
+        // The first word matches, so the last word decides.
+        let mut target = [0u8; 32];
+        target[31] = 0x80;
+        let mut digest = [0u8; 32];
+        digest[31] = 0x7f;
+        assert!(meets_target(&digest, &target));
+        digest[31] = 0x81;
+        assert!(!meets_target(&digest, &target));
+    }
+
+    fn message(prefix: &[u8; 56], nonce: u64) -> [u8; 64] {
+        let mut msg = [0u8; 64];
+        msg[..56].copy_from_slice(prefix);
+        msg[56..].copy_from_slice(&nonce.to_le_bytes());
+        msg
+    }
+
+    #[test]
+    fn test_grind_matches_full_message() {
+        let mut prefix = [0u8; 56];
+        for (i, b) in prefix.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let start = 0x0102_0304_0506_0708;
+        let mut seen = 0;
+        let found = grind::<5, _>(&prefix, start, 16, |digest| {
+            let mut expect = [0u8; 32];
+            haraka512::<5>(&mut expect, &message(&prefix, start + seen));
+            assert_eq!(digest, &expect);
+            seen += 1;
+            false
+        });
+        assert_eq!(found, None);
+        assert_eq!(seen, 16);
+    }
+
+    #[test]
+    fn test_grind_finds_nonce() {
+        let prefix = [0xa5u8; 56];
+        let nonce = grind::<5, _>(&prefix, 0, 1 << 16, |digest| difficulty(digest) >= 8)
+            .expect("a difficulty-8 nonce within 2^16 tries");
+        let mut digest = [0u8; 32];
+        haraka512::<5>(&mut digest, &message(&prefix, nonce));
+        assert!(difficulty(&digest) >= 8);
+    }
+
+    #[test]
+    fn test_grind_wraps_nonce() {
+        let prefix = [0u8; 56];
+        let mut nonces = [0u64; 2];
+        let mut idx = 0;
+        let _ = grind::<5, _>(&prefix, u64::MAX, 2, |digest| {
+            let mut expect = [0u8; 32];
+            let nonce = u64::MAX.wrapping_add(idx as u64);
+            haraka512::<5>(&mut expect, &message(&prefix, nonce));
+            assert_eq!(digest, &expect);
+            nonces[idx] = nonce;
+            idx += 1;
+            false
+        });
+        assert_eq!(nonces, [u64::MAX, 0]);
+    }
+
+    #[test]
+    fn test_grind_zero_count() {
+        assert_eq!(grind::<5, _>(&[0u8; 56], 0, 0, |_| true), None);
+    }
+}
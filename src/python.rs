@@ -0,0 +1,114 @@
+//! Python bindings generated with `pyo3`.
+//!
+//! The module is importable as `haraka_bpf` and exposes `haraka256`,
+//! `haraka512` and `haraka512_keyed`, each taking `bytes` and an optional
+//! round count and returning the 32-byte digest as `bytes`. All three
+//! accept 5 or 6 rounds.
+//!
+//! The `PyInit_haraka_bpf` entry point is exported from any `cdylib` that
+//! links this crate with the `python` feature. When building such an
+//! extension (e.g. with maturin), also enable `pyo3/extension-module`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+fn array<const N: usize>(bytes: &[u8]) -> PyResult<[u8; N]> {
+    bytes
+        .try_into()
+        .map_err(|_| PyValueError::new_err(std::format!("expected {N} bytes, got {}", bytes.len())))
+}
+
+fn unsupported_rounds(rounds: usize) -> PyErr {
+    PyValueError::new_err(std::format!(
+        "unsupported round count {rounds}, expected 5 or 6"
+    ))
+}
+
+/// Haraka-256 of a 32-byte input.
+#[pyfunction]
+#[pyo3(signature = (data, rounds = 5))]
+fn haraka256<'py>(py: Python<'py>, data: &[u8], rounds: usize) -> PyResult<Bound<'py, PyBytes>> {
+    let src = array::<32>(data)?;
+    let mut dst = [0u8; 32];
+    match rounds {
+        5 => crate::haraka256::haraka256::<5>(&mut dst, &src),
+        6 => crate::haraka256::haraka256::<6>(&mut dst, &src),
+        _ => return Err(unsupported_rounds(rounds)),
+    }
+    Ok(PyBytes::new(py, &dst))
+}
+
+/// Haraka-512 of a 64-byte input.
+#[pyfunction]
+#[pyo3(signature = (data, rounds = 5))]
+fn haraka512<'py>(py: Python<'py>, data: &[u8], rounds: usize) -> PyResult<Bound<'py, PyBytes>> {
+    let src = array::<64>(data)?;
+    let mut dst = [0u8; 32];
+    match rounds {
+        5 => crate::haraka512::haraka512::<5>(&mut dst, &src),
+        6 => crate::haraka512::haraka512::<6>(&mut dst, &src),
+        _ => return Err(unsupported_rounds(rounds)),
+    }
+    Ok(PyBytes::new(py, &dst))
+}
+
+/// Keyed Haraka-512 of a 64-byte state under a 64-byte key.
+#[pyfunction]
+#[pyo3(signature = (state, key, rounds = 5))]
+fn haraka512_keyed<'py>(
+    py: Python<'py>,
+    state: &[u8],
+    key: &[u8],
+    rounds: usize,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let state = array::<64>(state)?;
+    let key = array::<64>(key)?;
+    let mut dst = [0u8; 32];
+    match rounds {
+        5 => crate::haraka512_keyed::haraka512_keyed::<5>(&mut dst, &state, &key),
+        6 => crate::haraka512_keyed::haraka512_keyed::<6>(&mut dst, &state, &key),
+        _ => return Err(unsupported_rounds(rounds)),
+    }
+    Ok(PyBytes::new(py, &dst))
+}
+
+#[pymodule]
+fn haraka_bpf(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(haraka256, m)?)?;
+    m.add_function(wrap_pyfunction!(haraka512, m)?)?;
+    m.add_function(wrap_pyfunction!(haraka512_keyed, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_haraka512() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let src = [3u8; 64];
+            let mut expect = [0u8; 32];
+            crate::haraka512::haraka512::<6>(&mut expect, &src);
+            let digest = haraka512(py, &src, 6).unwrap();
+            assert_eq!(digest.as_bytes(), &expect);
+
+            let key = [5u8; 64];
+            crate::haraka512_keyed::haraka512_keyed::<6>(&mut expect, &src, &key);
+            let digest = haraka512_keyed(py, &src, &key, 6).unwrap();
+            assert_eq!(digest.as_bytes(), &expect);
+        });
+    }
+
+    #[test]
+    fn test_python_errors() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            assert!(haraka256(py, &[0u8; 31], 5).is_err());
+            assert!(haraka256(py, &[0u8; 32], 4).is_err());
+            assert!(haraka512_keyed(py, &[0u8; 64], &[0u8; 63], 5).is_err());
+        });
+    }
+}
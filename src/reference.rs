@@ -0,0 +1,433 @@
+//! Slow, straightforward Haraka used as a test oracle.
+//!
+//! Everything here works on plain byte arrays and follows the Haraka v2
+//! specification literally: the AES round is computed from the field
+//! arithmetic (no tables, no `aes` crate), MIX is the word permutation
+//! from the paper and the round constants are derived from the digits of
+//! π. The optimized paths of the crate are checked against
+//! these functions on many inputs.
+
+type Block = [u8; 16];
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1.
+fn gmul(mut a: u8, mut b: u8) -> u8 {
+    let mut p = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            p ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    p
+}
+
+/// The AES S-box: inversion in GF(2^8) followed by the affine map.
+fn sbox(x: u8) -> u8 {
+    // x^254 is the inverse of x, and maps 0 to 0.
+    let mut inv = 1;
+    for _ in 0..254 {
+        inv = gmul(inv, x);
+    }
+    inv ^ inv.rotate_left(1) ^ inv.rotate_left(2) ^ inv.rotate_left(3) ^ inv.rotate_left(4) ^ 0x63
+}
+
+/// One AES encryption round (SubBytes, ShiftRows, MixColumns, AddRoundKey).
+/// Byte `i` of the block is row `i % 4` of column `i / 4`.
+fn aes_round(state: &mut Block, key: &Block) {
+    let mut s = [0u8; 16];
+    for c in 0..4 {
+        for r in 0..4 {
+            s[r + 4 * c] = sbox(state[r + 4 * ((c + r) % 4)]);
+        }
+    }
+    for c in 0..4 {
+        let col = [s[4 * c], s[4 * c + 1], s[4 * c + 2], s[4 * c + 3]];
+        for r in 0..4 {
+            state[4 * c + r] = gmul(col[r], 2)
+                ^ gmul(col[(r + 1) % 4], 3)
+                ^ col[(r + 2) % 4]
+                ^ col[(r + 3) % 4]
+                ^ key[4 * c + r];
+        }
+    }
+}
+
+/// Permutes the 32-bit words of `state`: word `i` of the result is word
+/// `perm[i]` of the input.
+fn permute_words<const N: usize>(state: &mut [u8; N], perm: &[usize]) {
+    let old = *state;
+    for (i, &p) in perm.iter().enumerate() {
+        state[4 * i..4 * i + 4].copy_from_slice(&old[4 * p..4 * p + 4]);
+    }
+}
+
+const MIX256: [usize; 8] = [0, 4, 1, 5, 2, 6, 3, 7];
+const MIX512: [usize; 16] = [3, 11, 7, 15, 8, 0, 12, 4, 9, 1, 13, 5, 2, 10, 6, 14];
+
+/// Fractional decimal digits of π behind the constants, one per bit.
+const PI_DIGITS: usize = 48 * 128;
+
+/// Decimal digits per limb of the fixed-point π.
+const LIMB_DIGITS: usize = 18;
+
+/// Limbs of the fixed-point π, base 10^18, most significant first: the
+/// integer part, the digits, and a guard limb that absorbs the truncation
+/// error of the series.
+const LIMBS: usize = 1 + PI_DIGITS.div_ceil(LIMB_DIGITS) + 1;
+
+const BASE: u128 = 10u128.pow(LIMB_DIGITS as u32);
+
+/// Adds `m * arctan(1 / x)` to `sum`, with Euler's series
+///
+/// ```text
+/// arctan(1/x) = x/(x^2 + 1) * sum_k (2k)!! / (2k + 1)!! / (x^2 + 1)^k
+/// ```
+///
+/// whose terms are all positive and follow from each other by a single
+/// multiply-divide pass. `sum` is left unnormalized.
+const fn add_arctan(sum: &mut [u128; LIMBS], m: u128, x: u128) {
+    let mut term = [0u128; LIMBS];
+    term[0] = m * x;
+    let (mut mul, mut div) = (1, x * x + 1);
+    let mut first = 0;
+    let mut k = 0;
+    while first < LIMBS {
+        let mut rem = 0;
+        let mut i = first;
+        while i < LIMBS {
+            let cur = rem * BASE + term[i] * mul;
+            term[i] = cur / div;
+            rem = cur % div;
+            sum[i] += term[i];
+            i += 1;
+        }
+        while first < LIMBS && term[first] == 0 {
+            first += 1;
+        }
+        k += 1;
+        (mul, div) = (2 * k, (2 * k + 1) * (x * x + 1));
+    }
+}
+
+/// Derives the round constants from π: bit `j` of byte `i` of the table, in
+/// the byte order of `_mm_load_si128`, is the parity of decimal digit
+/// `8i + j + 1` of π after the point. This reproduces the published table
+/// bit for bit.
+///
+/// π comes from Machin's formula, 16 arctan(1/5) - 4 arctan(1/239); the
+/// second arctan is subtracted as the ten's complement of its sum.
+const fn pi_constants() -> [Block; 48] {
+    let mut plus = [0u128; LIMBS];
+    let mut minus = [0u128; LIMBS];
+    add_arctan(&mut plus, 16, 5);
+    add_arctan(&mut minus, 4, 239);
+
+    let mut pi = [0u128; LIMBS];
+    let mut carry = 0;
+    let mut i = LIMBS;
+    while i > 0 {
+        i -= 1;
+        let limb = plus[i] as i128 - minus[i] as i128 + carry;
+        carry = limb.div_euclid(BASE as i128);
+        pi[i] = limb.rem_euclid(BASE as i128) as u128;
+    }
+    assert!(carry == 0 && pi[0] == 3);
+
+    let mut rc = [[0u8; 16]; 48];
+    let mut d = 0;
+    while d < PI_DIGITS {
+        let limb = pi[1 + d / LIMB_DIGITS];
+        let digit = limb / 10u128.pow((LIMB_DIGITS - 1 - d % LIMB_DIGITS) as u32) % 10;
+        rc[d / 128][d / 8 % 16] |= ((digit & 1) as u8) << (d % 8);
+        d += 1;
+    }
+    rc
+}
+
+/// The standard round constants, derived from π at compile time, so the
+/// oracle does not depend on the table it checks.
+const PI_CONSTANTS: [Block; 48] = pi_constants();
+
+/// The standard round constants as bytes.
+pub(crate) fn constants() -> [Block; 48] {
+    PI_CONSTANTS
+}
+
+/// The constants of `constants::tweaked(kind, tag)`.
+pub(crate) fn tweaked(kind: u8, tag: &[u8]) -> [Block; 48] {
+    let mut rc = constants();
+    for (i, c) in rc.iter_mut().enumerate() {
+        if i % 2 == 0 {
+            for (b, t) in c.iter_mut().zip(tag) {
+                *b ^= t;
+            }
+        } else {
+            c[0] ^= tag.len() as u8;
+            c[1] ^= kind;
+        }
+    }
+    rc
+}
+
+/// The Haraka-256 permutation (no feed-forward).
+pub(crate) fn haraka256_perm(rc: &[Block; 48], rounds: usize, src: &[u8; 32]) -> [u8; 32] {
+    let mut state = *src;
+    for i in 0..rounds {
+        for j in 0..2 {
+            for lane in 0..2 {
+                let mut b = [0u8; 16];
+                b.copy_from_slice(&state[16 * lane..16 * lane + 16]);
+                aes_round(&mut b, &rc[4 * i + 2 * j + lane]);
+                state[16 * lane..16 * lane + 16].copy_from_slice(&b);
+            }
+        }
+        permute_words(&mut state, &MIX256);
+    }
+    state
+}
+
+/// Haraka-256: permutation followed by the feed-forward.
+pub(crate) fn haraka256(rc: &[Block; 48], rounds: usize, src: &[u8; 32]) -> [u8; 32] {
+    let mut out = haraka256_perm(rc, rounds, src);
+    for (o, s) in out.iter_mut().zip(src) {
+        *o ^= s;
+    }
+    out
+}
+
+/// The Haraka-512 permutation (no feed-forward, no truncation).
+pub(crate) fn haraka512_perm(rc: &[Block; 48], rounds: usize, src: &[u8; 64]) -> [u8; 64] {
+    let mut state = *src;
+    for i in 0..rounds {
+        for j in 0..2 {
+            for lane in 0..4 {
+                let mut b = [0u8; 16];
+                b.copy_from_slice(&state[16 * lane..16 * lane + 16]);
+                aes_round(&mut b, &rc[8 * i + 4 * j + lane]);
+                state[16 * lane..16 * lane + 16].copy_from_slice(&b);
+            }
+        }
+        permute_words(&mut state, &MIX512);
+    }
+    state
+}
+
+/// Haraka-512: permutation, feed-forward and truncation to bytes
+/// 8..16, 24..32, 32..40 and 48..56.
+pub(crate) fn haraka512(rc: &[Block; 48], rounds: usize, src: &[u8; 64]) -> [u8; 32] {
+    let mut full = haraka512_perm(rc, rounds, src);
+    for (o, s) in full.iter_mut().zip(src) {
+        *o ^= s;
+    }
+    let mut out = [0u8; 32];
+    out[0..8].copy_from_slice(&full[8..16]);
+    out[8..16].copy_from_slice(&full[24..32]);
+    out[16..24].copy_from_slice(&full[32..40]);
+    out[24..32].copy_from_slice(&full[48..56]);
+    out
+}
+
+/// Haraka-S with rate 32 and the 5-round Haraka-512 permutation; the
+/// output length is that of `out` and at most 64 bytes.
+pub(crate) fn haraka_s(out: &mut [u8], input: &[u8]) {
+    let rc = constants();
+    let mut state = [0u8; 64];
+    let mut blocks = input.chunks_exact(32);
+    for block in &mut blocks {
+        for (s, b) in state.iter_mut().zip(block) {
+            *s ^= b;
+        }
+        state = haraka512_perm(&rc, 5, &state);
+    }
+    let rest = blocks.remainder();
+    for (s, b) in state.iter_mut().zip(rest) {
+        *s ^= b;
+    }
+    state[rest.len()] ^= 0x1f;
+    state[31] ^= 0x80;
+    for chunk in out.chunks_mut(32) {
+        state = haraka512_perm(&rc, 5, &state);
+        chunk.copy_from_slice(&state[..chunk.len()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{self, SoftBackend};
+    use crate::{
+        haraka256 as h256, haraka512 as h512, haraka512_keyed, short, sponge, Haraka512State,
+        Personalization,
+    };
+
+    /// Deterministic pseudo-random test inputs (xorshift64).
+    struct Inputs(u64);
+
+    impl Inputs {
+        fn fill(&mut self, buf: &mut [u8]) {
+            for b in buf {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                *b = self.0 as u8;
+            }
+        }
+    }
+
+    #[test]
+    fn test_sbox() {
+        // FIPS-197, section 5.1.1.
+        assert_eq!(sbox(0x00), 0x63);
+        assert_eq!(sbox(0x53), 0xed);
+        assert_eq!(sbox(0xff), 0x16);
+    }
+
+    #[test]
+    fn test_aes_round() {
+        // FIPS-197 appendix B: state at the start of round 2 and round key 1.
+        let mut state = [
+            0x19, 0x3d, 0xe3, 0xbe, 0xa0, 0xf4, 0xe2, 0x2b, 0x9a, 0xc6, 0x8d, 0x2a, 0xe9, 0xf8,
+            0x48, 0x08,
+        ];
+        let key = [
+            0xa0, 0xfa, 0xfe, 0x17, 0x88, 0x54, 0x2c, 0xb1, 0x23, 0xa3, 0x39, 0x39, 0x2a, 0x6c,
+            0x76, 0x05,
+        ];
+        let expect = [
+            0xa4, 0x9c, 0x7f, 0xf2, 0x68, 0x9f, 0x35, 0x2b, 0x6b, 0x5b, 0xea, 0x43, 0x02, 0x6a,
+            0x50, 0x49,
+        ];
+        aes_round(&mut state, &key);
+        assert_eq!(state, expect);
+    }
+
+    #[test]
+    fn test_pi_constants_match_table() {
+        let table = crate::constants::HARAKA_CONSTANTS.as_flattened();
+        for (i, (derived, lane)) in constants().iter().zip(table).enumerate() {
+            assert_eq!(derived, lane, "constant {i}");
+        }
+    }
+
+    #[test]
+    fn test_known_vectors() {
+        let rc = constants();
+        let mut src = [0u8; 64];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut src32 = [0u8; 32];
+        src32.copy_from_slice(&src[..32]);
+        assert_eq!(haraka256(&rc, 5, &src32)[..4], [0x80, 0x27, 0xcc, 0xb8]);
+        assert_eq!(haraka512(&rc, 5, &src)[..4], [0xbe, 0x7f, 0x72, 0x3b]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under Miri")]
+    fn test_haraka256_paths() {
+        let rc = constants();
+        let mut inputs = Inputs(1);
+        for _ in 0..20 {
+            let mut src = [0u8; 32];
+            inputs.fill(&mut src);
+            let mut dst = [0u8; 32];
+
+            h256::haraka256::<5>(&mut dst, &src);
+            assert_eq!(dst, haraka256(&rc, 5, &src));
+            h256::haraka256::<6>(&mut dst, &src);
+            assert_eq!(dst, haraka256(&rc, 6, &src));
+            backend::haraka256::<SoftBackend, 5>(&mut dst, &src);
+            assert_eq!(dst, haraka256(&rc, 5, &src));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under Miri")]
+    fn test_haraka512_paths() {
+        let rc = constants();
+        let mut inputs = Inputs(2);
+        for _ in 0..20 {
+            let mut src = [0u8; 64];
+            let mut key = [0u8; 64];
+            inputs.fill(&mut src);
+            inputs.fill(&mut key);
+            let mut dst = [0u8; 32];
+
+            h512::haraka512::<5>(&mut dst, &src);
+            assert_eq!(dst, haraka512(&rc, 5, &src));
+            h512::haraka512::<6>(&mut dst, &src);
+            assert_eq!(dst, haraka512(&rc, 6, &src));
+            backend::haraka512::<SoftBackend, 5>(&mut dst, &src);
+            assert_eq!(dst, haraka512(&rc, 5, &src));
+
+            // The batch path hashes the messages in pairs, on a Simd256.
+            let mut pair = [0u8; 128];
+            pair[..64].copy_from_slice(&src);
+            pair[64..].copy_from_slice(&key);
+            let mut digests = [0u8; 64];
+            crate::haraka512_batch::<5>(&mut digests, &pair, 2).unwrap();
+            assert_eq!(digests[..32], haraka512(&rc, 5, &src));
+            assert_eq!(digests[32..], haraka512(&rc, 5, &key));
+
+            let mut perm = [0u8; 64];
+            h512::haraka512_perm::<5>(&mut perm, &src);
+            assert_eq!(&perm as &[u8], &haraka512_perm(&rc, 5, &src) as &[u8]);
+
+            let mut state = Haraka512State::load(&src);
+            for i in 0..5 {
+                state.round(i);
+            }
+            state.feed_forward();
+            assert_eq!(state.extract(), haraka512(&rc, 5, &src));
+
+            let mut keyed_src = src;
+            for (s, k) in keyed_src.iter_mut().zip(&key) {
+                *s ^= k;
+            }
+            haraka512_keyed::haraka512_keyed::<5>(&mut dst, &src, &key);
+            assert_eq!(dst, haraka512(&rc, 5, &keyed_src));
+        }
+    }
+
+    #[test]
+    fn test_tweaked_paths() {
+        let mut inputs = Inputs(3);
+        let mut src32 = [0u8; 32];
+        let mut src64 = [0u8; 64];
+        inputs.fill(&mut src32);
+        inputs.fill(&mut src64);
+        let mut dst = [0u8; 32];
+
+        let personal = b"example personal";
+        Personalization::new(personal)
+            .unwrap()
+            .haraka512::<5>(&mut dst, &src64);
+        assert_eq!(dst, haraka512(&tweaked(0x01, personal), 5, &src64));
+
+        crate::domain::haraka256_domain::<5, _>(&mut dst, crate::domain::NODE, &src32);
+        assert_eq!(dst, haraka256(&tweaked(0x02, &[1]), 5, &src32));
+
+        let full = haraka256(&tweaked(0x03, &[16]), 5, &src32);
+        assert_eq!(short::haraka256_short::<5>(&src32), full[..16]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "too slow under Miri")]
+    fn test_haraka_s() {
+        let mut inputs = Inputs(4);
+        let mut input = [0u8; 100];
+        inputs.fill(&mut input);
+        for len in [0, 1, 31, 32, 33, 64, 100] {
+            let mut expect = [0u8; 48];
+            haraka_s(&mut expect, &input[..len]);
+            let mut out = [0u8; 48];
+            sponge::haraka_s(&mut out, &input[..len]);
+            assert_eq!(out, expect);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Hashing to curve25519 scalars.
+//!
+//! 64 bytes of Haraka-S output, read as a little-endian 512-bit integer,
+//! are reduced modulo the order `l = 2^252 + 27742317777372353535851937790883648493`
+//! of the prime-order subgroup. Since `l` is much smaller than `2^512`, the
+//! result is statistically indistinguishable from a uniform scalar.
+
+use crate::sponge::{domain_block, HarakaS};
+
+/// Domain block absorbed before the message.
+const DOMAIN: &[u8; 32] = &domain_block("haraka-bpf/hash-to-scalar/v1");
+
+/// The group order `l` as little-endian 64-bit limbs.
+const L: [u64; 4] = [
+    0x5812631a5cf5d3ed,
+    0x14def9dea2f79cd6,
+    0x0000000000000000,
+    0x1000000000000000,
+];
+
+/// Hashes `msg` to a scalar in `[0, l)`, encoded in 32 little-endian bytes.
+pub fn hash_to_scalar(msg: &[u8]) -> [u8; 32] {
+    let mut hasher = HarakaS::with_domain(DOMAIN);
+    hasher.update(msg);
+    let mut wide = [0u8; 64];
+    hasher.finalize_xof().squeeze(&mut wide);
+    reduce_wide(&wide)
+}
+
+/// Reduces a little-endian 512-bit integer modulo `l`.
+///
+/// The reduction shifts the input in one bit at a time, from the most
+/// significant bit down, and conditionally subtracts `l` after each step.
+/// The subtraction is selected with masks, so the running time does not
+/// depend on the input.
+pub(crate) fn reduce_wide(wide: &[u8; 64]) -> [u8; 32] {
+    let mut r = [0u64; 4];
+    for byte in wide.iter().rev() {
+        for bit in (0..8).rev() {
+            // r < l < 2^253, so 2r + 1 < 2^254 cannot overflow.
+            r[3] = (r[3] << 1) | (r[2] >> 63);
+            r[2] = (r[2] << 1) | (r[1] >> 63);
+            r[1] = (r[1] << 1) | (r[0] >> 63);
+            r[0] = (r[0] << 1) | ((byte >> bit) & 1) as u64;
+
+            let mut diff = [0u64; 4];
+            let mut borrow = 0u64;
+            for i in 0..4 {
+                let (d, b1) = r[i].overflowing_sub(L[i]);
+                let (d, b2) = d.overflowing_sub(borrow);
+                diff[i] = d;
+                borrow = (b1 | b2) as u64;
+            }
+            // Keep `r - l` unless the subtraction borrowed, i.e. r < l.
+            let keep = borrow.wrapping_neg();
+            for i in 0..4 {
+                r[i] = (r[i] & keep) | (diff[i] & !keep);
+            }
+        }
+    }
+
+    let mut out = [0u8; 32];
+    for (chunk, limb) in out.chunks_exact_mut(8).zip(r) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ct;
+    use arrayref::array_ref;
+
+    /// Returns `true` if the little-endian `scalar` is below `l`.
+    fn is_canonical(scalar: &[u8; 32]) -> bool {
+        let mut lt = 0u64;
+        let mut gt = 0u64;
+        for i in (0..4).rev() {
+            let limb = u64::from_le_bytes(*array_ref![scalar, 8 * i, 8]);
+            let undecided = 1 ^ (lt | gt);
+            lt |= ct::lt_u64(limb, L[i]) & undecided;
+            gt |= ct::lt_u64(L[i], limb) & undecided;
+        }
+        lt == 1
+    }
+
+    fn l_bytes() -> [u8; 64] {
+        let mut wide = [0u8; 64];
+        for (chunk, limb) in wide.chunks_exact_mut(8).zip(L) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        wide
+    }
+
+    #[test]
+    fn test_reduce_small() {
+        let mut wide = [0u8; 64];
+        wide[0] = 5;
+        let mut expect = [0u8; 32];
+        expect[0] = 5;
+        assert_eq!(reduce_wide(&wide), expect);
+    }
+
+    #[test]
+    fn test_reduce_l() {
+        assert_eq!(reduce_wide(&l_bytes()), [0u8; 32]);
+        let mut wide = l_bytes();
+        wide[0] += 1;
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(reduce_wide(&wide), one);
+    }
+
+    #[test]
+    fn test_reduce_max() {
+        // (2^512 - 1) mod l, computed with Python integers.
+        let expect = [
+            0x00, 0x0f, 0x9c, 0x44, 0xe3, 0x11, 0x06, 0xa4, 0x47, 0x93, 0x85, 0x68, 0xa7, 0x1b,
+            0x0e, 0xd0, 0x65, 0xbe, 0xf5, 0x17, 0xd2, 0x73, 0xec, 0xce, 0x3d, 0x9a, 0x30, 0x7c,
+            0x1b, 0x41, 0x99, 0x03,
+        ];
+        assert_eq!(reduce_wide(&[0xff; 64]), expect);
+    }
+
+    #[test]
+    fn test_hash_to_scalar_vector() {
+        // Haraka-S output from the PQClean SPHINCS+ code, reduced with
+        // Python integers.
+        let expect = [
+            0x73, 0xe1, 0x15, 0x9f, 0x1d, 0x62, 0xf5, 0x23, 0x33, 0x7b, 0x33, 0xd3, 0xa6, 0x67,
+            0xb4, 0xfc, 0x84, 0xb3, 0x40, 0xe7, 0xa1, 0x94, 0x41, 0x00, 0x6e, 0x3e, 0xbd, 0x8d,
+            0xd4, 0x60, 0x99, 0x0b,
+        ];
+        assert_eq!(hash_to_scalar(b"schnorr challenge"), expect);
+    }
+
+    #[test]
+    fn test_hash_to_scalar_canonical() {
+        for i in 0..64u8 {
+            let scalar = hash_to_scalar(&[i]);
+            assert!(is_canonical(&scalar));
+            // The top nibble of a reduced scalar is at most 1.
+            assert!(scalar[31] <= 0x10);
+        }
+        let l = l_bytes();
+        assert!(!is_canonical(array_ref![l, 0, 32]));
+    }
+}
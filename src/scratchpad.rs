@@ -0,0 +1,117 @@
+//! Memory-hard wrapper around the Haraka permutations.
+//!
+//! A caller-provided scratchpad is first filled with a Haraka-256 chain
+//! derived from a seed. The digest is then refined by a sequence of
+//! Haraka-512 compressions, each reading a scratchpad entry selected by the
+//! current digest. Since the read addresses are only known once the previous
+//! step has finished, evaluating [`hash`] requires either keeping the whole
+//! scratchpad around or recomputing entries on demand.
+//!
+//! This is intended for latency-bound proof-of-work schemes. It makes no
+//! claim of side-channel resistance: the memory access pattern depends on
+//! the seed.
+
+use crate::backend::DefaultBackend;
+use crate::haraka256::{haraka256_lanes, load2, store2};
+use crate::haraka512::haraka512;
+use arrayref::array_ref;
+
+/// Fills `pad` with the Haraka-256 chain `H(seed), H(H(seed)), ...`.
+pub fn fill<const N_ROUNDS: usize>(seed: &[u8; 32], pad: &mut [[u8; 32]]) {
+    let mut s = load2::<DefaultBackend>(seed);
+
+    for entry in pad.iter_mut() {
+        haraka256_lanes::<N_ROUNDS>(&mut s);
+        store2::<DefaultBackend>(entry, &s);
+    }
+}
+
+/// Performs `reads` data-dependent reads over a filled scratchpad.
+///
+/// Starting from the last entry, each step interprets the first 8 bytes of
+/// the current digest as a little-endian index (reduced modulo the pad
+/// length) and replaces the digest by `Haraka512(digest || pad[index])`.
+///
+/// # Panics
+///
+/// Panics if `pad` is empty.
+pub fn mix<const N_ROUNDS: usize>(pad: &[[u8; 32]], reads: u64) -> [u8; 32] {
+    assert!(!pad.is_empty(), "scratchpad must not be empty");
+
+    let len = pad.len() as u64;
+    let mut block = [0u8; 64];
+    let mut digest = pad[pad.len() - 1];
+    for _ in 0..reads {
+        let index = u64::from_le_bytes(*array_ref![digest, 0, 8]) % len;
+        block[..32].copy_from_slice(&digest);
+        block[32..].copy_from_slice(&pad[index as usize]);
+        haraka512::<N_ROUNDS>(&mut digest, &block);
+    }
+    digest
+}
+
+/// Fills `pad` from `seed` and returns the result of `reads` mixing steps.
+///
+/// The cost is governed by the size of `pad` (memory) and `reads`
+/// (latency-bound work). See [`fill`] and [`mix`].
+///
+/// # Panics
+///
+/// Panics if `pad` is empty.
+pub fn hash<const N_ROUNDS: usize>(seed: &[u8; 32], pad: &mut [[u8; 32]], reads: u64) -> [u8; 32] {
+    fill::<N_ROUNDS>(seed, pad);
+    mix::<N_ROUNDS>(pad, reads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::hash_chain;
+
+    #[test]
+    fn test_fill_is_hash_chain() {
+        let seed = [0x5cu8; 32];
+        let mut pad = [[0u8; 32]; 16];
+        fill::<5>(&seed, &mut pad);
+        for (i, entry) in pad.iter().enumerate() {
+            assert_eq!(entry, &hash_chain::<5>(&seed, i as u64 + 1));
+        }
+    }
+
+    #[test]
+    fn test_mix_zero_reads() {
+        let seed = [0x5cu8; 32];
+        let mut pad = [[0u8; 32]; 4];
+        assert_eq!(hash::<5>(&seed, &mut pad, 0), pad[3]);
+    }
+
+    #[test]
+    fn test_mix_reads_selected_entry() {
+        let seed = [0x01u8; 32];
+        let mut pad = [[0u8; 32]; 8];
+        fill::<5>(&seed, &mut pad);
+
+        let last = pad[7];
+        let index = u64::from_le_bytes(*array_ref![last, 0, 8]) % 8;
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(&last);
+        block[32..].copy_from_slice(&pad[index as usize]);
+        let mut expect = [0u8; 32];
+        haraka512::<5>(&mut expect, &block);
+
+        assert_eq!(mix::<5>(&pad, 1), expect);
+    }
+
+    #[test]
+    fn test_hash_depends_on_pad_contents() {
+        let seed = [0x01u8; 32];
+        let mut pad = [[0u8; 32]; 8];
+        let digest = hash::<5>(&seed, &mut pad, 64);
+
+        // Corrupting every entry but the last one must change the result.
+        for entry in pad.iter_mut().take(7) {
+            entry[0] ^= 1;
+        }
+        assert_ne!(mix::<5>(&pad, 64), digest);
+    }
+}
@@ -1,44 +1,178 @@
-use aes::cipher::Block;
-use aes::hazmat::cipher_round;
-use aes::Aes128; // Import the specific AES type
+#[cfg(not(any(
+    feature = "sbox-table",
+    all(
+        not(feature = "sbox-computed"),
+        any(target_os = "solana", target_arch = "bpf")
+    )
+)))]
+use aes::{
+    cipher::Block,
+    hazmat::{cipher_round, cipher_round_par},
+};
 use core::ops::BitXorAssign;
 
-/// Represents a 128-bit SIMD value, implemented using aes::Block<aes::Aes128> for portability.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(all(feature = "sbox-table", feature = "sbox-computed"))]
+compile_error!("features `sbox-table` and `sbox-computed` are mutually exclusive");
+
+// `sanitizer` implies `sbox-table`; report the conflict under its own name.
+#[cfg(all(feature = "sanitizer", feature = "sbox-computed"))]
+compile_error!("feature `sanitizer` cannot be combined with `sbox-computed`");
+
+/// Whether the AES round evaluates several blocks in one parallel pass, as
+/// the bitsliced round does, so that two Haraka states cost about as much
+/// as one. The table round has no such batching.
+pub(crate) const PARALLEL_ROUND: bool = cfg!(not(any(
+    feature = "sbox-table",
+    all(
+        not(feature = "sbox-computed"),
+        any(target_os = "solana", target_arch = "bpf")
+    )
+)));
+
+/// Represents a 128-bit SIMD value as plain bytes, so that every operation
+/// is safe code.
+#[derive(Clone, Copy, Debug, Default, Eq)]
 #[repr(transparent)]
-pub(crate) struct Simd128(Block<Aes128>);
+pub struct Simd128([u8; 16]);
 
 impl Simd128 {
-    /// Creates a Simd128 value from a u128.
-    /// Note: The byte order depends on the target architecture (little-endian assumed).
+    /// Creates a Simd128 value from a u128, least significant byte first.
     pub const fn from(x: u128) -> Self {
-        // GenericArray requires a const context for `from_slice`, which isn't stable yet.
-        // We use `to_le_bytes` and transmute as a workaround.
-        // This assumes the underlying Block representation matches [u8; 16].
-        // SAFETY: Block is repr(transparent) over GenericArray<u8, U16>, which has the same
-        // size and alignment as [u8; 16].
-        unsafe { core::mem::transmute(x.to_le_bytes()) }
+        Self(x.to_le_bytes())
     }
 
     /// Read from array pointer (potentially unaligned)
     #[inline(always)]
     pub fn read(src: &[u8; 16]) -> Self {
-        Self(Block::<Aes128>::clone_from_slice(src))
+        Self(*src)
     }
 
     /// Write into array pointer (potentially unaligned)
     #[inline(always)]
     pub fn write(self, dst: &mut [u8; 16]) {
-        dst.copy_from_slice(self.0.as_slice());
+        *dst = self.0;
     }
 
     /// Performs one round of AES encryption (SubBytes, ShiftRows, MixColumns)
     /// on the block, then XORs the result with the key.
     /// This mimics the behavior of the `_mm_aesenc_si128` intrinsic.
+    ///
+    /// The S-box strategy depends on the target: SBF programs use a lookup
+    /// table, which costs far fewer instructions, while other targets use
+    /// the constant-time bitsliced round of the `aes` crate. The
+    /// `sbox-table` and `sbox-computed` features override the choice, and
+    /// `sanitizer` selects the table so that no `aes` crate intrinsics or
+    /// inline assembly are linked in. With `small-code`, the round is a
+    /// single out-of-line function shared by every caller.
     #[inline(always)]
     pub(crate) fn aesenc(block: &mut Self, key: &Self) {
+        Self::aesenc_bytes(block, &key.0);
+    }
+
+    /// Like [`aesenc`](Self::aesenc), with the key given as its bytes. A
+    /// reference into the constant table keeps its address, which the
+    /// `folded-tables` round looks up.
+    #[cfg_attr(not(feature = "small-code"), inline(always))]
+    #[cfg_attr(feature = "small-code", inline(never))]
+    pub(crate) fn aesenc_bytes(block: &mut Self, key: &[u8; 16]) {
+        #[cfg(not(any(
+            feature = "sbox-table",
+            all(
+                not(feature = "sbox-computed"),
+                any(target_os = "solana", target_arch = "bpf")
+            )
+        )))]
         // cipher_round performs SubBytes, ShiftRows, MixColumns, and AddRoundKey (XOR)
-        cipher_round(&mut block.0, &key.0);
+        cipher_round(
+            Block::<aes::Aes128>::from_mut_slice(&mut block.0),
+            Block::<aes::Aes128>::from_slice(key),
+        );
+
+        #[cfg(any(
+            feature = "sbox-table",
+            all(
+                not(feature = "sbox-computed"),
+                any(target_os = "solana", target_arch = "bpf")
+            )
+        ))]
+        {
+            #[cfg(feature = "folded-tables")]
+            if let Some(i) = crate::constants::standard_index(key) {
+                return table::cipher_round_folded(&mut block.0, i);
+            }
+            table::cipher_round(&mut block.0, key);
+        }
+    }
+
+    /// Applies one AES round to four independent blocks, `blocks[i]` with
+    /// key `keys[i]`, with the same result as four [`aesenc`](Self::aesenc)
+    /// calls.
+    ///
+    /// The bitsliced round of the `aes` crate evaluates four blocks in one
+    /// parallel pass for the cost of one, so handing it the four lanes of a
+    /// Haraka-512 round together halves the work. The table round has no
+    /// such batching and does the four rounds in turn.
+    #[inline(always)]
+    pub(crate) fn aesenc4(blocks: &mut [Self; 4], keys: &[[u8; 16]; 4]) {
+        #[cfg(not(any(
+            feature = "sbox-table",
+            all(
+                not(feature = "sbox-computed"),
+                any(target_os = "solana", target_arch = "bpf")
+            )
+        )))]
+        {
+            // The parallel round takes eight blocks; the last four are
+            // left zero and discarded.
+            let mut par = [Self::default(); 8];
+            let mut par_keys = [Self::default(); 8];
+            par[..4].copy_from_slice(blocks);
+            for (par_key, key) in par_keys.iter_mut().zip(keys) {
+                *par_key = Self(*key);
+            }
+            Self::aesenc8(&mut par, &par_keys);
+            for (block, out) in blocks.iter_mut().zip(par) {
+                *block = out;
+            }
+        }
+
+        #[cfg(any(
+            feature = "sbox-table",
+            all(
+                not(feature = "sbox-computed"),
+                any(target_os = "solana", target_arch = "bpf")
+            )
+        ))]
+        {
+            for (block, key) in blocks.iter_mut().zip(keys) {
+                Self::aesenc_bytes(block, key);
+            }
+        }
+    }
+
+    /// Applies one AES round to eight independent blocks, `blocks[i]` with
+    /// key `keys[i]`, in two parallel passes of the bitsliced round. See
+    /// [`aesenc4`](Self::aesenc4).
+    #[cfg(not(any(
+        feature = "sbox-table",
+        all(
+            not(feature = "sbox-computed"),
+            any(target_os = "solana", target_arch = "bpf")
+        )
+    )))]
+    #[cfg_attr(not(feature = "small-code"), inline(always))]
+    #[cfg_attr(feature = "small-code", inline(never))]
+    pub(crate) fn aesenc8(blocks: &mut [Self; 8], keys: &[Self; 8]) {
+        let mut par = aes::Block8::default();
+        let mut par_keys = aes::Block8::default();
+        for i in 0..8 {
+            par[i] = blocks[i].0.into();
+            par_keys[i] = keys[i].0.into();
+        }
+        cipher_round_par(&mut par, &par_keys);
+        for (block, out) in blocks.iter_mut().zip(par) {
+            block.0 = out.into();
+        }
     }
 
     /// Performs a bitwise XOR operation.
@@ -53,16 +187,7 @@ impl Simd128 {
     /// result = [a0 a1 a2 a3 | b0 b1 b2 b3 | a4 a5 a6 a7 | b4 b5 b6 b7]
     #[inline(always)]
     pub(crate) fn unpacklo_epi32(dst: &mut Self, src: &Self) {
-        let mut res = [0u8; 16];
-        let a = dst.0.as_slice();
-        let b = src.0.as_slice();
-
-        res[0..4].copy_from_slice(&a[0..4]);
-        res[4..8].copy_from_slice(&b[0..4]);
-        res[8..12].copy_from_slice(&a[4..8]);
-        res[12..16].copy_from_slice(&b[4..8]);
-
-        dst.0 = Block::<Aes128>::clone_from_slice(&res);
+        *dst = Self::from_words(interleave32(dst.words()[0], src.words()[0]));
     }
 
     /// Interleaves the higher 4-byte words of `dst` and `src`.
@@ -71,16 +196,7 @@ impl Simd128 {
     /// result = [a8 a9 aa ab | b8 b9 ba bb | ac ad ae af | bc bd be bf]
     #[inline(always)]
     pub(crate) fn unpackhi_epi32(dst: &mut Self, src: &Self) {
-        let mut res = [0u8; 16];
-        let a = dst.0.as_slice();
-        let b = src.0.as_slice();
-
-        res[0..4].copy_from_slice(&a[8..12]);
-        res[4..8].copy_from_slice(&b[8..12]);
-        res[8..12].copy_from_slice(&a[12..16]);
-        res[12..16].copy_from_slice(&b[12..16]);
-
-        dst.0 = Block::<Aes128>::clone_from_slice(&res);
+        *dst = Self::from_words(interleave32(dst.words()[1], src.words()[1]));
     }
 
     /// Interleaves the lower 8-byte words of `lhs` and `rhs`.
@@ -89,14 +205,7 @@ impl Simd128 {
     /// result = [a0..a7 | b0..b7]
     #[inline(always)]
     pub(crate) fn unpacklo_epi64(lhs: &Self, rhs: &Self) -> Self {
-        let mut res = [0u8; 16];
-        let a = lhs.0.as_slice();
-        let b = rhs.0.as_slice();
-
-        res[0..8].copy_from_slice(&a[0..8]);
-        res[8..16].copy_from_slice(&b[0..8]);
-
-        Self(Block::<Aes128>::clone_from_slice(&res))
+        Self::from_words([lhs.words()[0], rhs.words()[0]])
     }
 
     /// Interleaves the higher 8-byte words of `lhs` and `rhs`.
@@ -105,31 +214,315 @@ impl Simd128 {
     /// result = [a8..af | b8..bf]
     #[inline(always)]
     pub(crate) fn unpackhi_epi64(lhs: &Self, rhs: &Self) -> Self {
-        let mut res = [0u8; 16];
-        let a = lhs.0.as_slice();
-        let b = rhs.0.as_slice();
+        Self::from_words([lhs.words()[1], rhs.words()[1]])
+    }
+}
+
+/// Table-driven AES round. Lookups are indexed by secret state bytes, so
+/// this variant is not constant-time on hardware with data caches.
+#[cfg(any(
+    test,
+    feature = "sbox-table",
+    all(
+        not(feature = "sbox-computed"),
+        any(target_os = "solana", target_arch = "bpf")
+    )
+))]
+mod table {
+    const fn xtime(x: u8) -> u8 {
+        (x << 1) ^ ((x >> 7) * 0x1b)
+    }
+
+    /// The AES S-box, computed at compile time from the inverse in GF(2^8)
+    /// and the affine map.
+    static SBOX: [u8; 256] = {
+        let mut sbox = [0u8; 256];
+        // Walk x = 3^i and y = 3^-i together: 3 generates the
+        // multiplicative group, and 0xf6 is its inverse.
+        let mut x: u8 = 1;
+        let mut y: u8 = 1;
+        let mut i = 0;
+        while i < 255 {
+            let b = y;
+            sbox[x as usize] = b
+                ^ b.rotate_left(1)
+                ^ b.rotate_left(2)
+                ^ b.rotate_left(3)
+                ^ b.rotate_left(4)
+                ^ 0x63;
+            x ^= xtime(x);
+            // y *= 0xf6, with 0xf6 = x^7 + x^6 + x^5 + x^4 + x^2 + x.
+            let mut p = 0u8;
+            let mut a = y;
+            let mut m = 0xf6u8;
+            while m != 0 {
+                if m & 1 != 0 {
+                    p ^= a;
+                }
+                a = xtime(a);
+                m >>= 1;
+            }
+            y = p;
+            i += 1;
+        }
+        sbox[0] = 0x63;
+        sbox
+    };
+
+    /// SubBytes, ShiftRows, MixColumns and AddRoundKey, like `aesenc`.
+    ///
+    /// Both passes read their input in address order (the state, then the
+    /// shifted bytes and the key), which keeps consecutive loads of the SBF
+    /// interpreter on neighbouring addresses; ShiftRows moves byte `r` of
+    /// column `c` to column `c - r`, on the store side.
+    #[inline(always)]
+    pub(super) fn cipher_round(block: &mut [u8; 16], key: &[u8; 16]) {
+        let s = block;
+        let mut t = [0u8; 16];
+        for c in 0..4 {
+            for r in 0..4 {
+                t[4 * ((c + 4 - r) % 4) + r] = SBOX[s[4 * c + r] as usize];
+            }
+        }
+        for c in 0..4 {
+            let [a0, a1, a2, a3] = [t[4 * c], t[4 * c + 1], t[4 * c + 2], t[4 * c + 3]];
+            let all = a0 ^ a1 ^ a2 ^ a3;
+            s[4 * c] = a0 ^ all ^ xtime(a0 ^ a1) ^ key[4 * c];
+            s[4 * c + 1] = a1 ^ all ^ xtime(a1 ^ a2) ^ key[4 * c + 1];
+            s[4 * c + 2] = a2 ^ all ^ xtime(a2 ^ a3) ^ key[4 * c + 2];
+            s[4 * c + 3] = a3 ^ all ^ xtime(a3 ^ a0) ^ key[4 * c + 3];
+        }
+    }
+
+    /// Multiplication in GF(2^8).
+    #[cfg(feature = "folded-tables")]
+    const fn gmul(mut a: u8, mut b: u8) -> u8 {
+        let mut p = 0;
+        while b != 0 {
+            if b & 1 != 0 {
+                p ^= a;
+            }
+            a = xtime(a);
+            b >>= 1;
+        }
+        p
+    }
+
+    /// The S-box with each standard round constant folded in: entry
+    /// `[i][j][b]` is `SBOX[b] ^ k[j]`, where `k` is InvMixColumns of
+    /// constant `i`. MixColumns is linear, so mixing the looked-up bytes
+    /// yields MixColumns of the S-box output XOR constant `i`, and the
+    /// AddRoundKey pass disappears. 192 KiB, one 4 KiB table per constant.
+    #[cfg(feature = "folded-tables")]
+    static FOLDED: [[[u8; 256]; 16]; crate::constants::N_CONSTANTS] = {
+        let mut folded = [[[0u8; 256]; 16]; crate::constants::N_CONSTANTS];
+        let mut i = 0;
+        while i < crate::constants::N_CONSTANTS {
+            let key = crate::constants::HARAKA_CONSTANTS[i / 8][i % 8];
+            let mut c = 0;
+            while c < 4 {
+                let [a0, a1, a2, a3] = [key[4 * c], key[4 * c + 1], key[4 * c + 2], key[4 * c + 3]];
+                let k = [
+                    gmul(a0, 14) ^ gmul(a1, 11) ^ gmul(a2, 13) ^ gmul(a3, 9),
+                    gmul(a0, 9) ^ gmul(a1, 14) ^ gmul(a2, 11) ^ gmul(a3, 13),
+                    gmul(a0, 13) ^ gmul(a1, 9) ^ gmul(a2, 14) ^ gmul(a3, 11),
+                    gmul(a0, 11) ^ gmul(a1, 13) ^ gmul(a2, 9) ^ gmul(a3, 14),
+                ];
+                let mut r = 0;
+                while r < 4 {
+                    let mut b = 0;
+                    while b < 256 {
+                        folded[i][4 * c + r][b] = SBOX[b] ^ k[r];
+                        b += 1;
+                    }
+                    r += 1;
+                }
+                c += 1;
+            }
+            i += 1;
+        }
+        folded
+    };
+
+    /// [`cipher_round`] with standard round constant `i`, through the
+    /// [`FOLDED`] tables.
+    #[cfg(feature = "folded-tables")]
+    #[inline(always)]
+    pub(super) fn cipher_round_folded(block: &mut [u8; 16], i: usize) {
+        let tables = &FOLDED[i];
+        let s = block;
+        let mut t = [0u8; 16];
+        for c in 0..4 {
+            for r in 0..4 {
+                let j = 4 * ((c + 4 - r) % 4) + r;
+                t[j] = tables[j][s[4 * c + r] as usize];
+            }
+        }
+        for c in 0..4 {
+            let [a0, a1, a2, a3] = [t[4 * c], t[4 * c + 1], t[4 * c + 2], t[4 * c + 3]];
+            let all = a0 ^ a1 ^ a2 ^ a3;
+            s[4 * c] = a0 ^ all ^ xtime(a0 ^ a1);
+            s[4 * c + 1] = a1 ^ all ^ xtime(a1 ^ a2);
+            s[4 * c + 2] = a2 ^ all ^ xtime(a2 ^ a3);
+            s[4 * c + 3] = a3 ^ all ^ xtime(a3 ^ a0);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use aes::{cipher::Block, Aes128};
+
+        #[test]
+        fn test_sbox() {
+            // FIPS-197, figure 7.
+            assert_eq!(SBOX[0x00], 0x63);
+            assert_eq!(SBOX[0x01], 0x7c);
+            assert_eq!(SBOX[0x53], 0xed);
+            assert_eq!(SBOX[0xff], 0x16);
+            let mut seen = [false; 256];
+            for &b in SBOX.iter() {
+                seen[b as usize] = true;
+            }
+            assert!(seen.iter().all(|&s| s));
+        }
+
+        #[test]
+        fn test_matches_aes_crate() {
+            let mut block = [0u8; 16];
+            let mut key = [0u8; 16];
+            let mut x = 0x9e3779b97f4a7c15u64;
+            for _ in 0..64 {
+                for b in block.iter_mut().chain(key.iter_mut()) {
+                    x ^= x << 13;
+                    x ^= x >> 7;
+                    x ^= x << 17;
+                    *b = x as u8;
+                }
+                let mut expect = Block::<Aes128>::from(block);
+                aes::hazmat::cipher_round(&mut expect, &key.into());
+                cipher_round(&mut block, &key);
+                assert_eq!(block, <[u8; 16]>::from(expect));
+            }
+        }
 
-        res[0..8].copy_from_slice(&a[8..16]);
-        res[8..16].copy_from_slice(&b[8..16]);
+        #[test]
+        #[cfg(feature = "folded-tables")]
+        fn test_folded_matches_plain() {
+            let constants = crate::constants::HARAKA_CONSTANTS.as_flattened();
+            let mut block: [u8; 16] = core::array::from_fn(|j| 17 * j as u8);
+            for (i, key) in constants.iter().enumerate() {
+                let mut expect = block;
+                cipher_round(&mut expect, key);
+                cipher_round_folded(&mut block, i);
+                assert_eq!(block, expect, "constant {i}");
+
+                assert_eq!(crate::constants::standard_index(key), Some(i));
+                let copy = *key;
+                assert_eq!(crate::constants::standard_index(&copy), None);
+            }
+        }
+    }
+}
 
-        Self(Block::<Aes128>::clone_from_slice(&res))
+// The unpack helpers, XOR and comparison work on the two 64-bit halves of
+// a lane, so SBF code moves each operand with two doubleword loads and
+// stores instead of sixteen byte moves; the `word_ops` test checks this. The result is built in one expression rather than in
+// a zeroed temporary, so no memset survives either.
+impl Simd128 {
+    /// Returns the low and high 64-bit halves, little-endian.
+    #[inline(always)]
+    fn words(self) -> [u64; 2] {
+        let x = u128::from_le_bytes(self.0);
+        [x as u64, (x >> 64) as u64]
     }
+
+    /// Builds a lane from its low and high 64-bit halves.
+    #[inline(always)]
+    fn from_words([lo, hi]: [u64; 2]) -> Self {
+        Self((u128::from(lo) | u128::from(hi) << 64).to_le_bytes())
+    }
+}
+
+/// Interleaves the two 4-byte words of `a` and `b`: `[a0, b0, a1, b1]`.
+#[inline(always)]
+fn interleave32(a: u64, b: u64) -> [u64; 2] {
+    const LOW: u64 = 0xffff_ffff;
+    [(a & LOW) | (b << 32), (a >> 32) | (b & !LOW)]
 }
 
 impl BitXorAssign for Simd128 {
     #[inline(always)]
     fn bitxor_assign(&mut self, rhs: Self) {
-        // Perform XOR element-wise since GenericArray doesn't implement BitXorAssign
-        for (a, b) in self.0.as_mut_slice().iter_mut().zip(rhs.0.as_slice()) {
-            *a ^= *b;
+        let ([a0, a1], [b0, b1]) = (self.words(), rhs.words());
+        *self = Self::from_words([a0 ^ b0, a1 ^ b1]);
+    }
+}
+
+// Compared as two words too, rather than through a byte-wise `memcmp`.
+impl PartialEq for Simd128 {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        let ([a0, a1], [b0, b1]) = (self.words(), other.words());
+        (a0 ^ b0) | (a1 ^ b1) == 0
+    }
+}
+
+/// Lane operations shared by [`Simd128`] and the wider vectors that process
+/// several 128-bit states in lockstep. The round functions are generic over
+/// this trait, so every width runs the same round logic.
+pub(crate) trait Lanes: Copy {
+    /// Applies one AES round to every 128-bit state, with the same key.
+    fn aesenc(block: &mut Self, key: &[u8; 16]);
+    /// Applies one AES round to four independent values, `blocks[i]` with
+    /// `keys[i]`. Implementations may interleave the four rounds.
+    #[inline(always)]
+    fn aesenc4(blocks: &mut [Self; 4], keys: &[[u8; 16]; 4]) {
+        for (block, key) in blocks.iter_mut().zip(keys) {
+            Self::aesenc(block, key);
         }
     }
+    fn pxor(dst: &mut Self, src: &Self);
+    fn unpacklo_epi32(dst: &mut Self, src: &Self);
+    fn unpackhi_epi32(dst: &mut Self, src: &Self);
+    fn unpacklo_epi64(lhs: &Self, rhs: &Self) -> Self;
+    fn unpackhi_epi64(lhs: &Self, rhs: &Self) -> Self;
 }
 
-// Implement Default for Simd128 if needed, e.g., for constants
-impl Default for Simd128 {
-    fn default() -> Self {
-        Self(Block::<Aes128>::default())
+impl Lanes for Simd128 {
+    #[inline(always)]
+    fn aesenc(block: &mut Self, key: &[u8; 16]) {
+        Simd128::aesenc_bytes(block, key);
+    }
+
+    #[inline(always)]
+    fn aesenc4(blocks: &mut [Self; 4], keys: &[[u8; 16]; 4]) {
+        Simd128::aesenc4(blocks, keys);
+    }
+
+    #[inline(always)]
+    fn pxor(dst: &mut Self, src: &Self) {
+        Simd128::pxor(dst, src);
+    }
+
+    #[inline(always)]
+    fn unpacklo_epi32(dst: &mut Self, src: &Self) {
+        Simd128::unpacklo_epi32(dst, src);
+    }
+
+    #[inline(always)]
+    fn unpackhi_epi32(dst: &mut Self, src: &Self) {
+        Simd128::unpackhi_epi32(dst, src);
+    }
+
+    #[inline(always)]
+    fn unpacklo_epi64(lhs: &Self, rhs: &Self) -> Self {
+        Simd128::unpacklo_epi64(lhs, rhs)
+    }
+
+    #[inline(always)]
+    fn unpackhi_epi64(lhs: &Self, rhs: &Self) -> Self {
+        Simd128::unpackhi_epi64(lhs, rhs)
     }
 }
 
@@ -157,6 +550,20 @@ mod tests {
         assert_eq!(dst, expect);
     }
 
+    #[test]
+    fn test_aesenc4_matches_aesenc() {
+        let blocks: [Simd128; 4] =
+            core::array::from_fn(|i| Simd128::from(0x0123_4567 * (i as u128 + 1)));
+        let keys = core::array::from_fn(|i| Simd128::from(!(0x89ab_cdef << (8 * i))));
+        let mut expect = blocks;
+        for (block, key) in expect.iter_mut().zip(&keys) {
+            Simd128::aesenc(block, key);
+        }
+        let mut x = blocks;
+        Simd128::aesenc4(&mut x, &keys.map(|key| key.0));
+        assert_eq!(x, expect);
+    }
+
     fn pxor_slice(dst: &mut [u8; 16], src: &[u8; 16]) {
         let mut dst_xmm = Simd128::read(dst);
         let src_xmm = Simd128::read(src);
@@ -0,0 +1,163 @@
+use crate::simd128::{Lanes, Simd128};
+
+/// Two 128-bit states processed in lockstep, like the two lanes of an
+/// AVX2 register or a pair of NEON registers. Every operation acts on both
+/// halves independently; round keys are broadcast to both.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Simd256([Simd128; 2]);
+
+impl Simd256 {
+    /// Builds a vector from its two halves.
+    #[inline(always)]
+    pub(crate) fn from_lanes(lo: Simd128, hi: Simd128) -> Self {
+        Self([lo, hi])
+    }
+
+    /// Returns the two halves.
+    #[inline(always)]
+    pub(crate) fn lanes(self) -> [Simd128; 2] {
+        self.0
+    }
+}
+
+impl Lanes for Simd256 {
+    #[inline(always)]
+    fn aesenc(block: &mut Self, key: &[u8; 16]) {
+        Simd128::aesenc_bytes(&mut block.0[0], key);
+        Simd128::aesenc_bytes(&mut block.0[1], key);
+    }
+
+    // The four lanes of both states fill the eight blocks of one parallel
+    // bitsliced round.
+    #[cfg(not(any(
+        feature = "sbox-table",
+        all(
+            not(feature = "sbox-computed"),
+            any(target_os = "solana", target_arch = "bpf")
+        )
+    )))]
+    #[inline(always)]
+    fn aesenc4(blocks: &mut [Self; 4], keys: &[[u8; 16]; 4]) {
+        let mut par = [Simd128::default(); 8];
+        let mut par_keys = [Simd128::default(); 8];
+        for i in 0..4 {
+            [par[2 * i], par[2 * i + 1]] = blocks[i].0;
+            [par_keys[2 * i], par_keys[2 * i + 1]] = [Simd128::read(&keys[i]); 2];
+        }
+        Simd128::aesenc8(&mut par, &par_keys);
+        for (i, block) in blocks.iter_mut().enumerate() {
+            block.0 = [par[2 * i], par[2 * i + 1]];
+        }
+    }
+
+    #[inline(always)]
+    fn pxor(dst: &mut Self, src: &Self) {
+        Simd128::pxor(&mut dst.0[0], &src.0[0]);
+        Simd128::pxor(&mut dst.0[1], &src.0[1]);
+    }
+
+    #[inline(always)]
+    fn unpacklo_epi32(dst: &mut Self, src: &Self) {
+        Simd128::unpacklo_epi32(&mut dst.0[0], &src.0[0]);
+        Simd128::unpacklo_epi32(&mut dst.0[1], &src.0[1]);
+    }
+
+    #[inline(always)]
+    fn unpackhi_epi32(dst: &mut Self, src: &Self) {
+        Simd128::unpackhi_epi32(&mut dst.0[0], &src.0[0]);
+        Simd128::unpackhi_epi32(&mut dst.0[1], &src.0[1]);
+    }
+
+    #[inline(always)]
+    fn unpacklo_epi64(lhs: &Self, rhs: &Self) -> Self {
+        Self([
+            Simd128::unpacklo_epi64(&lhs.0[0], &rhs.0[0]),
+            Simd128::unpacklo_epi64(&lhs.0[1], &rhs.0[1]),
+        ])
+    }
+
+    #[inline(always)]
+    fn unpackhi_epi64(lhs: &Self, rhs: &Self) -> Self {
+        Self([
+            Simd128::unpackhi_epi64(&lhs.0[0], &rhs.0[0]),
+            Simd128::unpackhi_epi64(&lhs.0[1], &rhs.0[1]),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrayref::array_ref;
+
+    fn pair() -> ([u8; 32], [u8; 32]) {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        for i in 0..32 {
+            a[i] = i as u8;
+            b[i] = 0x80 | (3 * i) as u8;
+        }
+        (a, b)
+    }
+
+    fn read(src: &[u8; 32]) -> Simd256 {
+        Simd256::from_lanes(
+            Simd128::read(array_ref![src, 0, 16]),
+            Simd128::read(array_ref![src, 16, 16]),
+        )
+    }
+
+    /// Applies `op` to a Simd256 and to each of its halves separately.
+    fn check(op: fn(&mut Simd128, &Simd128), wide: fn(&mut Simd256, &Simd256)) {
+        let (a, b) = pair();
+        let mut x = read(&a);
+        let y = read(&b);
+        wide(&mut x, &y);
+        let [mut lo, mut hi] = read(&a).lanes();
+        let [ylo, yhi] = y.lanes();
+        op(&mut lo, &ylo);
+        op(&mut hi, &yhi);
+        assert_eq!(x, Simd256::from_lanes(lo, hi));
+    }
+
+    #[test]
+    fn test_halves_are_independent() {
+        check(Simd128::pxor, <Simd256 as Lanes>::pxor);
+        check(Simd128::unpacklo_epi32, <Simd256 as Lanes>::unpacklo_epi32);
+        check(Simd128::unpackhi_epi32, <Simd256 as Lanes>::unpackhi_epi32);
+        check(
+            |x, y| *x = Simd128::unpacklo_epi64(x, y),
+            |x, y| *x = <Simd256 as Lanes>::unpacklo_epi64(x, y),
+        );
+        check(
+            |x, y| *x = Simd128::unpackhi_epi64(x, y),
+            |x, y| *x = <Simd256 as Lanes>::unpackhi_epi64(x, y),
+        );
+    }
+
+    #[test]
+    fn test_aesenc_broadcasts_key() {
+        let (a, b) = pair();
+        let key = array_ref![b, 0, 16];
+        let mut x = read(&a);
+        <Simd256 as Lanes>::aesenc(&mut x, key);
+        let [mut lo, mut hi] = read(&a).lanes();
+        Simd128::aesenc_bytes(&mut lo, key);
+        Simd128::aesenc_bytes(&mut hi, key);
+        assert_eq!(x, Simd256::from_lanes(lo, hi));
+    }
+
+    #[test]
+    fn test_aesenc4_matches_aesenc() {
+        let (a, b) = pair();
+        let keys = core::array::from_fn(|i| *array_ref![b, 4 * i, 16]);
+        let mut x = [read(&a), read(&b), read(&a), read(&b)];
+        x[2].0[1] = Simd128::read(&keys[3]);
+        let mut expect = x;
+        for (lanes, key) in expect.iter_mut().zip(&keys) {
+            <Simd256 as Lanes>::aesenc(lanes, key);
+        }
+        <Simd256 as Lanes>::aesenc4(&mut x, &keys);
+        assert_eq!(x, expect);
+    }
+}
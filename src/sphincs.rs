@@ -0,0 +1,944 @@
+//! Zero-copy views of SPHINCS+ public keys and signatures.
+//!
+//! A SPHINCS+ signature is the randomizer `R`, a FORS signature and a
+//! hypertree signature of `d` XMSS layers:
+//!
+//! ```text
+//! R (n) || k * (secret (n) || a siblings (a * n)) || d * (WOTS+ (len * n) || h / d siblings)
+//! ```
+//!
+//! and a public key is `PK.seed || PK.root`, `n` bytes each. The views
+//! check the length once, against the [`Params`] they are created with,
+//! and then hand out sub-slices of the instruction data without copying,
+//! so parsing costs no allocation and no stack beyond the view itself.
+//! Accessors taking an index return `None` past the last tree or layer.
+//!
+//! The six Haraka parameter sets of the submission are marker types
+//! implementing [`ParameterSet`], such as [`Haraka128f`].
+//!
+//! [`verify`] checks a signature with the "simple" tweakable hashes of
+//! the PQClean reference: every hash uses Haraka round constants derived
+//! from `PK.seed`, `F` is Haraka-512 of `address || x` and the other
+//! hashes are Haraka-S of `address || inputs`, all truncated to `n`
+//! bytes. Tests check a signature made by that reference, and one for a
+//! small `log_w = 8` set, where the WOTS+ checksum follows the SPHINCS+
+//! reference implementation.
+
+use crate::backend::DefaultBackend;
+use crate::constants::{self, RoundConstants};
+use crate::error::Error;
+use crate::haraka512::{haraka512_lanes_with, load4};
+use crate::sponge::{self, HarakaS, RATE};
+use crate::wots;
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// The numbers that shape a SPHINCS+ parameter set.
+///
+/// The standard sets have `log_w = 4` and `h` a multiple of `d`. A zero
+/// `d`, or a `log_w` other than 1, 2, 4 or 8, gives lengths of zero
+/// rather than a panic, and so does a length that overflows `usize`; the
+/// views reject parameters of the latter kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Hash output length in bytes.
+    pub n: usize,
+    /// Total height of the hypertree.
+    pub h: usize,
+    /// Number of hypertree layers; each XMSS tree is `h / d` levels high.
+    pub d: usize,
+    /// Height of each FORS tree.
+    pub a: usize,
+    /// Number of FORS trees.
+    pub k: usize,
+    /// Base-2 logarithm of the Winternitz parameter `w`.
+    pub log_w: u32,
+}
+
+/// `?` for `Option` in a `const fn`.
+macro_rules! checked {
+    ($value:expr) => {
+        match $value {
+            Some(value) => value,
+            None => return None,
+        }
+    };
+}
+
+/// The value of a checked length, or zero if it overflowed.
+const fn or_zero(len: Option<usize>) -> usize {
+    match len {
+        Some(len) => len,
+        None => 0,
+    }
+}
+
+impl Params {
+    /// Number of WOTS+ digits of an `n`-byte message.
+    pub const fn wots_len1(&self) -> usize {
+        or_zero(self.checked_wots_len1())
+    }
+
+    /// Number of WOTS+ checksum digits.
+    pub const fn wots_len2(&self) -> usize {
+        or_zero(self.checked_wots_len2())
+    }
+
+    /// Number of WOTS+ chains.
+    pub const fn wots_len(&self) -> usize {
+        or_zero(self.checked_wots_len())
+    }
+
+    /// Height of each XMSS tree of the hypertree.
+    pub const fn tree_height(&self) -> usize {
+        match self.d {
+            0 => 0,
+            d => self.h / d,
+        }
+    }
+
+    /// Length of one FORS tree's part of the signature.
+    pub const fn fors_tree_len(&self) -> usize {
+        or_zero(self.checked_fors_tree_len())
+    }
+
+    /// Length of one XMSS layer's part of the signature.
+    pub const fn layer_len(&self) -> usize {
+        or_zero(self.checked_layer_len())
+    }
+
+    /// Length of a signature.
+    pub const fn signature_len(&self) -> usize {
+        or_zero(self.checked_signature_len())
+    }
+
+    /// Length of a public key.
+    pub const fn public_key_len(&self) -> usize {
+        or_zero(self.n.checked_mul(2))
+    }
+
+    const fn checked_wots_len1(&self) -> Option<usize> {
+        match self.log_w {
+            1 | 2 | 4 | 8 => Some(checked!(self.n.checked_mul(8)) / self.log_w as usize),
+            _ => Some(0),
+        }
+    }
+
+    const fn checked_wots_len2(&self) -> Option<usize> {
+        match self.log_w {
+            log_w @ (1 | 2 | 4 | 8) => {
+                let len1 = checked!(self.checked_wots_len1()) as u64;
+                let max = checked!(len1.checked_mul((1 << log_w) - 1));
+                Some((64 - max.leading_zeros()).div_ceil(log_w) as usize)
+            }
+            _ => Some(0),
+        }
+    }
+
+    const fn checked_wots_len(&self) -> Option<usize> {
+        checked!(self.checked_wots_len1()).checked_add(checked!(self.checked_wots_len2()))
+    }
+
+    const fn checked_fors_tree_len(&self) -> Option<usize> {
+        checked!(self.a.checked_add(1)).checked_mul(self.n)
+    }
+
+    const fn checked_layer_len(&self) -> Option<usize> {
+        let len = checked!(checked!(self.checked_wots_len()).checked_add(self.tree_height()));
+        len.checked_mul(self.n)
+    }
+
+    const fn checked_signature_len(&self) -> Option<usize> {
+        let fors = checked!(self.k.checked_mul(checked!(self.checked_fors_tree_len())));
+        let hypertree = checked!(self.d.checked_mul(checked!(self.checked_layer_len())));
+        checked!(self.n.checked_add(fors)).checked_add(hypertree)
+    }
+}
+
+/// A named SPHINCS+ parameter set, for code that fixes the set at compile
+/// time: `SphincsSignatureRef::parse::<Haraka128f>(bytes)` rather than
+/// seven numbers.
+pub trait ParameterSet {
+    /// Name of the set in the SPHINCS+ submission.
+    const NAME: &'static str;
+    /// The numbers of the set.
+    const PARAMS: Params;
+    /// Length of a signature.
+    const SIGNATURE_LEN: usize = Self::PARAMS.signature_len();
+    /// Length of a public key.
+    const PUBLIC_KEY_LEN: usize = Self::PARAMS.public_key_len();
+}
+
+macro_rules! parameter_sets {
+    ($($(#[$doc:meta])* $ty:ident = $name:literal { $($field:ident: $value:expr),* })*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+            pub struct $ty;
+
+            impl ParameterSet for $ty {
+                const NAME: &'static str = $name;
+                const PARAMS: Params = Params { $($field: $value,)* log_w: 4 };
+            }
+        )*
+    };
+}
+
+parameter_sets! {
+    /// SPHINCS+-Haraka-128s: small signatures, slow signing.
+    Haraka128s = "sphincs-haraka-128s" { n: 16, h: 63, d: 7, a: 12, k: 14 }
+    /// SPHINCS+-Haraka-128f: fast signing, larger signatures.
+    Haraka128f = "sphincs-haraka-128f" { n: 16, h: 66, d: 22, a: 6, k: 33 }
+    /// SPHINCS+-Haraka-192s.
+    Haraka192s = "sphincs-haraka-192s" { n: 24, h: 63, d: 7, a: 14, k: 17 }
+    /// SPHINCS+-Haraka-192f.
+    Haraka192f = "sphincs-haraka-192f" { n: 24, h: 66, d: 22, a: 8, k: 33 }
+    /// SPHINCS+-Haraka-256s.
+    Haraka256s = "sphincs-haraka-256s" { n: 32, h: 64, d: 8, a: 14, k: 22 }
+    /// SPHINCS+-Haraka-256f.
+    Haraka256f = "sphincs-haraka-256f" { n: 32, h: 68, d: 17, a: 9, k: 35 }
+}
+
+/// A SPHINCS+ public key borrowed from a byte slice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SphincsPublicKeyRef<'a> {
+    seed: &'a [u8],
+    root: &'a [u8],
+}
+
+impl<'a> SphincsPublicKeyRef<'a> {
+    /// Borrows the public key in `bytes`.
+    ///
+    /// Fails with [`Error::InvalidLength`] unless `bytes` is exactly
+    /// [`Params::public_key_len`] bytes long, with `expected` set to
+    /// `usize::MAX` if that length overflows.
+    pub fn new(params: &Params, bytes: &'a [u8]) -> Result<Self, Error> {
+        check_len(params.n.checked_mul(2), bytes)?;
+        let (seed, root) = bytes.split_at(params.n);
+        Ok(Self { seed, root })
+    }
+
+    /// Borrows a public key of the parameter set `P`.
+    pub fn parse<P: ParameterSet>(bytes: &'a [u8]) -> Result<Self, Error> {
+        Self::new(&P::PARAMS, bytes)
+    }
+
+    /// Returns `PK.seed`.
+    pub fn seed(&self) -> &'a [u8] {
+        self.seed
+    }
+
+    /// Returns `PK.root`, the root of the top XMSS tree.
+    pub fn root(&self) -> &'a [u8] {
+        self.root
+    }
+}
+
+/// A SPHINCS+ signature borrowed from a byte slice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SphincsSignatureRef<'a> {
+    params: Params,
+    bytes: &'a [u8],
+}
+
+impl<'a> SphincsSignatureRef<'a> {
+    /// Borrows the signature in `bytes`.
+    ///
+    /// Fails with [`Error::InvalidLength`] unless `bytes` is exactly
+    /// [`Params::signature_len`] bytes long, with `expected` set to
+    /// `usize::MAX` if that length overflows.
+    pub fn new(params: &Params, bytes: &'a [u8]) -> Result<Self, Error> {
+        check_len(params.checked_signature_len(), bytes)?;
+        Ok(Self {
+            params: *params,
+            bytes,
+        })
+    }
+
+    /// Borrows a signature of the parameter set `P`.
+    pub fn parse<P: ParameterSet>(bytes: &'a [u8]) -> Result<Self, Error> {
+        Self::new(&P::PARAMS, bytes)
+    }
+
+    /// Returns the parameters the signature was parsed with.
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    /// Returns the whole signature.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the randomizer `R`.
+    pub fn randomizer(&self) -> &'a [u8] {
+        self.part(0, self.params.n)
+    }
+
+    /// Returns the FORS signature, all `k` trees.
+    pub fn fors_bytes(&self) -> &'a [u8] {
+        let p = &self.params;
+        self.part(p.n, p.k * p.fors_tree_len())
+    }
+
+    /// Returns the part of FORS tree `i`.
+    pub fn fors_tree(&self, i: usize) -> Option<ForsTreeRef<'a>> {
+        let p = &self.params;
+        if i >= p.k {
+            return None;
+        }
+        let tree = self.part(p.n + i * p.fors_tree_len(), p.fors_tree_len());
+        let (secret, auth_path) = tree.split_at(p.n.min(tree.len()));
+        Some(ForsTreeRef {
+            n: p.n,
+            secret,
+            auth_path,
+        })
+    }
+
+    /// Returns the hypertree signature, all `d` layers.
+    pub fn hypertree_bytes(&self) -> &'a [u8] {
+        let p = &self.params;
+        self.part(p.n + p.k * p.fors_tree_len(), p.d * p.layer_len())
+    }
+
+    /// Returns the part of hypertree layer `i`, counted from the bottom.
+    pub fn layer(&self, i: usize) -> Option<XmssLayerRef<'a>> {
+        let p = &self.params;
+        if i >= p.d {
+            return None;
+        }
+        let start = p.n + p.k * p.fors_tree_len() + i * p.layer_len();
+        let layer = self.part(start, p.layer_len());
+        let (wots_signature, auth_path) = layer.split_at((p.wots_len() * p.n).min(layer.len()));
+        Some(XmssLayerRef {
+            n: p.n,
+            wots_signature,
+            auth_path,
+        })
+    }
+
+    /// Returns `len` bytes from `start`; the length check in
+    /// [`new`](Self::new) keeps every part in bounds, and an empty slice
+    /// stands in otherwise.
+    fn part(&self, start: usize, len: usize) -> &'a [u8] {
+        start
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(start..end))
+            .unwrap_or_default()
+    }
+}
+
+/// One FORS tree's part of a signature: the revealed secret and its
+/// authentication path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForsTreeRef<'a> {
+    n: usize,
+    secret: &'a [u8],
+    auth_path: &'a [u8],
+}
+
+impl<'a> ForsTreeRef<'a> {
+    /// Returns the revealed secret value.
+    pub fn secret(&self) -> &'a [u8] {
+        self.secret
+    }
+
+    /// Returns the `a` siblings, leaf level first.
+    pub fn auth_path(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.auth_path.chunks_exact(self.n.max(1))
+    }
+}
+
+/// One hypertree layer's part of a signature: a WOTS+ signature and the
+/// authentication path of its key in the layer's XMSS tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XmssLayerRef<'a> {
+    n: usize,
+    wots_signature: &'a [u8],
+    auth_path: &'a [u8],
+}
+
+impl<'a> XmssLayerRef<'a> {
+    /// Returns the WOTS+ signature, `len` chain values of `n` bytes.
+    pub fn wots_signature(&self) -> &'a [u8] {
+        self.wots_signature
+    }
+
+    /// Returns the WOTS+ chain values.
+    pub fn wots_chains(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.wots_signature.chunks_exact(self.n.max(1))
+    }
+
+    /// Returns the `h / d` siblings, leaf level first.
+    pub fn auth_path(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.auth_path.chunks_exact(self.n.max(1))
+    }
+}
+
+/// Returns `true` if `sig` is a SPHINCS+-Haraka signature of `msg` under
+/// `pk`, with the "simple" tweakable hashes.
+///
+/// The parameters are those `sig` was parsed with. Any set of the
+/// submission verifies; other parameters are accepted as long as `n` is
+/// at most 32, each tree is 1 to 31 levels high, `h` is a multiple of `d`
+/// and the tree index fits in 64 bits, and are rejected otherwise. A
+/// SPHINCS+-Haraka-128f signature costs about 6,000 Haraka-512 calls, far
+/// more than one instruction's compute budget.
+pub fn verify(pk: &SphincsPublicKeyRef<'_>, msg: &[u8], sig: &SphincsSignatureRef<'_>) -> bool {
+    let p = sig.params();
+    if !verifiable(p) || pk.seed().len() != p.n {
+        return false;
+    }
+    let thash = Thash::new(pk.seed(), p.n);
+    let tree_height = p.tree_height() as u32;
+
+    let mut digest = thash.sponge();
+    digest.absorb(sig.randomizer());
+    digest.absorb(pk.root());
+    digest.absorb(msg);
+    let mut fors_digest = digest.finalize();
+    // The tree and leaf indices follow the FORS indices in the digest.
+    let mut digest = fors_digest.clone();
+    digest.skip((p.k * p.a).div_ceil(8));
+    let tree_bits = (p.h - p.tree_height()) as u32;
+    let mut tree = digest.read_be(tree_bits.div_ceil(8)) & mask(tree_bits);
+    let mut leaf = (digest.read_be(tree_height.div_ceil(8)) & mask(tree_height)) as u32;
+
+    let mut addr = [0; 8];
+    set_tree(&mut addr, tree);
+    addr[KEYPAIR] = leaf;
+    let mut root = thash.fors_public_key(&addr, &mut fors_digest, sig);
+    for (layer, part) in (0..).zip((0..p.d).filter_map(|i| sig.layer(i))) {
+        let mut addr = [0; 8];
+        addr[LAYER] = layer;
+        set_tree(&mut addr, tree);
+        root = thash.layer_root(&addr, leaf, &root, &part, p);
+        leaf = (tree & mask(tree_height)) as u32;
+        tree >>= tree_height;
+    }
+    crate::ct::eq(&root, &node(pk.root()))
+}
+
+/// Returns `true` if [`verify`] supports `p`.
+fn verifiable(p: &Params) -> bool {
+    let tree_height = p.tree_height();
+    (1..=32).contains(&p.n)
+        && p.wots_len() != 0
+        && (1..32).contains(&tree_height)
+        && tree_height * p.d == p.h
+        && p.h - tree_height <= 64
+        && (1..32).contains(&p.a)
+        && p.k != 0
+}
+
+/// An address: eight words, serialized big-endian.
+type Address = [u32; 8];
+
+/// Address words: the layer, the tree (words 1 to 3), the type, the key
+/// pair, and then the chain and position in the chain of a WOTS+ hash or
+/// the height and index of a tree node.
+const LAYER: usize = 0;
+const TYPE: usize = 4;
+const KEYPAIR: usize = 5;
+const CHAIN: usize = 6;
+const HASH: usize = 7;
+
+/// Address types.
+const WOTS_HASH: u32 = 0;
+const WOTS_PK: u32 = 1;
+const HASH_TREE: u32 = 2;
+const FORS_TREE: u32 = 3;
+const FORS_PK: u32 = 4;
+
+#[inline(always)]
+fn set_tree(addr: &mut Address, tree: u64) {
+    addr[1] = 0;
+    addr[2] = (tree >> 32) as u32;
+    addr[3] = tree as u32;
+}
+
+#[inline(always)]
+fn address_bytes(addr: &Address) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (out, word) in bytes.chunks_exact_mut(4).zip(addr) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    bytes
+}
+
+/// The low `bits` bits set, for `bits` up to 64.
+#[inline(always)]
+fn mask(bits: u32) -> u64 {
+    u64::MAX.checked_shr(64 - bits).unwrap_or(0)
+}
+
+/// Copies an `n`-byte value into a zero-padded 32-byte node.
+#[inline(always)]
+fn node(value: &[u8]) -> [u8; 32] {
+    core::array::from_fn(|i| value.get(i).copied().unwrap_or(0))
+}
+
+/// The tweakable hash of one public key. Its round constants are the
+/// first 640 bytes of `HarakaS(PK.seed)`, as the reference derives them.
+struct Thash {
+    rc: RoundConstants,
+    n: usize,
+}
+
+impl Thash {
+    fn new(seed: &[u8], n: usize) -> Self {
+        let mut hasher = HarakaS::new();
+        hasher.update(seed);
+        let mut reader = hasher.finalize_xof();
+        let mut rc = *constants::HARAKA_CONSTANTS;
+        for lane in rc[..ROUNDS].as_flattened_mut() {
+            reader.squeeze(lane);
+        }
+        Self { rc, n }
+    }
+
+    /// `F`, the chain and leaf hash: Haraka-512 of `address || x || 0^(32 - n)`.
+    #[inline(always)]
+    fn f(&self, addr: &Address, x: &[u8; 32]) -> [u8; 32] {
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(&address_bytes(addr));
+        block[32..].copy_from_slice(x);
+        let mut out = [0u8; 32];
+        haraka512_lanes_with::<ROUNDS>(&mut out, &load4::<DefaultBackend>(&block), &self.rc);
+        self.truncate(out)
+    }
+
+    /// Starts Haraka-S over `address`, for a hash of several values.
+    #[inline(always)]
+    fn start(&self, addr: &Address) -> Sponge<'_> {
+        let mut sponge = self.sponge();
+        sponge.absorb(&address_bytes(addr));
+        sponge
+    }
+
+    #[inline(always)]
+    fn sponge(&self) -> Sponge<'_> {
+        Sponge {
+            thash: self,
+            state: [0u8; 64],
+            pos: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn truncate(&self, value: [u8; 32]) -> [u8; 32] {
+        // Selecting each byte, rather than zeroing the tail, keeps a
+        // memset out of every `F`.
+        core::array::from_fn(|i| if i < self.n { value[i] } else { 0 })
+    }
+
+    /// Public key of the FORS signature of `sig`, for the FORS key at
+    /// `addr`, reading the tree indices from `digest`.
+    fn fors_public_key(
+        &self,
+        addr: &Address,
+        digest: &mut Sponge<'_>,
+        sig: &SphincsSignatureRef<'_>,
+    ) -> [u8; 32] {
+        let p = sig.params();
+        let mut tree_addr = *addr;
+        tree_addr[TYPE] = FORS_TREE;
+        let mut pk_addr = *addr;
+        pk_addr[TYPE] = FORS_PK;
+        let mut roots = self.start(&pk_addr);
+        // Indices are read from the least significant bit of each byte.
+        let mut byte = 0;
+        let mut bit = 0;
+        for (i, tree) in (0u32..).zip((0..p.k).filter_map(|i| sig.fors_tree(i))) {
+            let mut index = 0u32;
+            for j in 0..p.a {
+                if bit % 8 == 0 {
+                    byte = digest.byte();
+                }
+                index |= ((byte >> (bit % 8)) as u32 & 1) << j;
+                bit += 1;
+            }
+            let offset = i << p.a;
+            tree_addr[CHAIN] = 0;
+            tree_addr[HASH] = index.wrapping_add(offset);
+            let leaf = self.f(&tree_addr, &node(tree.secret()));
+            let root = self.compute_root(&mut tree_addr, leaf, index, offset, tree.auth_path());
+            roots.absorb_node(&root);
+        }
+        roots.finish()
+    }
+
+    /// Root of the XMSS tree at `addr` from its layer of the signature,
+    /// where key pair `leaf` signs `msg`.
+    fn layer_root(
+        &self,
+        addr: &Address,
+        leaf: u32,
+        msg: &[u8; 32],
+        part: &XmssLayerRef<'_>,
+        p: &Params,
+    ) -> [u8; 32] {
+        let mut wots_addr = *addr;
+        wots_addr[TYPE] = WOTS_HASH;
+        wots_addr[KEYPAIR] = leaf;
+        let mut pk_addr = *addr;
+        pk_addr[TYPE] = WOTS_PK;
+        pk_addr[KEYPAIR] = leaf;
+        let mut public_key = self.start(&pk_addr);
+        let w_max = (1 << p.log_w) - 1;
+        let mut chains = (0u32..).zip(part.wots_chains());
+        let mut walk = |start: u8| {
+            let Some((i, chain)) = chains.next() else {
+                return;
+            };
+            wots_addr[CHAIN] = i;
+            let mut value = node(chain);
+            for step in start as u32..w_max {
+                wots_addr[HASH] = step;
+                value = self.f(&wots_addr, &value);
+            }
+            public_key.absorb_node(&value);
+        };
+        let msg = &msg[..self.n.min(32)];
+        let len2 = p.wots_len2();
+        match p.log_w {
+            1 => wots_digits::<1>(msg, len2, &mut walk),
+            2 => wots_digits::<2>(msg, len2, &mut walk),
+            4 => wots_digits::<4>(msg, len2, &mut walk),
+            // `verifiable` admits no other value.
+            _ => wots_digits::<8>(msg, len2, &mut walk),
+        }
+        let leaf_node = public_key.finish();
+
+        let mut tree_addr = *addr;
+        tree_addr[TYPE] = HASH_TREE;
+        self.compute_root(&mut tree_addr, leaf_node, leaf, 0, part.auth_path())
+    }
+
+    /// Walks `auth_path` from `leaf`, at position `index` of a tree whose
+    /// node indices are offset by `offset` at the leaf level.
+    fn compute_root<'a>(
+        &self,
+        addr: &mut Address,
+        leaf: [u8; 32],
+        mut index: u32,
+        mut offset: u32,
+        auth_path: impl Iterator<Item = &'a [u8]>,
+    ) -> [u8; 32] {
+        let mut current = leaf;
+        for (height, sibling) in (1..).zip(auth_path) {
+            let sibling = node(sibling);
+            let (left, right) = if index & 1 == 0 {
+                (current, sibling)
+            } else {
+                (sibling, current)
+            };
+            index >>= 1;
+            offset >>= 1;
+            addr[CHAIN] = height;
+            addr[HASH] = index.wrapping_add(offset);
+            let mut hash = self.start(addr);
+            hash.absorb_node(&left);
+            hash.absorb_node(&right);
+            current = hash.finish();
+        }
+        current
+    }
+}
+
+/// Calls `walk` with each WOTS+ digit of `msg` and then with each of the
+/// `len2` digits of their checksum, in chain order.
+///
+/// The digits are those of [`wots::base_w`] and [`wots::checksum_digits`],
+/// which encode the checksum as the SPHINCS+ reference does: shifted to a
+/// byte boundary by `(8 - len2 * LOG_W % 8) % 8` bits and read back
+/// alongside the message digits.
+#[inline(always)]
+fn wots_digits<const LOG_W: u32>(msg: &[u8], len2: usize, walk: &mut impl FnMut(u8)) {
+    let mut digits = [0u8; 8];
+    let digits = &mut digits[..8 / LOG_W as usize];
+    let mut checksum = 0;
+    for byte in msg {
+        wots::write_base_w::<LOG_W>(digits, core::slice::from_ref(byte));
+        checksum += wots::checksum::<LOG_W>(digits);
+        digits.iter().for_each(|&digit| walk(digit));
+    }
+    // At most 9 digits, for 256 message bits with `LOG_W = 1`.
+    let mut checksum_digits = [0u8; 16];
+    let checksum_digits = &mut checksum_digits[..len2.min(16)];
+    wots::write_checksum_digits::<LOG_W>(checksum_digits, checksum);
+    checksum_digits.iter().for_each(|&digit| walk(digit));
+}
+
+/// Haraka-S with the round constants of a [`Thash`].
+#[derive(Clone)]
+struct Sponge<'a> {
+    thash: &'a Thash,
+    state: [u8; 64],
+    pos: usize,
+}
+
+impl Sponge<'_> {
+    #[inline(always)]
+    fn absorb(&mut self, data: &[u8]) {
+        sponge::absorb_with(&mut self.state, &mut self.pos, data, &self.thash.rc);
+    }
+
+    /// Absorbs the `n` bytes of `node`.
+    #[inline(always)]
+    fn absorb_node(&mut self, node: &[u8; 32]) {
+        self.absorb(&node[..self.thash.n.min(32)]);
+    }
+
+    /// Pads the input; the sponge then squeezes.
+    #[inline(always)]
+    fn finalize(mut self) -> Self {
+        sponge::pad(&mut self.state, self.pos);
+        self.pos = RATE;
+        self
+    }
+
+    /// Pads the input and returns the first `n` output bytes.
+    #[inline(always)]
+    fn finish(self) -> [u8; 32] {
+        let mut sponge = self.finalize();
+        let mut out = [0u8; 32];
+        sponge.squeeze(&mut out[..sponge.thash.n.min(32)]);
+        out
+    }
+
+    #[inline(always)]
+    fn squeeze(&mut self, out: &mut [u8]) {
+        sponge::squeeze_with(&mut self.state, &mut self.pos, out, &self.thash.rc);
+    }
+
+    #[inline(always)]
+    fn byte(&mut self) -> u8 {
+        let mut byte = [0u8; 1];
+        self.squeeze(&mut byte);
+        byte[0]
+    }
+
+    fn skip(&mut self, len: usize) {
+        for _ in 0..len {
+            self.byte();
+        }
+    }
+
+    /// Squeezes `len` bytes, at most 8, as a big-endian integer.
+    fn read_be(&mut self, len: u32) -> u64 {
+        (0..len).fold(0, |value, _| value << 8 | self.byte() as u64)
+    }
+}
+
+/// Checks that `bytes` is `expected` bytes long, where `None` is a length
+/// that overflowed.
+#[inline(always)]
+fn check_len(expected: Option<usize>, bytes: &[u8]) -> Result<(), Error> {
+    if expected != Some(bytes.len()) {
+        return Err(Error::InvalidLength {
+            expected: expected.unwrap_or(usize::MAX),
+            actual: bytes.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P128F: Params = Haraka128f::PARAMS;
+
+    #[test]
+    fn test_lengths_match_submission() {
+        fn check<P: ParameterSet>(signature_len: usize, public_key_len: usize) {
+            assert_eq!(P::SIGNATURE_LEN, signature_len, "{}", P::NAME);
+            assert_eq!(P::PUBLIC_KEY_LEN, public_key_len, "{}", P::NAME);
+            assert_eq!(P::PARAMS.wots_len(), 2 * P::PARAMS.n + 3);
+            assert_eq!(P::PARAMS.h % P::PARAMS.d, 0);
+        }
+        check::<Haraka128s>(7856, 32);
+        check::<Haraka128f>(17088, 32);
+        check::<Haraka192s>(16224, 48);
+        check::<Haraka192f>(35664, 48);
+        check::<Haraka256s>(29792, 64);
+        check::<Haraka256f>(49856, 64);
+    }
+
+    #[test]
+    fn test_signature_parts() {
+        let p = P128F;
+        // Byte `i` holds the index of the n-byte unit it falls in.
+        let bytes: [u8; 17088] = core::array::from_fn(|i| (i / p.n) as u8);
+        let sig = SphincsSignatureRef::parse::<Haraka128f>(&bytes).unwrap();
+        assert_eq!(sig, SphincsSignatureRef::new(&p, &bytes).unwrap());
+
+        assert_eq!(sig.randomizer(), &[0; 16]);
+        assert_eq!(sig.fors_bytes().len(), 33 * 7 * 16);
+        let tree = sig.fors_tree(2).unwrap();
+        assert_eq!(tree.secret(), &[1 + 2 * 7; 16]);
+        assert_eq!(tree.auth_path().count(), 6);
+        assert_eq!(tree.auth_path().last(), Some(&[1 + 2 * 7 + 6; 16][..]));
+        assert!(sig.fors_tree(33).is_none());
+
+        let units_before_ht = 1 + 33 * 7;
+        assert_eq!(sig.hypertree_bytes().len(), 22 * (35 + 3) * 16);
+        let layer = sig.layer(1).unwrap();
+        let first = (units_before_ht + 38) as u8;
+        assert_eq!(layer.wots_signature().len(), 35 * 16);
+        assert_eq!(layer.wots_chains().next(), Some(&[first; 16][..]));
+        let path = [[first + 35; 16], [first + 36; 16], [first + 37; 16]];
+        assert!(layer.auth_path().eq(path.iter().map(|node| &node[..])));
+        let last = sig.layer(21).unwrap();
+        assert_eq!(last.auth_path().last(), Some(&bytes[bytes.len() - 16..]));
+        assert!(sig.layer(22).is_none());
+    }
+
+    /// A key pair and a signature of `REFERENCE_MSG` made by the PQClean
+    /// `sphincs-haraka-128f-simple` code: the key from the 48-byte seed
+    /// `0, 1, ..., 47` (so `PK.seed` is `32, ..., 47`), the signature with
+    /// `randombytes` returning the bytes `0xa0, 0xa1, ...`. The file holds
+    /// the public key followed by the signature.
+    const REFERENCE: &[u8; 32 + 17088] =
+        include_bytes!("../vectors/sphincs-haraka-128f-simple.bin");
+    const REFERENCE_MSG: &[u8] = b"haraka-bpf interop test message";
+
+    fn verify_reference(pk: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+        let pk = SphincsPublicKeyRef::parse::<Haraka128f>(pk).unwrap();
+        let sig = SphincsSignatureRef::parse::<Haraka128f>(sig).unwrap();
+        verify(&pk, msg, &sig)
+    }
+
+    #[test]
+    fn test_verifies_reference_signature() {
+        let (pk, sig) = REFERENCE.split_at(32);
+        assert_eq!(
+            &pk[..16],
+            &core::array::from_fn::<u8, 16, _>(|i| 32 + i as u8)
+        );
+        assert!(verify_reference(pk, REFERENCE_MSG, sig));
+    }
+
+    /// `pk || sig` of [`REFERENCE_MSG`] for a small set with `log_w = 8`,
+    /// whose 16 checksum bits end on a byte boundary. It was made like
+    /// [`REFERENCE`], with the WOTS+ checksum shift of the SPHINCS+
+    /// reference implementation, `(8 - len2 * log_w % 8) % 8`; PQClean
+    /// shifts by 8 here instead and drops the checksum's high byte.
+    const REFERENCE_LOG_W8: &[u8] = include_bytes!("../vectors/sphincs-haraka-logw8-small.bin");
+    const LOG_W8: Params = Params {
+        n: 16,
+        h: 4,
+        d: 2,
+        a: 4,
+        k: 8,
+        log_w: 8,
+    };
+
+    #[test]
+    fn test_verifies_reference_log_w8() {
+        let (pk, sig) = REFERENCE_LOG_W8.split_at(32);
+        let pk = SphincsPublicKeyRef::new(&LOG_W8, pk).unwrap();
+        let sig = SphincsSignatureRef::new(&LOG_W8, sig).unwrap();
+        assert!(verify(&pk, REFERENCE_MSG, &sig));
+        assert!(!verify(&pk, b"another message", &sig));
+    }
+
+    #[test]
+    fn test_rejects_tampered_reference() {
+        let (pk, sig) = REFERENCE.split_at(32);
+        assert!(!verify_reference(
+            pk,
+            b"haraka-bpf interop test messagf",
+            sig
+        ));
+        assert!(!verify_reference(pk, &REFERENCE_MSG[1..], sig));
+        for i in [0, 31] {
+            let mut other = [0u8; 32];
+            other.copy_from_slice(pk);
+            other[i] ^= 1;
+            assert!(!verify_reference(&other, REFERENCE_MSG, sig));
+        }
+        // Flip a bit of R, a FORS secret, the last FORS sibling, the first
+        // WOTS+ chain, the first layer's last sibling and the last byte.
+        let first_layer = 16 + 33 * 7 * 16;
+        for i in [
+            0,
+            16,
+            first_layer - 1,
+            first_layer,
+            first_layer + 38 * 16 - 1,
+            sig.len() - 1,
+        ] {
+            let mut forged = [0u8; 17088];
+            forged.copy_from_slice(sig);
+            forged[i] ^= 0x80;
+            assert!(!verify_reference(pk, REFERENCE_MSG, &forged), "byte {i}");
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_unsupported_params() {
+        let pk = SphincsPublicKeyRef::parse::<Haraka128f>(&REFERENCE[..32]).unwrap();
+        // The same length, with layers of 2 rather than 3 levels.
+        let p = Params { h: 44, ..P128F };
+        let bytes = [0u8; 17088 - 22 * 16];
+        let sig = SphincsSignatureRef::new(&p, &bytes).unwrap();
+        assert!(!verify(&pk, REFERENCE_MSG, &sig));
+    }
+
+    #[test]
+    fn test_rejects_wrong_lengths() {
+        let bytes = [0u8; 17089];
+        assert_eq!(
+            SphincsSignatureRef::new(&P128F, &bytes),
+            Err(Error::InvalidLength {
+                expected: 17088,
+                actual: 17089
+            })
+        );
+        assert!(SphincsPublicKeyRef::new(&P128F, &bytes[..31]).is_err());
+        let pk = SphincsPublicKeyRef::new(&P128F, &bytes[..32]).unwrap();
+        assert_eq!((pk.seed().len(), pk.root().len()), (16, 16));
+    }
+
+    #[test]
+    fn test_rejects_overflowing_params() {
+        let overflow = Error::InvalidLength {
+            expected: usize::MAX,
+            actual: 0,
+        };
+        for params in [
+            Params {
+                n: usize::MAX,
+                ..P128F
+            },
+            Params {
+                n: usize::MAX / 8,
+                ..P128F
+            },
+            Params {
+                k: usize::MAX,
+                ..P128F
+            },
+            Params {
+                a: usize::MAX,
+                ..P128F
+            },
+            Params {
+                d: usize::MAX / 2,
+                h: usize::MAX,
+                ..P128F
+            },
+        ] {
+            assert_eq!(SphincsSignatureRef::new(&params, &[]), Err(overflow));
+            assert_eq!(params.signature_len(), 0);
+        }
+        let params = Params {
+            n: usize::MAX,
+            ..P128F
+        };
+        assert_eq!(SphincsPublicKeyRef::new(&params, &[]), Err(overflow));
+    }
+}
@@ -0,0 +1,434 @@
+//! Haraka-S, the sponge construction over the Haraka-512 permutation used by
+//! SPHINCS+.
+//!
+//! The sponge has a 64-byte state with a 32-byte rate. Input is XORed into
+//! the first 32 bytes and the state is permuted with 5 rounds of
+//! Haraka-512, without feed-forward. Padding follows SPHINCS+: `0x1f` after
+//! the last message byte and `0x80` in the last byte of the rate.
+
+use crate::backend::DefaultBackend;
+use crate::constants::{self, RoundConstants};
+use crate::haraka512::{aes_mix4, load4, store4};
+
+/// Number of bytes absorbed or squeezed per permutation call.
+pub const RATE: usize = 32;
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// Returns the domain block of `label`: the ASCII label, zero-padded to one
+/// sponge block.
+///
+/// The crate's Haraka-S constructions absorb such a block first, with a
+/// label of the form `haraka-bpf/<name>/v1`, so that their inputs never
+/// collide with one another or with plain Haraka-S inputs.
+pub(crate) const fn domain_block(label: &str) -> [u8; RATE] {
+    let label = label.as_bytes();
+    assert!(
+        label.len() < RATE,
+        "a domain label is shorter than one block"
+    );
+    let mut block = [0u8; RATE];
+    let mut i = 0;
+    while i < label.len() {
+        block[i] = label[i];
+        i += 1;
+    }
+    block
+}
+
+/// Applies the 5-round Haraka-512 permutation to `state` in place.
+#[inline(always)]
+pub(crate) fn permute(state: &mut [u8; 64]) {
+    permute_with(state, constants::HARAKA_CONSTANTS);
+}
+
+/// Like [`permute`], but with the round constants taken from `rc`.
+#[inline(always)]
+pub(crate) fn permute_with(state: &mut [u8; 64], rc: &RoundConstants) {
+    let mut s = load4::<DefaultBackend>(state);
+    for round in &rc[..ROUNDS] {
+        aes_mix4::<DefaultBackend>(&mut s, round);
+    }
+    store4::<DefaultBackend>(state, &s);
+}
+
+/// XORs `data` into the rate of `state`, starting at offset `*pos` and
+/// permuting whenever a block is full.
+#[inline(always)]
+pub(crate) fn absorb(state: &mut [u8; 64], pos: &mut usize, data: &[u8]) {
+    absorb_with(state, pos, data, constants::HARAKA_CONSTANTS);
+}
+
+/// Like [`absorb`], but permuting with the round constants of `rc`.
+#[inline(always)]
+pub(crate) fn absorb_with(
+    state: &mut [u8; 64],
+    pos: &mut usize,
+    mut data: &[u8],
+    rc: &RoundConstants,
+) {
+    while !data.is_empty() {
+        let take = (RATE - *pos).min(data.len());
+        let (head, rest) = data.split_at(take);
+        for (s, d) in state.iter_mut().skip(*pos).zip(head) {
+            *s ^= d;
+        }
+        *pos += take;
+        data = rest;
+        if *pos == RATE {
+            permute_with(state, rc);
+            *pos = 0;
+        }
+    }
+}
+
+/// Applies the Haraka-S padding to a block holding `pos` input bytes.
+#[inline(always)]
+pub(crate) fn pad(state: &mut [u8; 64], pos: usize) {
+    // `pos < RATE` always holds; the mask lets the compiler see it.
+    state[pos % RATE] ^= 0x1f;
+    state[RATE - 1] ^= 0x80;
+}
+
+/// Incremental Haraka-S hasher.
+///
+/// Feed input with [`HarakaS::update`], then either take a 32-byte digest
+/// with [`HarakaS::finalize`] or switch to squeezing arbitrary amounts of
+/// output with [`HarakaS::finalize_xof`].
+#[derive(Clone)]
+pub struct HarakaS {
+    state: [u8; 64],
+    /// Number of bytes absorbed into the current block.
+    pos: usize,
+}
+
+impl HarakaS {
+    /// Creates a hasher with an all-zero state.
+    pub const fn new() -> Self {
+        Self {
+            state: [0u8; 64],
+            pos: 0,
+        }
+    }
+
+    /// Creates a hasher that has absorbed the [`domain_block`] `domain`.
+    pub(crate) fn with_domain(domain: &[u8; RATE]) -> Self {
+        let mut hasher = Self::new();
+        hasher.update(domain);
+        hasher
+    }
+
+    /// Resumes a hasher at a block boundary from its state.
+    pub(crate) const fn from_block_state(state: [u8; 64]) -> Self {
+        Self { state, pos: 0 }
+    }
+
+    /// Absorbs `data` into the sponge.
+    pub fn update(&mut self, data: &[u8]) {
+        absorb(&mut self.state, &mut self.pos, data);
+    }
+
+    /// Pads the input and returns a reader for the output stream.
+    pub fn finalize_xof(mut self) -> HarakaSReader {
+        pad(&mut self.state, self.pos);
+        HarakaSReader {
+            state: self.state,
+            pos: RATE,
+        }
+    }
+
+    /// Pads the input and returns the first 32 output bytes.
+    pub fn finalize(self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        self.finalize_xof().squeeze(&mut out);
+        out
+    }
+}
+
+impl Default for HarakaS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Output stream of a finalized [`HarakaS`] hasher.
+///
+/// Consecutive [`HarakaSReader::squeeze`] calls continue where the previous
+/// one stopped, so the output does not depend on how reads are split.
+#[derive(Clone)]
+pub struct HarakaSReader {
+    state: [u8; 64],
+    /// Number of bytes of the current block already handed out.
+    pos: usize,
+}
+
+impl HarakaSReader {
+    /// Fills `out` with the next bytes of the output stream.
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        squeeze_with(
+            &mut self.state,
+            &mut self.pos,
+            out,
+            constants::HARAKA_CONSTANTS,
+        );
+    }
+}
+
+/// Fills `out` from the rate of a padded `state`, of which `*pos` bytes
+/// were already handed out, permuting with the round constants of `rc`
+/// whenever the block is used up.
+#[inline(always)]
+pub(crate) fn squeeze_with(
+    state: &mut [u8; 64],
+    pos: &mut usize,
+    mut out: &mut [u8],
+    rc: &RoundConstants,
+) {
+    while !out.is_empty() {
+        if *pos == RATE {
+            permute_with(state, rc);
+            *pos = 0;
+        }
+        let take = (RATE - *pos).min(out.len());
+        let (head, tail) = out.split_at_mut(take);
+        for (o, s) in head.iter_mut().zip(state.iter().skip(*pos)) {
+            *o = *s;
+        }
+        *pos += take;
+        out = tail;
+    }
+}
+
+/// Hashes `input` with Haraka-S and fills `out` with the output stream.
+pub fn haraka_s(out: &mut [u8], input: &[u8]) {
+    let mut hasher = HarakaS::new();
+    hasher.update(input);
+    hasher.finalize_xof().squeeze(out);
+}
+
+/// Returns the 32-byte Haraka-S digest of `msg`.
+#[cfg(feature = "alloc")]
+pub fn haraka_s_vec(msg: &[u8]) -> alloc::vec::Vec<u8> {
+    let mut hasher = HarakaS::new();
+    hasher.update(msg);
+    hasher.finalize().to_vec()
+}
+
+/// Returns the 32-byte Haraka-S digest of each message, in order.
+#[cfg(feature = "alloc")]
+pub fn hash_many(msgs: &[alloc::vec::Vec<u8>]) -> alloc::vec::Vec<[u8; 32]> {
+    msgs.iter()
+        .map(|msg| {
+            let mut hasher = HarakaS::new();
+            hasher.update(msg);
+            hasher.finalize()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haraka512::haraka512_perm;
+
+    fn counting<const N: usize>() -> [u8; N] {
+        let mut buf = [0u8; N];
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        buf
+    }
+
+    #[test]
+    fn test_permute_matches_perm() {
+        let src = counting::<64>();
+        let mut expect = [0u8; 64];
+        haraka512_perm::<5>(&mut expect, &src);
+        let mut state = src;
+        permute(&mut state);
+        assert_eq!(&state as &[u8], &expect as &[u8]);
+    }
+
+    #[test]
+    fn test_haraka_s_reference() {
+        // Test vectors computed with haraka_S from the PQClean SPHINCS+
+        // haraka code, using the untweaked round constants.
+        let input = counting::<200>();
+        let vectors: [(usize, &str); 8] = [
+            (
+                0,
+                "ae551e5b5bfb0c3e4febd1003dc18065769bae2d06ab3870aa4169fd7a529b52",
+            ),
+            (
+                1,
+                "a9632012c6361aef66681cfc1841d41d96c52a2d70ba06d8bc3d50127ee209a6",
+            ),
+            (
+                31,
+                "22bce7ebfaf59bc8c4479a05b3f26b87df17aece54b0b31be5eae8f7b5d77991",
+            ),
+            (
+                32,
+                "4b50398c5072bd5d2f255ea8fc7b2c7735e3d9b32fc4ab86abde9953a9453306",
+            ),
+            (
+                33,
+                "c90f93bdfb170829cc5290dbaccaddc75958c90ef770aed8d51051a37ffc006a",
+            ),
+            (
+                64,
+                "cfbc92bc9b22ec2dd8245e3f7335083551a3c22754d45a2939e5868297198999",
+            ),
+            (
+                100,
+                "992c860121adb535de043a0a187a1399c27cc74fdcc2f008be233e83d58fc65c",
+            ),
+            (
+                200,
+                "f29cf607b0aa5b7f1385a73b8bf9c4ef1d520ba60d7e930600d30df2fbc0f655",
+            ),
+        ];
+        for (len, expect) in vectors {
+            let mut hasher = HarakaS::new();
+            hasher.update(&input[..len]);
+            let mut digest = [0u8; 32];
+            crate::decode_hex(expect, &mut digest).unwrap();
+            assert_eq!(hasher.finalize(), digest, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_haraka_s_long_output() {
+        let mut expect = [0u8; 100];
+        crate::decode_hex(
+            "992c860121adb535de043a0a187a1399c27cc74fdcc2f008be233e83d58fc65c\
+             e5c7ea2437c0fbf05253af97940c0a68aed29f407d5070641f338bb01a35e6db\
+             fb79c8c2845beeb808d9c70b63761d203ec9bcfc1c0248cc0dba171955bf913d\
+             d8d1c270",
+            &mut expect,
+        )
+        .unwrap();
+        let mut out = [0u8; 100];
+        haraka_s(&mut out, &counting::<100>());
+        assert_eq!(&out as &[u8], &expect as &[u8]);
+    }
+
+    #[test]
+    fn test_split_update() {
+        let input = counting::<200>();
+        let mut expect = [0u8; 32];
+        haraka_s(&mut expect, &input);
+        for split in [0, 1, 31, 32, 33, 64, 150, 200] {
+            let mut hasher = HarakaS::new();
+            hasher.update(&input[..split]);
+            hasher.update(&input[split..]);
+            assert_eq!(hasher.finalize(), expect, "split {}", split);
+        }
+    }
+
+    #[test]
+    fn test_split_squeeze() {
+        let mut expect = [0u8; 100];
+        haraka_s(&mut expect, b"abc");
+        let mut hasher = HarakaS::new();
+        hasher.update(b"abc");
+        let mut reader = hasher.finalize_xof();
+        let mut out = [0u8; 100];
+        let (a, rest) = out.split_at_mut(7);
+        let (b, c) = rest.split_at_mut(40);
+        reader.squeeze(a);
+        reader.squeeze(b);
+        reader.squeeze(c);
+        assert_eq!(&out as &[u8], &expect as &[u8]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_vec_helpers() {
+        use alloc::vec::Vec;
+
+        let msgs: Vec<Vec<u8>> = (0..70).map(|n| counting::<70>()[..n].to_vec()).collect();
+        let digests = hash_many(&msgs);
+        assert_eq!(digests.len(), msgs.len());
+        for (msg, digest) in msgs.iter().zip(&digests) {
+            let mut expect = [0u8; 32];
+            haraka_s(&mut expect, msg);
+            assert_eq!(*digest, expect);
+            assert_eq!(haraka_s_vec(msg), expect);
+        }
+        assert!(hash_many(&[]).is_empty());
+    }
+
+    /// Haraka-S of the concatenation of `parts`, in a single call.
+    fn haraka_s_concat(parts: &[&[u8]]) -> [u8; 32] {
+        let mut input = [0u8; 256];
+        let mut len = 0;
+        for part in parts {
+            input[len..len + part.len()].copy_from_slice(part);
+            len += part.len();
+        }
+        let mut out = [0u8; 32];
+        haraka_s(&mut out, &input[..len]);
+        out
+    }
+
+    #[test]
+    fn test_domain_block() {
+        let mut expect = [0u8; 32];
+        expect[..20].copy_from_slice(b"haraka-bpf/commit/v1");
+        assert_eq!(domain_block("haraka-bpf/commit/v1"), expect);
+        assert_eq!(domain_block(""), [0; 32]);
+    }
+
+    #[test]
+    fn test_domain_encodings() {
+        // Each construction is Haraka-S over its documented encoding.
+        use crate::compressed::{HarakaLeafHasher, LeafHasher};
+        let id = [0x11; 32];
+        let cases = [
+            (
+                crate::commit(b"yes", &id),
+                haraka_s_concat(&[&domain_block("haraka-bpf/commit/v1"), &id, b"yes"]),
+            ),
+            (
+                crate::hash_instruction(&id, b"vote"),
+                haraka_s_concat(&[&domain_block("haraka-bpf/instruction/v1"), &id, b"vote"]),
+            ),
+            (
+                crate::commit_event(*b"transfer", b"hello"),
+                haraka_s_concat(&[&domain_block("haraka-bpf/event/v1"), b"transfer", b"hello"]),
+            ),
+            (
+                crate::commit_nonce(&id, 0x0201, 7),
+                haraka_s_concat(&[
+                    &domain_block("haraka-bpf/nonce/v1"),
+                    &id,
+                    &0x0201u64.to_le_bytes(),
+                    &7u64.to_le_bytes(),
+                ]),
+            ),
+            (
+                crate::derive_randomness(&id, &[b"round", b""]),
+                haraka_s_concat(&[
+                    &domain_block("haraka-bpf/randomness/v1"),
+                    &id,
+                    &5u64.to_le_bytes(),
+                    b"round",
+                    &0u64.to_le_bytes(),
+                ]),
+            ),
+            (
+                HarakaLeafHasher::hashv(&[b"abc", b""]),
+                haraka_s_concat(&[
+                    &domain_block("haraka-bpf/compressed/v1"),
+                    &3u64.to_le_bytes(),
+                    b"abc",
+                    &0u64.to_le_bytes(),
+                ]),
+            ),
+        ];
+        for (i, (digest, expect)) in cases.into_iter().enumerate() {
+            assert_eq!(digest, expect, "case {i}");
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Step-by-step access to the Haraka-512 computation.
+
+use crate::backend::DefaultBackend;
+use crate::constants;
+use crate::haraka512::{aes_mix4, load4, pxor4, store4, truncstore, truncstore_be, State};
+
+/// The four 128-bit lanes of a Haraka-512 evaluation, together with the
+/// loaded input used by the feed-forward.
+///
+/// Calling [`round`](Self::round) for `i = 0..N`, then
+/// [`feed_forward`](Self::feed_forward) and [`extract`](Self::extract)
+/// gives the N-round Haraka-512 digest. Lanes can be inspected between
+/// steps with [`to_bytes`](Self::to_bytes).
+#[derive(Clone, Debug)]
+pub struct Haraka512State {
+    lanes: State,
+    input: State,
+}
+
+impl Haraka512State {
+    /// Loads a 64-byte message into the lanes.
+    pub fn load(src: &[u8; 64]) -> Self {
+        let lanes = load4::<DefaultBackend>(src);
+        Self {
+            lanes,
+            input: lanes,
+        }
+    }
+
+    /// Applies round `i`: two AES rounds per lane with the constants of
+    /// round `i`, followed by the MIX permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= 6`, as the constant table only covers six rounds.
+    pub fn round(&mut self, i: usize) {
+        assert!(i < constants::MAX_ROUNDS_512, "round index out of range");
+        aes_mix4::<DefaultBackend>(&mut self.lanes, &constants::HARAKA_CONSTANTS[i]);
+    }
+
+    /// XORs the loaded message into the lanes.
+    pub fn feed_forward(&mut self) {
+        pxor4::<DefaultBackend>(&mut self.lanes, &self.input);
+    }
+
+    /// Returns the truncated 32-byte output of the current lanes.
+    pub fn extract(&self) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        truncstore::<DefaultBackend>(&mut dst, &self.lanes);
+        dst
+    }
+
+    /// Like [`extract`](Self::extract), with each 8-byte word of the output
+    /// in big-endian byte order.
+    pub fn extract_be(&self) -> [u8; 32] {
+        let mut dst = [0u8; 32];
+        truncstore_be::<DefaultBackend>(&mut dst, &self.lanes);
+        dst
+    }
+
+    /// Returns the current lanes as 64 bytes.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut dst = [0u8; 64];
+        store4::<DefaultBackend>(&mut dst, &self.lanes);
+        dst
+    }
+}
+
+// Compared through their bytes: the AES-NI lanes have no `PartialEq`.
+impl PartialEq for Haraka512State {
+    fn eq(&self, other: &Self) -> bool {
+        let input = |s: &Self| {
+            let mut dst = [0u8; 64];
+            store4::<DefaultBackend>(&mut dst, &s.input);
+            dst
+        };
+        self.to_bytes() == other.to_bytes() && input(self) == input(other)
+    }
+}
+
+impl Eq for Haraka512State {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haraka512::{haraka512, haraka512_perm};
+
+    fn counting() -> [u8; 64] {
+        let mut buf = [0u8; 64];
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        buf
+    }
+
+    #[test]
+    fn test_steps_match_haraka512() {
+        let src = counting();
+        for rounds in [5, 6] {
+            let mut state = Haraka512State::load(&src);
+            for i in 0..rounds {
+                state.round(i);
+            }
+            state.feed_forward();
+            let mut expect = [0u8; 32];
+            if rounds == 5 {
+                haraka512::<5>(&mut expect, &src);
+            } else {
+                haraka512::<6>(&mut expect, &src);
+            }
+            assert_eq!(state.extract(), expect);
+            let mut be = state.extract_be();
+            crate::haraka512::swap_words(&mut be);
+            assert_eq!(be, expect);
+        }
+    }
+
+    #[test]
+    fn test_lanes_match_perm() {
+        let src = counting();
+        let mut state = Haraka512State::load(&src);
+        assert_eq!(&state.to_bytes() as &[u8], &src as &[u8]);
+        for i in 0..5 {
+            state.round(i);
+        }
+        let mut expect = [0u8; 64];
+        haraka512_perm::<5>(&mut expect, &src);
+        assert_eq!(&state.to_bytes() as &[u8], &expect as &[u8]);
+    }
+
+    #[test]
+    #[should_panic(expected = "round index out of range")]
+    fn test_round_out_of_range() {
+        Haraka512State::load(&[0u8; 64]).round(6);
+    }
+}
@@ -0,0 +1,265 @@
+//! Statistical checks on long Haraka output streams and on diffusion.
+//!
+//! A subset of the NIST SP 800-22 tests (frequency, runs) plus a chi-square
+//! test of byte frequencies and a per-bit frequency test over the digest
+//! positions. They cannot show that the output is random, but any
+//! implementation error that sticks a lane, drops a round or repeats a
+//! word fails them by a wide margin. The inputs are fixed counters, so the
+//! outcome is deterministic; the thresholds sit far in the tails
+//! (z = 5 for the normal statistics, p = 10^-4 on each side for the
+//! chi-square) so they are not tuned to these particular streams.
+//!
+//! The avalanche tests flip every input bit of a few base inputs and
+//! count the output bits that change: about half of them should, on
+//! average, for every flip and for every output bit.
+
+use crate::haraka256::haraka256;
+use crate::haraka512::haraka512;
+use crate::haraka512_keyed::haraka512_keyed;
+
+const DIGESTS: u64 = 8192;
+const Z_SQUARED: f64 = 25.0;
+/// Chi-square bounds for 255 degrees of freedom at p = 10^-4 per tail
+/// (Wilson-Hilferty approximation).
+const CHI2_BOUNDS: (f64, f64) = (179.4, 347.7);
+
+struct Tally {
+    digests: u64,
+    ones: u64,
+    runs: u64,
+    last: Option<u8>,
+    bytes: [u64; 256],
+    positions: [u64; 256],
+}
+
+impl Tally {
+    fn new() -> Self {
+        Self {
+            digests: 0,
+            ones: 0,
+            runs: 0,
+            last: None,
+            bytes: [0; 256],
+            positions: [0; 256],
+        }
+    }
+
+    fn update(&mut self, digest: &[u8; 32]) {
+        self.digests += 1;
+        for (i, &byte) in digest.iter().enumerate() {
+            self.bytes[byte as usize] += 1;
+            for j in 0..8 {
+                let bit = (byte >> (7 - j)) & 1;
+                self.ones += bit as u64;
+                self.positions[8 * i + j] += bit as u64;
+                if self.last != Some(bit) {
+                    self.runs += 1;
+                    self.last = Some(bit);
+                }
+            }
+        }
+    }
+
+    fn check(&self) -> Result<(), &'static str> {
+        let n = 256 * self.digests;
+
+        // Every output bit is set about half of the time.
+        let d = self.digests as i64;
+        if self
+            .positions
+            .iter()
+            .any(|&c| ((2 * c as i64 - d).pow(2) as f64) >= Z_SQUARED * d as f64)
+        {
+            return Err("stuck output bit");
+        }
+
+        // Frequency (monobit): S_n^2 < z^2 n.
+        let s = 2 * self.ones as i64 - n as i64;
+        if (s * s) as f64 >= Z_SQUARED * n as f64 {
+            return Err("monobit");
+        }
+
+        // Runs: |V - 2n pi (1 - pi)| < z 2 sqrt(2n) pi (1 - pi).
+        let pi = self.ones as f64 / n as f64;
+        let p = pi * (1.0 - pi);
+        let dev = self.runs as f64 - 2.0 * n as f64 * p;
+        if dev * dev >= Z_SQUARED * 8.0 * n as f64 * p * p {
+            return Err("runs");
+        }
+
+        // Byte frequencies, 255 degrees of freedom.
+        let expect = (32 * self.digests) as f64 / 256.0;
+        let chi2: f64 = self
+            .bytes
+            .iter()
+            .map(|&c| (c as f64 - expect) * (c as f64 - expect) / expect)
+            .sum();
+        if chi2 <= CHI2_BOUNDS.0 || chi2 >= CHI2_BOUNDS.1 {
+            return Err("byte chi-square");
+        }
+        Ok(())
+    }
+}
+
+/// Flips each input bit of `BASES` counter inputs and checks the mean
+/// number of changed output bits, the minimum over all flips, and how
+/// often each output bit changes.
+fn avalanche<const N: usize>(hash: impl Fn(&[u8; N]) -> [u8; 32]) -> Result<(), &'static str> {
+    const BASES: u64 = 16;
+    let flips = BASES * 8 * N as u64;
+    let mut changed = 0u64;
+    let mut fewest = 256;
+    let mut per_bit = [0u64; 256];
+    for b in 0..BASES {
+        let src = counter::<N>(0x9e37_79b9_7f4a_7c15 ^ b);
+        let base = hash(&src);
+        for i in 0..8 * N {
+            let mut flipped = src;
+            flipped[i / 8] ^= 1 << (i % 8);
+            let out = hash(&flipped);
+            let mut diff = 0;
+            for (j, (x, y)) in base.iter().zip(&out).enumerate() {
+                let delta = x ^ y;
+                diff += delta.count_ones();
+                for k in 0..8 {
+                    per_bit[8 * j + k] += ((delta >> k) & 1) as u64;
+                }
+            }
+            changed += diff as u64;
+            fewest = fewest.min(diff);
+        }
+    }
+
+    // The mean of `flips * 256` fair coin flips is within a fraction of a
+    // bit of 128; a broken mix step moves it by many bits.
+    let mean = changed as f64 / flips as f64;
+    if !(127.0..129.0).contains(&mean) {
+        return Err("mean changed bits");
+    }
+    // Eight standard deviations below the mean of Binomial(256, 1/2).
+    if fewest < 64 {
+        return Err("weak flip");
+    }
+    let f = flips as i64;
+    if per_bit
+        .iter()
+        .any(|&c| ((2 * c as i64 - f).pow(2) as f64) >= Z_SQUARED * f as f64)
+    {
+        return Err("output bit bias");
+    }
+    Ok(())
+}
+
+fn stream(mut hash: impl FnMut(u64) -> [u8; 32]) -> Tally {
+    let mut tally = Tally::new();
+    for i in 0..DIGESTS {
+        tally.update(&hash(i));
+    }
+    tally
+}
+
+fn counter<const N: usize>(i: u64) -> [u8; N] {
+    let mut src = [0u8; N];
+    src[..8].copy_from_slice(&i.to_le_bytes());
+    src
+}
+
+#[test]
+fn test_haraka256_stream() {
+    let tally = stream(|i| {
+        let mut dst = [0u8; 32];
+        haraka256::<5>(&mut dst, &counter(i));
+        dst
+    });
+    assert_eq!(tally.check(), Ok(()));
+}
+
+#[test]
+fn test_haraka512_stream() {
+    let tally = stream(|i| {
+        let mut dst = [0u8; 32];
+        haraka512::<5>(&mut dst, &counter(i));
+        dst
+    });
+    assert_eq!(tally.check(), Ok(()));
+}
+
+#[test]
+fn test_haraka512_keyed_stream() {
+    let key = [0x5a; 64];
+    let tally = stream(|i| {
+        let mut dst = [0u8; 32];
+        haraka512_keyed::<5>(&mut dst, &counter(i), &key);
+        dst
+    });
+    assert_eq!(tally.check(), Ok(()));
+}
+
+#[test]
+fn test_detects_broken_streams() {
+    let tally = stream(|i| {
+        let mut dst = [0u8; 32];
+        haraka512::<5>(&mut dst, &counter(i));
+        dst[8..16].fill(0);
+        dst
+    });
+    assert_eq!(tally.check(), Err("stuck output bit"));
+
+    // One round leaves the counter visible in the output.
+    let tally = stream(|i| {
+        let mut dst = [0u8; 32];
+        haraka256::<1>(&mut dst, &counter(i));
+        dst
+    });
+    assert!(tally.check().is_err());
+}
+
+#[test]
+fn test_avalanche_haraka256() {
+    assert_eq!(
+        avalanche::<32>(|src| {
+            let mut dst = [0u8; 32];
+            haraka256::<5>(&mut dst, src);
+            dst
+        }),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_avalanche_haraka512() {
+    assert_eq!(
+        avalanche::<64>(|src| {
+            let mut dst = [0u8; 32];
+            haraka512::<5>(&mut dst, src);
+            dst
+        }),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_avalanche_haraka512_keyed() {
+    // The key is XORed into the state first, so flipping a key bit has the
+    // same effect as flipping the input bit; only input bits are flipped.
+    let key = [0x5a; 64];
+    assert_eq!(
+        avalanche::<64>(|src| {
+            let mut dst = [0u8; 32];
+            haraka512_keyed::<5>(&mut dst, src, &key);
+            dst
+        }),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_avalanche_detects_weak_mixing() {
+    // One round does not reach full diffusion.
+    assert!(avalanche::<64>(|src| {
+        let mut dst = [0u8; 32];
+        haraka512::<1>(&mut dst, src);
+        dst
+    })
+    .is_err());
+}
@@ -0,0 +1,112 @@
+//! Round-by-round traces of the Haraka permutations.
+//!
+//! These functions compute the usual digests and additionally record the
+//! state after every round (after the MIX step, before the feed-forward).
+//! When a digest disagrees with another implementation, comparing traces
+//! pinpoints the first round that differs.
+
+use crate::backend::DefaultBackend;
+use crate::constants;
+use crate::haraka256::{aes_mix2, load2, pxor2, store2};
+use crate::state::Haraka512State;
+
+/// Computes Haraka-256 and stores the 32-byte state after each round in
+/// `trace`.
+pub fn haraka256<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    src: &[u8; 32],
+    trace: &mut [[u8; 32]; N_ROUNDS],
+) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_256,
+            "Haraka-256 supports at most 12 rounds"
+        )
+    };
+    let t = load2::<DefaultBackend>(src);
+    let mut s = t;
+
+    for (i, entry) in trace.iter_mut().enumerate() {
+        aes_mix2::<DefaultBackend>(&mut s, constants::round256(constants::HARAKA_CONSTANTS, i));
+        store2::<DefaultBackend>(entry, &s);
+    }
+
+    pxor2::<DefaultBackend>(&mut s, &t);
+    store2::<DefaultBackend>(dst, &s);
+}
+
+/// Computes Haraka-512 and stores the 64-byte state after each round in
+/// `trace`.
+pub fn haraka512<const N_ROUNDS: usize>(
+    dst: &mut [u8; 32],
+    src: &[u8; 64],
+    trace: &mut [[u8; 64]; N_ROUNDS],
+) {
+    const {
+        assert!(
+            N_ROUNDS <= constants::MAX_ROUNDS_512,
+            "Haraka-512 supports at most 6 rounds"
+        )
+    };
+    let mut state = Haraka512State::load(src);
+    for (i, entry) in trace.iter_mut().enumerate() {
+        state.round(i);
+        *entry = state.to_bytes();
+    }
+    state.feed_forward();
+    *dst = state.extract();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::haraka256::haraka256 as reference256;
+    use crate::haraka512::{haraka512 as reference512, haraka512_perm};
+
+    #[test]
+    fn test_haraka256_trace() {
+        let mut src = [0u8; 32];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut expect = [0u8; 32];
+        reference256::<5>(&mut expect, &src);
+
+        let mut dst = [0u8; 32];
+        let mut trace = [[0u8; 32]; 5];
+        haraka256::<5>(&mut dst, &src, &mut trace);
+        assert_eq!(dst, expect);
+
+        // The last trace entry is the digest before the feed-forward.
+        for i in 0..32 {
+            assert_eq!(trace[4][i] ^ src[i], expect[i]);
+        }
+    }
+
+    #[test]
+    fn test_haraka512_trace() {
+        let mut src = [0u8; 64];
+        for (i, b) in src.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let mut expect = [0u8; 32];
+        reference512::<5>(&mut expect, &src);
+
+        let mut dst = [0u8; 32];
+        let mut trace = [[0u8; 64]; 5];
+        haraka512::<5>(&mut dst, &src, &mut trace);
+        assert_eq!(dst, expect);
+
+        for rounds in 1..=5 {
+            let mut perm = [0u8; 64];
+            match rounds {
+                1 => haraka512_perm::<1>(&mut perm, &src),
+                2 => haraka512_perm::<2>(&mut perm, &src),
+                3 => haraka512_perm::<3>(&mut perm, &src),
+                4 => haraka512_perm::<4>(&mut perm, &src),
+                _ => haraka512_perm::<5>(&mut perm, &src),
+            }
+            assert_eq!(&trace[rounds - 1] as &[u8], &perm as &[u8]);
+        }
+    }
+}
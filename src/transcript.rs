@@ -0,0 +1,178 @@
+//! Fiat-Shamir transcripts on a Haraka-S duplex.
+//!
+//! The API mirrors [merlin](https://merlin.cool): protocol messages are
+//! appended under labels and challenges are squeezed from everything
+//! absorbed so far. Every operation is framed, so the transcript encodes
+//! the sequence of labels and messages unambiguously:
+//!
+//! ```text
+//! append_message(label, msg):  0x01 || u32le(len(label)) || label || u64le(len(msg)) || msg
+//! challenge_bytes(label, out): 0x02 || u32le(len(label)) || label || u64le(len(out))
+//! ```
+//!
+//! A challenge pads the absorbed data with the Haraka-S padding and
+//! squeezes `out` with the 5-round Haraka-512 permutation. Later operations
+//! are absorbed into the state left by the squeeze, so each challenge
+//! depends on the whole history, including previous challenges. The first
+//! challenge of a transcript is exactly the Haraka-S output of the framed
+//! encoding.
+
+use crate::sponge::{absorb, domain_block, pad, permute, RATE};
+
+/// Block absorbed first by every transcript, before the protocol label.
+const DOMAIN: &[u8; 32] = &domain_block("haraka-bpf/transcript/v1");
+
+const OP_MESSAGE: u8 = 0x01;
+const OP_CHALLENGE: u8 = 0x02;
+
+/// A transcript of a public-coin argument.
+#[derive(Clone)]
+pub struct Transcript {
+    state: [u8; 64],
+    /// Number of bytes absorbed into the current block.
+    pos: usize,
+}
+
+impl Transcript {
+    /// Starts a transcript for the protocol identified by `label`.
+    pub fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            state: [0u8; 64],
+            pos: 0,
+        };
+        transcript.absorb(DOMAIN);
+        transcript.append_message(b"dom-sep", label);
+        transcript
+    }
+
+    /// Appends a prover message under `label`.
+    pub fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.absorb(&[OP_MESSAGE]);
+        self.absorb_label(label);
+        self.absorb(&(message.len() as u64).to_le_bytes());
+        self.absorb(message);
+    }
+
+    /// Appends `x` as 8 little-endian bytes under `label`.
+    pub fn append_u64(&mut self, label: &'static [u8], x: u64) {
+        self.append_message(label, &x.to_le_bytes());
+    }
+
+    /// Fills `out` with a verifier challenge bound to `label` and to the
+    /// transcript so far.
+    pub fn challenge_bytes(&mut self, label: &'static [u8], out: &mut [u8]) {
+        self.absorb(&[OP_CHALLENGE]);
+        self.absorb_label(label);
+        self.absorb(&(out.len() as u64).to_le_bytes());
+
+        pad(&mut self.state, self.pos);
+        if out.is_empty() {
+            // Still close the padded block before absorbing again.
+            permute(&mut self.state);
+        }
+        for chunk in out.chunks_mut(RATE) {
+            permute(&mut self.state);
+            chunk.copy_from_slice(&self.state[..chunk.len()]);
+        }
+        self.pos = 0;
+    }
+
+    fn absorb_label(&mut self, label: &[u8]) {
+        self.absorb(&(label.len() as u32).to_le_bytes());
+        self.absorb(label);
+    }
+
+    fn absorb(&mut self, data: &[u8]) {
+        absorb(&mut self.state, &mut self.pos, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sponge::haraka_s;
+
+    fn frame(op: u8, label: &[u8], len: usize, out: &mut [u8; 128]) -> usize {
+        let mut n = 0;
+        out[n] = op;
+        n += 1;
+        out[n..n + 4].copy_from_slice(&(label.len() as u32).to_le_bytes());
+        n += 4;
+        out[n..n + label.len()].copy_from_slice(label);
+        n += label.len();
+        out[n..n + 8].copy_from_slice(&(len as u64).to_le_bytes());
+        n + 8
+    }
+
+    #[test]
+    fn test_first_challenge_is_haraka_s() {
+        let mut encoding = [0u8; 256];
+        let mut n = 0;
+        encoding[..32].copy_from_slice(DOMAIN);
+        n += 32;
+        let mut buf = [0u8; 128];
+        for (op, label, msg) in [
+            (OP_MESSAGE, &b"dom-sep"[..], &b"example"[..]),
+            (OP_MESSAGE, b"commitment", b"0123456789"),
+        ] {
+            let len = frame(op, label, msg.len(), &mut buf);
+            encoding[n..n + len].copy_from_slice(&buf[..len]);
+            n += len;
+            encoding[n..n + msg.len()].copy_from_slice(msg);
+            n += msg.len();
+        }
+        let len = frame(OP_CHALLENGE, b"c", 80, &mut buf);
+        encoding[n..n + len].copy_from_slice(&buf[..len]);
+        n += len;
+
+        let mut expect = [0u8; 80];
+        haraka_s(&mut expect, &encoding[..n]);
+
+        let mut transcript = Transcript::new(b"example");
+        transcript.append_message(b"commitment", b"0123456789");
+        let mut challenge = [0u8; 80];
+        transcript.challenge_bytes(b"c", &mut challenge);
+        assert_eq!(&challenge as &[u8], &expect as &[u8]);
+    }
+
+    fn challenge(transcript: &mut Transcript) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        transcript.challenge_bytes(b"challenge", &mut out);
+        out
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = Transcript::new(b"proto");
+        let mut b = Transcript::new(b"proto");
+        a.append_u64(b"n", 7);
+        b.append_u64(b"n", 7);
+        assert_eq!(challenge(&mut a), challenge(&mut b));
+        assert_eq!(challenge(&mut a), challenge(&mut b));
+    }
+
+    #[test]
+    fn test_binds_labels_and_messages() {
+        let base = challenge(&mut Transcript::new(b"proto"));
+        assert_ne!(challenge(&mut Transcript::new(b"other")), base);
+
+        let mut a = Transcript::new(b"proto");
+        a.append_message(b"ab", b"c");
+        let mut b = Transcript::new(b"proto");
+        b.append_message(b"a", b"bc");
+        assert_ne!(challenge(&mut a), challenge(&mut b));
+    }
+
+    #[test]
+    fn test_challenges_chain() {
+        let mut t = Transcript::new(b"proto");
+        let first = challenge(&mut t);
+        let second = challenge(&mut t);
+        assert_ne!(first, second);
+
+        // The challenge length is part of the transcript.
+        let mut short = [0u8; 16];
+        Transcript::new(b"proto").challenge_bytes(b"challenge", &mut short);
+        assert_ne!(&short, &first[..16]);
+    }
+}
@@ -0,0 +1,427 @@
+//! Test-vector generation for cross-implementation testing.
+//!
+//! [`generate`] produces a fixed set of vectors covering every variant and
+//! supported round count. Each vector records the input, the key (for the
+//! keyed variant), the final digest, and the full state after every round,
+//! so that an implementation in another language can pinpoint the first
+//! round where it diverges. Vectors can be written as JSON or CSV; all byte
+//! strings are lowercase hex.
+//!
+//! The JSON form is a versioned document,
+//!
+//! ```text
+//! { "version": 1, "vectors": [ { "variant", "rounds", "key"?, "input", "digest", "states" }, ... ] }
+//! ```
+//!
+//! which [`read_json`] loads back. [`Vector::check`] recomputes a loaded
+//! vector with this crate, so vectors from other implementations can be
+//! verified mechanically. The crate's own set is checked in at
+//! `vectors/haraka.json` and loaded by the test suite.
+
+use crate::backend::DefaultBackend;
+use crate::constants::{round256, HARAKA_CONSTANTS};
+use crate::haraka256::{aes_mix2, load2, store2};
+use crate::haraka512::{self, aes_mix4, load4, pxor4, store4};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::string::String;
+use std::vec::Vec;
+
+/// Version of the JSON document written by [`write_json`].
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The Haraka variant a vector exercises.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    /// Haraka-256 over a 32-byte input.
+    Haraka256,
+    /// Haraka-512 over a 64-byte input.
+    Haraka512,
+    /// Keyed Haraka-512 over a 64-byte input and a 64-byte key.
+    Haraka512Keyed,
+}
+
+impl Variant {
+    /// The name used in the JSON and CSV output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Variant::Haraka256 => "haraka256",
+            Variant::Haraka512 => "haraka512",
+            Variant::Haraka512Keyed => "haraka512_keyed",
+        }
+    }
+}
+
+/// One test vector.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vector {
+    /// The variant, written as `haraka256`, `haraka512` or
+    /// `haraka512_keyed`.
+    pub variant: Variant,
+    /// Number of rounds.
+    pub rounds: usize,
+    /// Hex-encoded 64-byte key, for the keyed variant only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Hex-encoded input.
+    pub input: String,
+    /// Hex-encoded 32-byte digest.
+    pub digest: String,
+    /// Hex-encoded state after each round, before the feed-forward. For the
+    /// keyed variant the key has already been XORed into the state.
+    pub states: Vec<String>,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut s = String::with_capacity(2 * bytes.len());
+    for b in bytes {
+        s.push(DIGITS[(b >> 4) as usize] as char);
+        s.push(DIGITS[(b & 0xf) as usize] as char);
+    }
+    s
+}
+
+fn vector256<const N_ROUNDS: usize>(input: &[u8; 32]) -> Vector {
+    let mut s = load2::<DefaultBackend>(input);
+    let mut states = Vec::with_capacity(N_ROUNDS);
+    let mut bytes = [0u8; 32];
+    for i in 0..N_ROUNDS {
+        aes_mix2::<DefaultBackend>(&mut s, round256(HARAKA_CONSTANTS, i));
+        store2::<DefaultBackend>(&mut bytes, &s);
+        states.push(hex(&bytes));
+    }
+
+    let mut digest = [0u8; 32];
+    crate::haraka256::haraka256::<N_ROUNDS>(&mut digest, input);
+    Vector {
+        variant: Variant::Haraka256,
+        rounds: N_ROUNDS,
+        key: None,
+        input: hex(input),
+        digest: hex(&digest),
+        states,
+    }
+}
+
+fn rounds512<const N_ROUNDS: usize>(mut s: haraka512::State) -> Vec<String> {
+    let mut states = Vec::with_capacity(N_ROUNDS);
+    let mut bytes = [0u8; 64];
+    for round in &HARAKA_CONSTANTS[..N_ROUNDS] {
+        aes_mix4::<DefaultBackend>(&mut s, round);
+        store4::<DefaultBackend>(&mut bytes, &s);
+        states.push(hex(&bytes));
+    }
+    states
+}
+
+fn vector512<const N_ROUNDS: usize>(input: &[u8; 64]) -> Vector {
+    let mut digest = [0u8; 32];
+    crate::haraka512::haraka512::<N_ROUNDS>(&mut digest, input);
+    Vector {
+        variant: Variant::Haraka512,
+        rounds: N_ROUNDS,
+        key: None,
+        input: hex(input),
+        digest: hex(&digest),
+        states: rounds512::<N_ROUNDS>(load4::<DefaultBackend>(input)),
+    }
+}
+
+fn vector512_keyed<const N_ROUNDS: usize>(input: &[u8; 64], key: &[u8; 64]) -> Vector {
+    let mut s = load4::<DefaultBackend>(input);
+    pxor4::<DefaultBackend>(&mut s, &load4::<DefaultBackend>(key));
+
+    let mut digest = [0u8; 32];
+    crate::haraka512_keyed::haraka512_keyed::<N_ROUNDS>(&mut digest, input, key);
+    Vector {
+        variant: Variant::Haraka512Keyed,
+        rounds: N_ROUNDS,
+        key: Some(hex(key)),
+        input: hex(input),
+        digest: hex(&digest),
+        states: rounds512::<N_ROUNDS>(s),
+    }
+}
+
+/// Deterministic pseudo-random filler, so that the vector set does not
+/// depend on any external randomness.
+fn filler<const N: usize>(seed: u8) -> [u8; N] {
+    let mut out = [0u8; N];
+    let mut block = [seed; 32];
+    for chunk in out.chunks_mut(32) {
+        block = crate::chain::hash_chain::<5>(&block, 1);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+    out
+}
+
+fn sequential<const N: usize>() -> [u8; N] {
+    let mut out = [0u8; N];
+    for (i, b) in out.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    out
+}
+
+/// Returns the standard set of test vectors.
+pub fn generate() -> Vec<Vector> {
+    let inputs32 = [sequential::<32>(), [0u8; 32], [0xff; 32], filler::<32>(1)];
+    let inputs64 = [sequential::<64>(), [0u8; 64], [0xff; 64], filler::<64>(2)];
+    let keys = [[0u8; 64], filler::<64>(3)];
+
+    let mut vectors = Vec::new();
+    for input in &inputs32 {
+        vectors.push(vector256::<5>(input));
+        vectors.push(vector256::<6>(input));
+    }
+    for input in &inputs64 {
+        vectors.push(vector512::<5>(input));
+        vectors.push(vector512::<6>(input));
+    }
+    for input in &inputs64 {
+        for key in &keys {
+            vectors.push(vector512_keyed::<5>(input, key));
+            vectors.push(vector512_keyed::<6>(input, key));
+        }
+    }
+    vectors
+}
+
+#[derive(Serialize)]
+struct DocumentRef<'a> {
+    version: u32,
+    vectors: &'a [Vector],
+}
+
+#[derive(Deserialize)]
+struct Document {
+    version: u32,
+    vectors: Vec<Vector>,
+}
+
+fn invalid(msg: std::string::String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn unhex<const N: usize>(field: &str, text: &str) -> io::Result<[u8; N]> {
+    let mut out = [0u8; N];
+    crate::encoding::decode_hex(text.as_bytes(), &mut out)
+        .map_err(|err| invalid(std::format!("{field}: {err}")))?;
+    Ok(out)
+}
+
+impl Vector {
+    /// Recomputes this vector from its variant, round count, input and key,
+    /// and checks that the digest and every round state match.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if a field is malformed, the
+    /// round count is not 5 or 6, or the recomputed vector differs; for a
+    /// differing state the message names the first divergent round.
+    pub fn check(&self) -> io::Result<()> {
+        let expect = match (self.variant, self.rounds) {
+            (Variant::Haraka256, 5) => vector256::<5>(&unhex("input", &self.input)?),
+            (Variant::Haraka256, 6) => vector256::<6>(&unhex("input", &self.input)?),
+            (Variant::Haraka512, 5) => vector512::<5>(&unhex("input", &self.input)?),
+            (Variant::Haraka512, 6) => vector512::<6>(&unhex("input", &self.input)?),
+            (Variant::Haraka512Keyed, rounds @ (5 | 6)) => {
+                let key = self
+                    .key
+                    .as_deref()
+                    .ok_or_else(|| invalid("key: missing".into()))?;
+                let (input, key) = (unhex("input", &self.input)?, unhex("key", key)?);
+                if rounds == 5 {
+                    vector512_keyed::<5>(&input, &key)
+                } else {
+                    vector512_keyed::<6>(&input, &key)
+                }
+            }
+            (_, rounds) => return Err(invalid(std::format!("unsupported rounds: {rounds}"))),
+        };
+        if self.variant != Variant::Haraka512Keyed && self.key.is_some() {
+            return Err(invalid("key: only valid for haraka512_keyed".into()));
+        }
+        if self.states.len() != expect.states.len() {
+            return Err(invalid(std::format!(
+                "states: expected {}, found {}",
+                expect.states.len(),
+                self.states.len()
+            )));
+        }
+        if let Some(round) = (0..self.states.len()).find(|&i| self.states[i] != expect.states[i]) {
+            return Err(invalid(std::format!("states: diverges at round {round}")));
+        }
+        if self.digest != expect.digest {
+            return Err(invalid("digest: mismatch".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Writes `vectors` as a pretty-printed JSON document tagged with
+/// [`SCHEMA_VERSION`].
+pub fn write_json<W: Write>(mut w: W, vectors: &[Vector]) -> io::Result<()> {
+    let doc = DocumentRef {
+        version: SCHEMA_VERSION,
+        vectors,
+    };
+    serde_json::to_writer_pretty(&mut w, &doc)?;
+    writeln!(w)
+}
+
+/// Reads a JSON document written by [`write_json`].
+///
+/// Fails with [`io::ErrorKind::InvalidData`] if the document is malformed
+/// or has a version other than [`SCHEMA_VERSION`]. The vectors are not
+/// checked; see [`Vector::check`].
+pub fn read_json<R: Read>(r: R) -> io::Result<Vec<Vector>> {
+    let doc: Document = serde_json::from_reader(r)?;
+    if doc.version != SCHEMA_VERSION {
+        return Err(invalid(std::format!(
+            "unsupported schema version {}",
+            doc.version
+        )));
+    }
+    Ok(doc.vectors)
+}
+
+/// Writes `vectors` as CSV with a header line. The per-round states are
+/// joined with `;` in a single column.
+pub fn write_csv<W: Write>(mut w: W, vectors: &[Vector]) -> io::Result<()> {
+    writeln!(w, "variant,rounds,key,input,digest,states")?;
+    for v in vectors {
+        writeln!(
+            w,
+            "{},{},{},{},{},{}",
+            v.variant.as_str(),
+            v.rounds,
+            v.key.as_deref().unwrap_or(""),
+            v.input,
+            v.digest,
+            v.states.join(";")
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_known_vectors() {
+        let vectors = generate();
+        // Vectors from the unit tests of the individual variants.
+        let h256 = &vectors[0];
+        assert_eq!(h256.variant, Variant::Haraka256);
+        assert_eq!(h256.rounds, 5);
+        assert_eq!(
+            h256.digest,
+            "8027ccb87949774b78d0545fb72bf70c695c2a0923cbd47bba1159efbf2b2c1c"
+        );
+        let h512 = vectors
+            .iter()
+            .find(|v| v.variant == Variant::Haraka512 && v.rounds == 6)
+            .unwrap();
+        assert_eq!(
+            h512.digest,
+            "0e27514e8ab7b4ee153c9a5413fb1e984a914f5b6fea17228541ce1707fc4e64"
+        );
+    }
+
+    #[test]
+    fn test_round_states() {
+        for v in generate() {
+            assert_eq!(v.states.len(), v.rounds);
+            let width = if v.variant == Variant::Haraka256 {
+                64
+            } else {
+                128
+            };
+            assert!(v.states.iter().all(|s| s.len() == width));
+        }
+    }
+
+    #[test]
+    fn test_zero_key_matches_unkeyed() {
+        let vectors = generate();
+        for keyed in vectors
+            .iter()
+            .filter(|v| v.variant == Variant::Haraka512Keyed)
+        {
+            if keyed.key.as_deref() != Some(hex(&[0u8; 64]).as_str()) {
+                continue;
+            }
+            let unkeyed = vectors
+                .iter()
+                .find(|v| {
+                    v.variant == Variant::Haraka512
+                        && v.rounds == keyed.rounds
+                        && v.input == keyed.input
+                })
+                .unwrap();
+            assert_eq!(keyed.digest, unkeyed.digest);
+            assert_eq!(keyed.states, unkeyed.states);
+        }
+    }
+
+    #[test]
+    fn test_csv_layout() {
+        let vectors = generate();
+        let mut out = Vec::new();
+        write_csv(&mut out, &vectors).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), vectors.len() + 1);
+        assert!(text
+            .lines()
+            .skip(1)
+            .all(|line| line.split(',').count() == 6));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let vectors = generate();
+        let mut out = Vec::new();
+        write_json(&mut out, &vectors).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["version"], SCHEMA_VERSION);
+        assert_eq!(parsed["vectors"][0]["variant"], "haraka256");
+        assert!(parsed["vectors"][0].get("key").is_none());
+        assert_eq!(read_json(&out[..]).unwrap(), vectors);
+    }
+
+    #[test]
+    fn test_checked_in_vectors() {
+        // Regenerate with `haraka-vectors > vectors/haraka.json`.
+        let vectors = read_json(&include_bytes!("../vectors/haraka.json")[..]).unwrap();
+        assert_eq!(vectors, generate());
+        for v in &vectors {
+            v.check().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_check_reports_divergence() {
+        let mut v = generate().swap_remove(0);
+        let flipped = if v.states[2].starts_with('0') {
+            "1"
+        } else {
+            "0"
+        };
+        v.states[2].replace_range(..1, flipped);
+        let err = v.check().unwrap_err();
+        assert_eq!(err.to_string(), "states: diverges at round 2");
+
+        let mut v = generate().swap_remove(0);
+        v.rounds = 4;
+        assert_eq!(v.check().unwrap_err().to_string(), "unsupported rounds: 4");
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let err = read_json(&br#"{"version": 2, "vectors": []}"#[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,101 @@
+//! JavaScript bindings generated with `wasm-bindgen`.
+//!
+//! Each function takes `Uint8Array`-compatible byte slices, so browser
+//! wallets can compute the same 5-round digests the on-chain program
+//! expects. Inputs of the wrong length raise a JavaScript `Error`.
+
+use crate::error::Error;
+use crate::params::DEFAULT_ROUNDS;
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+fn array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], Error> {
+    bytes.try_into().map_err(|_| Error::InvalidLength {
+        expected: N,
+        actual: bytes.len(),
+    })
+}
+
+fn haraka256_bytes(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut dst = [0u8; 32];
+    crate::haraka256::haraka256::<DEFAULT_ROUNDS>(&mut dst, &array(input)?);
+    Ok(dst.to_vec())
+}
+
+fn haraka512_bytes(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut dst = [0u8; 32];
+    crate::haraka512::haraka512::<DEFAULT_ROUNDS>(&mut dst, &array(input)?);
+    Ok(dst.to_vec())
+}
+
+fn haraka512_keyed_bytes(state: &[u8], key: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut dst = [0u8; 32];
+    crate::haraka512_keyed::haraka512_keyed::<DEFAULT_ROUNDS>(
+        &mut dst,
+        &array(state)?,
+        &array(key)?,
+    );
+    Ok(dst.to_vec())
+}
+
+fn js_error(err: Error) -> JsError {
+    JsError::new(&alloc::format!("{err}"))
+}
+
+/// Haraka-256 (5 rounds) of a 32-byte input.
+#[wasm_bindgen(js_name = haraka256)]
+pub fn haraka256_js(input: &[u8]) -> Result<Vec<u8>, JsError> {
+    haraka256_bytes(input).map_err(js_error)
+}
+
+/// Haraka-512 (5 rounds) of a 64-byte input.
+#[wasm_bindgen(js_name = haraka512)]
+pub fn haraka512_js(input: &[u8]) -> Result<Vec<u8>, JsError> {
+    haraka512_bytes(input).map_err(js_error)
+}
+
+/// Keyed Haraka-512 (5 rounds) of a 64-byte state under a 64-byte key.
+#[wasm_bindgen(js_name = haraka512Keyed)]
+pub fn haraka512_keyed_js(state: &[u8], key: &[u8]) -> Result<Vec<u8>, JsError> {
+    haraka512_keyed_bytes(state, key).map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_haraka256() {
+        let src = [7u8; 32];
+        let mut expect = [0u8; 32];
+        crate::haraka256::haraka256::<DEFAULT_ROUNDS>(&mut expect, &src);
+        assert_eq!(haraka256_bytes(&src).unwrap(), expect);
+    }
+
+    #[test]
+    fn test_wasm_haraka512_keyed() {
+        let state = [1u8; 64];
+        let key = [2u8; 64];
+        let mut expect = [0u8; 32];
+        crate::haraka512_keyed::haraka512_keyed::<DEFAULT_ROUNDS>(&mut expect, &state, &key);
+        assert_eq!(haraka512_keyed_bytes(&state, &key).unwrap(), expect);
+    }
+
+    #[test]
+    fn test_wasm_invalid_length() {
+        assert_eq!(
+            haraka512_bytes(&[0u8; 63]),
+            Err(Error::InvalidLength {
+                expected: 64,
+                actual: 63
+            })
+        );
+        assert_eq!(
+            haraka512_keyed_bytes(&[0u8; 64], &[0u8; 32]),
+            Err(Error::InvalidLength {
+                expected: 64,
+                actual: 32
+            })
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Machine check that the lane operations compile to word operations.
+//!
+//! SBF has no vector registers, and its interpreter charges every byte
+//! load, store and XOR as a full instruction, so a lane operation written
+//! as a loop over bytes costs 16 times what two 64-bit operations do. Each
+//! probe below is a non-inlined lane operation (XOR, comparison, copy, the
+//! unpacks and the MIX steps); the test disassembles the test binary and
+//! fails if any of them, or anything they call, touches a byte register.
+//!
+//! On x86-64 the vectorizer would turn a byte loop into one SSE instruction
+//! and hide it, so the check needs vectorization off, which makes the code
+//! as scalar as SBF's:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg aes_force_soft -C no-vectorize-loops -C no-vectorize-slp" \
+//!     cargo test --release --features word-ops-check word_ops
+//! ```
+//!
+//! A deliberately byte-wise probe checks that the flags are in effect.
+
+use crate::disasm::{mnemonic, Binary};
+use crate::haraka256::mix2;
+use crate::haraka512::mix4;
+use crate::simd128::Simd128;
+use core::hint::black_box;
+use std::string::String;
+
+#[inline(never)]
+fn probe_pxor(a: &mut Simd128, b: &Simd128) {
+    Simd128::pxor(a, b)
+}
+
+#[inline(never)]
+fn probe_eq(a: &Simd128, b: &Simd128) -> bool {
+    a == b
+}
+
+#[inline(never)]
+fn probe_copy(src: &[u8; 32], dst: &mut [Simd128; 2]) {
+    // Swapped, so that the probe is not merged with a plain copy elsewhere.
+    dst[1] = Simd128::read(arrayref::array_ref![src, 0, 16]);
+    dst[0] = Simd128::read(arrayref::array_ref![src, 16, 16]);
+}
+
+#[inline(never)]
+fn probe_unpack(a: &mut Simd128, b: &Simd128) -> Simd128 {
+    Simd128::unpacklo_epi32(a, b);
+    let mut c = *b;
+    Simd128::unpackhi_epi32(&mut c, a);
+    Simd128::pxor(a, &Simd128::unpacklo_epi64(&c, b));
+    Simd128::unpackhi_epi64(a, &c)
+}
+
+#[inline(never)]
+fn probe_mix2(s: &mut [Simd128; 2]) {
+    mix2(s)
+}
+
+#[inline(never)]
+fn probe_mix4(s: &mut [Simd128; 4]) {
+    mix4(s)
+}
+
+const PROBES: [&str; 6] = [
+    "probe_pxor",
+    "probe_eq",
+    "probe_copy",
+    "probe_unpack",
+    "probe_mix2",
+    "probe_mix4",
+];
+
+/// Calls every probe once, so that none is removed from the binary.
+fn run_probes() {
+    let mut a = black_box(Simd128::from(1));
+    let b = black_box(Simd128::from(2));
+    probe_pxor(&mut a, &b);
+    black_box(probe_eq(&a, &b));
+    probe_copy(black_box(&[3; 32]), black_box(&mut [a, b]));
+    black_box(probe_unpack(&mut a, &b));
+    probe_mix2(black_box(&mut [a, b]));
+    probe_mix4(black_box(&mut [a, b, a, b]));
+}
+
+/// Returns true if `line` reads or writes an 8-bit register. Setting a
+/// flag into one (`sete %al`, for a comparison result) is not a byte op.
+fn is_byte_op(line: &str) -> bool {
+    const BYTE_REGS: [&str; 20] = [
+        "%al", "%bl", "%cl", "%dl", "%ah", "%bh", "%ch", "%dh", "%sil", "%dil", "%bpl", "%spl",
+        "%r8b", "%r9b", "%r10b", "%r11b", "%r12b", "%r13b", "%r14b", "%r15b",
+    ];
+    let Some(m) = mnemonic(line) else {
+        return false;
+    };
+    if m.starts_with("set") {
+        return false;
+    }
+    m.starts_with("movzb")
+        || m.starts_with("movsb")
+        || line
+            .split_whitespace()
+            .flat_map(|field| field.split(','))
+            .any(|operand| BYTE_REGS.contains(&operand))
+}
+
+/// Returns the first byte operation reachable from `probe`.
+fn check(binary: &Binary, probe: &str) -> Result<(), String> {
+    binary.walk(&std::format!("word_ops::{probe}"), |name, body| match body
+        .iter()
+        .find(|line| is_byte_op(line))
+    {
+        Some(line) => Err(std::format!("byte operation in {name}: {line}")),
+        None => Ok(()),
+    })
+}
+
+/// A deliberately byte-wise probe: XOR as a loop over the bytes.
+#[inline(never)]
+fn probe_byte_loop(a: &mut [u8; 16], b: &[u8; 16]) {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x ^= y;
+    }
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_lane_ops_are_word_wise() {
+    run_probes();
+    let binary = Binary::current();
+    let mut failures = String::new();
+    for probe in PROBES {
+        if let Err(err) = check(&binary, probe) {
+            failures += &std::format!("{probe}: {err}\n");
+        }
+    }
+    assert!(failures.is_empty(), "{failures}");
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "needs an optimized build")]
+fn test_flags_byte_loops() {
+    probe_byte_loop(&mut [1; 16], black_box(&[2; 16]));
+    let err = check(&Binary::current(), "probe_byte_loop")
+        .expect_err("byte loop not flagged: is vectorization disabled?");
+    assert!(err.contains("probe_byte_loop"), "{err}");
+}
@@ -0,0 +1,727 @@
+//! WOTS+ one-time signatures over Haraka-512.
+//!
+//! The parameters are those of SPHINCS+ with `n = 32` and `w = 16`: the
+//! 32-byte message is read as 64 base-16 digits, high nibble first, and
+//! followed by the 3 digits of the checksum `sum(15 - d_i)`. Each of the
+//! [`LEN`] digits selects a position on a hash chain of length 15, and the
+//! chain step is
+//!
+//! ```text
+//! F(address, x) = Haraka-512_seed(address || x)
+//! ```
+//!
+//! where `Haraka-512_seed` uses round constants tweaked by the 16-byte
+//! public seed (see `constants::tweaked`, with kind `KIND`) and the 32-byte
+//! address names the key, the chain and the step, so no two steps of any
+//! key use the same function. The public key is
+//!
+//! ```text
+//! HarakaS(WOTS_DOMAIN || seed || u64le(key_index) || 0^8 || end_0 || ... || end_66)
+//! ```
+//!
+//! Verification is `no_std` and costs at most `67 * 15` Haraka-512 calls.
+//! Key generation and signing derive the chain starts from a 32-byte
+//! secret seed and need the `std` feature; they are meant for tests and
+//! off-chain signers, which must never sign twice with one key index.
+
+use crate::backend::DefaultBackend;
+use crate::constants::{self, RoundConstants};
+use crate::error::Error;
+use crate::haraka512::{haraka512_lanes_with, haraka512_x2_with, load4};
+use crate::simd128::PARALLEL_ROUND;
+use crate::sponge::{self, domain_block, HarakaS};
+use arrayref::{array_refs, mut_array_refs};
+
+/// Number of base-16 digits of the message.
+const LEN1: usize = 64;
+/// Number of base-16 digits of the checksum.
+const LEN2: usize = checksum_len::<4>(LEN1);
+/// Number of hash chains, one per digit.
+pub const LEN: usize = LEN1 + LEN2;
+/// Length of a signature: one 32-byte chain value per digit.
+pub const SIGNATURE_LEN: usize = 32 * LEN;
+
+/// Last position of a chain.
+const W_MAX: u8 = 15;
+
+/// Tweak kind reserved for WOTS+ public seeds.
+const KIND: u8 = 0x04;
+
+/// Address type of a chain step.
+const CHAIN: u8 = 0;
+/// Address type of a secret chain start.
+#[cfg(feature = "std")]
+const SECRET: u8 = 1;
+
+const ROUNDS: usize = crate::DEFAULT_ROUNDS;
+
+/// Chains walked together during verification: the ends of 16 chains take
+/// 512 bytes of stack, where all [`LEN`] would take 2 KiB of the 4 KiB an
+/// SBF frame may use.
+const GROUP: usize = 16;
+
+/// Domain block absorbed before the public key.
+const WOTS_DOMAIN: &[u8; 32] = &domain_block("haraka-bpf/wots/v1");
+
+/// WOTS+ instance of one public seed.
+///
+/// Deriving the round constants costs 48 XORs; keep the value around when
+/// checking many signatures under the same seed.
+#[derive(Clone)]
+pub struct Wots {
+    seed: [u8; 16],
+    rc: RoundConstants,
+}
+
+impl Wots {
+    /// Creates the instance of `seed`.
+    pub fn new(seed: &[u8; 16]) -> Self {
+        Self {
+            seed: *seed,
+            rc: constants::tweaked(KIND, seed),
+        }
+    }
+
+    /// Recomputes the public key of key `key_index` from a signature of
+    /// `msg`.
+    pub fn public_key_from_signature(
+        &self,
+        key_index: u64,
+        msg: &[u8; 32],
+        sig: &[u8; SIGNATURE_LEN],
+    ) -> [u8; 32] {
+        let mut sponge = self.header_state(key_index);
+        let (values, _) = sig.as_chunks::<32>();
+        self.absorb_chain_ends(&mut sponge, key_index, 0, &digits(msg), values);
+        HarakaS::from_block_state(sponge).finalize()
+    }
+
+    /// Returns `true` if `sig` is a signature of `msg` under `public_key`,
+    /// the public key of key `key_index`.
+    pub fn verify(
+        &self,
+        public_key: &[u8; 32],
+        key_index: u64,
+        msg: &[u8; 32],
+        sig: &[u8; SIGNATURE_LEN],
+    ) -> bool {
+        crate::ct::eq(
+            &self.public_key_from_signature(key_index, msg, sig),
+            public_key,
+        )
+    }
+
+    /// Derives the public key of key `key_index` from `secret_seed`.
+    #[cfg(feature = "std")]
+    pub fn public_key(&self, secret_seed: &[u8; 32], key_index: u64) -> [u8; 32] {
+        let mut ends: [[u8; 32]; LEN] =
+            core::array::from_fn(|i| self.secret(secret_seed, key_index, i as u8));
+        self.walk(key_index, 0, &[0; LEN], &mut ends);
+        let mut hasher = HarakaS::from_block_state(self.header_state(key_index));
+        for end in &ends {
+            hasher.update(end);
+        }
+        hasher.finalize()
+    }
+
+    /// Signs `msg` with key `key_index` derived from `secret_seed`.
+    ///
+    /// A key index must sign a single message: two signatures under one
+    /// key reveal enough chain values to forge others.
+    #[cfg(feature = "std")]
+    pub fn sign(
+        &self,
+        secret_seed: &[u8; 32],
+        key_index: u64,
+        msg: &[u8; 32],
+    ) -> [u8; SIGNATURE_LEN] {
+        let mut sig = [0u8; SIGNATURE_LEN];
+        for (i, &d) in digits(msg).iter().enumerate() {
+            let start = self.secret(secret_seed, key_index, i as u8);
+            *arrayref::array_mut_ref![sig, 32 * i, 32] =
+                self.chain(key_index, i as u8, 0, d, &start);
+        }
+        sig
+    }
+
+    /// Secret start of chain `chain` of key `key_index`.
+    #[cfg(feature = "std")]
+    fn secret(&self, secret_seed: &[u8; 32], key_index: u64, chain: u8) -> [u8; 32] {
+        self.f(&address(key_index, SECRET, chain, 0), secret_seed)
+    }
+
+    /// Walks chain `first + i` of key `key_index` from position `starts[i]`
+    /// to its end, replacing `values[i]` with the chain end, for every `i`.
+    ///
+    /// This is the inner loop of verification, for schemes that arrange
+    /// chains their own way. The chains share the round constants. With
+    /// the bitsliced AES round, which evaluates two Haraka-512 states for
+    /// the cost of one, they are walked two at a time on the halves of a
+    /// 256-bit state, and a chain that ends is replaced by the next, so
+    /// both halves stay busy until the last chain. The table round used on
+    /// SBF has no such batching and walks them one after another. A
+    /// position of 15 or more leaves the value as is.
+    ///
+    /// Fails with [`Error::InvalidLength`] if `starts` and `values` differ
+    /// in length, and with [`Error::LengthOutOfRange`] if the chains would
+    /// run past the last one.
+    pub fn chain_ends(
+        &self,
+        key_index: u64,
+        first: u8,
+        starts: &[u8],
+        values: &mut [[u8; 32]],
+    ) -> Result<(), Error> {
+        if starts.len() != values.len() {
+            return Err(Error::InvalidLength {
+                expected: values.len(),
+                actual: starts.len(),
+            });
+        }
+        let max = LEN.saturating_sub(first as usize);
+        if values.len() > max {
+            return Err(Error::LengthOutOfRange {
+                min: 0,
+                max,
+                actual: values.len(),
+            });
+        }
+        self.walk(key_index, first, starts, values);
+        Ok(())
+    }
+
+    /// The body of [`chain_ends`](Self::chain_ends), for checked inputs.
+    fn walk(&self, key_index: u64, first: u8, starts: &[u8], values: &mut [[u8; 32]]) {
+        let mut slots: [Option<Slot>; 2] = [None, None];
+        let mut next = 0;
+        loop {
+            let width = if PARALLEL_ROUND { 2 } else { 1 };
+            for slot in slots.iter_mut().take(width) {
+                while slot.is_none() {
+                    let (Some(&start), Some(value)) = (starts.get(next), values.get(next)) else {
+                        break;
+                    };
+                    if start < W_MAX {
+                        *slot = Some(Slot {
+                            index: next,
+                            chain: first.wrapping_add(next as u8),
+                            pos: start,
+                            value: *value,
+                        });
+                    }
+                    next += 1;
+                }
+            }
+            match &mut slots {
+                [Some(a), Some(b)] => {
+                    [a.value, b.value] = self.f_x2(
+                        [
+                            &address(key_index, CHAIN, a.chain, a.pos),
+                            &address(key_index, CHAIN, b.chain, b.pos),
+                        ],
+                        [&a.value, &b.value],
+                    );
+                }
+                [Some(a), None] | [None, Some(a)] => {
+                    a.value = self.f(&address(key_index, CHAIN, a.chain, a.pos), &a.value);
+                }
+                [None, None] => break,
+            }
+            for slot in &mut slots {
+                let Some(s) = slot else { continue };
+                s.pos += 1;
+                if s.pos == W_MAX {
+                    if let Some(value) = values.get_mut(s.index) {
+                        *value = s.value;
+                    }
+                    *slot = None;
+                }
+            }
+        }
+    }
+
+    /// Walks chains `first..` from the positions `digits` to their ends
+    /// and absorbs the ends into `sponge`, [`GROUP`] chains at a time.
+    fn absorb_chain_ends(
+        &self,
+        sponge: &mut [u8; 64],
+        key_index: u64,
+        first: u8,
+        digits: &[u8],
+        values: &[[u8; 32]],
+    ) {
+        let groups = digits.chunks(GROUP).zip(values.chunks(GROUP));
+        for (k, (digits, values)) in groups.enumerate() {
+            // Built from the values rather than zeroed and copied into,
+            // which would leave a memset in the loop.
+            let mut ends: [[u8; 32]; GROUP] =
+                core::array::from_fn(|i| values.get(i).copied().unwrap_or_default());
+            let ends = &mut ends[..values.len().min(GROUP)];
+            self.walk(
+                key_index,
+                first.wrapping_add((k * GROUP) as u8),
+                digits,
+                ends,
+            );
+            for end in ends.iter() {
+                sponge::absorb(sponge, &mut 0, end);
+            }
+        }
+    }
+
+    /// Walks chain `chain` from position `from` to position `to`.
+    #[cfg(feature = "std")]
+    fn chain(&self, key_index: u64, chain: u8, from: u8, to: u8, x: &[u8; 32]) -> [u8; 32] {
+        let mut x = *x;
+        for step in from..to {
+            x = self.f(&address(key_index, CHAIN, chain, step), &x);
+        }
+        x
+    }
+
+    /// Two evaluations of `F` at once, on the halves of a 256-bit state.
+    fn f_x2(&self, addresses: [&[u8; 32]; 2], xs: [&[u8; 32]; 2]) -> [[u8; 32]; 2] {
+        let mut blocks = [[0u8; 64]; 2];
+        for ((block, address), x) in blocks.iter_mut().zip(addresses).zip(xs) {
+            block[..32].copy_from_slice(address);
+            block[32..].copy_from_slice(x);
+        }
+        let mut dst = [[0u8; 32]; 2];
+        let [d0, d1] = &mut dst;
+        haraka512_x2_with::<ROUNDS>([d0, d1], [&blocks[0], &blocks[1]], &self.rc);
+        dst
+    }
+
+    /// The tweakable hash `F(address, x)`.
+    fn f(&self, address: &[u8; 32], x: &[u8; 32]) -> [u8; 32] {
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(address);
+        block[32..].copy_from_slice(x);
+        let mut dst = [0u8; 32];
+        haraka512_lanes_with::<ROUNDS>(&mut dst, &load4::<DefaultBackend>(&block), &self.rc);
+        dst
+    }
+
+    /// Sponge state after the domain block and the header of key
+    /// `key_index`, two whole blocks.
+    fn header_state(&self, key_index: u64) -> [u8; 64] {
+        let mut header = [0u8; 32];
+        header[..16].copy_from_slice(&self.seed);
+        header[16..24].copy_from_slice(&key_index.to_le_bytes());
+        let (mut state, mut pos) = ([0u8; 64], 0);
+        sponge::absorb(&mut state, &mut pos, WOTS_DOMAIN);
+        sponge::absorb(&mut state, &mut pos, &header);
+        state
+    }
+}
+
+/// Partial verification of a WOTS+ signature, for signatures that do not
+/// fit in one instruction.
+///
+/// Each [`advance`](Self::advance) walks some chains to their ends and
+/// absorbs the ends into the public key hash. Every chain end fills one
+/// sponge block, so the 105-byte [`to_bytes`](Self::to_bytes) form holds
+/// the whole sponge state next to the message, key index and progress,
+/// and can be stored in an account between instructions. An XMSS
+/// signature continues with a [`MerkleVerifyState`](crate::MerkleVerifyState)
+/// over [`public_key`](Self::public_key).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WotsVerifyState {
+    sponge: [u8; 64],
+    msg: [u8; 32],
+    key_index: u64,
+    progress: u8,
+}
+
+impl WotsVerifyState {
+    /// Length of the [`to_bytes`](Self::to_bytes) encoding.
+    pub const LEN: usize = 105;
+
+    /// Starts verifying a signature of `msg` by key `key_index`.
+    pub fn new(wots: &Wots, key_index: u64, msg: &[u8; 32]) -> Self {
+        Self {
+            sponge: wots.header_state(key_index),
+            msg: *msg,
+            key_index,
+            progress: 0,
+        }
+    }
+
+    /// Walks the next `values` of the signature, 32 bytes per chain, to
+    /// their chain ends.
+    ///
+    /// `wots` must be the instance the state was created with. Fails
+    /// without changing the state if the signature would exceed [`LEN`]
+    /// chains.
+    pub fn advance(&mut self, wots: &Wots, values: &[[u8; 32]]) -> Result<(), Error> {
+        let max = LEN.saturating_sub(self.progress as usize);
+        if values.len() > max {
+            return Err(Error::LengthOutOfRange {
+                min: 0,
+                max,
+                actual: values.len(),
+            });
+        }
+        let digits = digits(&self.msg);
+        let digits = digits.get(self.progress as usize..).unwrap_or_default();
+        wots.absorb_chain_ends(
+            &mut self.sponge,
+            self.key_index,
+            self.progress,
+            digits,
+            values,
+        );
+        // At most `LEN` chains in total, so this fits.
+        self.progress += values.len() as u8;
+        Ok(())
+    }
+
+    /// Returns the number of chains walked so far.
+    pub const fn progress(&self) -> u8 {
+        self.progress
+    }
+
+    /// Returns the recomputed public key, once every chain is in.
+    pub fn public_key(&self) -> Option<[u8; 32]> {
+        (self.progress as usize == LEN).then(|| HarakaS::from_block_state(self.sponge).finalize())
+    }
+
+    /// Checks that every chain is in and the signature matches
+    /// `public_key`. The comparison is constant-time.
+    pub fn verify(&self, public_key: &[u8; 32]) -> bool {
+        self.public_key()
+            .is_some_and(|recomputed| crate::ct::eq(&recomputed, public_key))
+    }
+
+    /// Encodes the state as sponge state, message, key index
+    /// (little-endian) and progress.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut out = [0u8; Self::LEN];
+        let (sponge, msg, key_index, progress) = mut_array_refs![&mut out, 64, 32, 8, 1];
+        *sponge = self.sponge;
+        *msg = self.msg;
+        *key_index = self.key_index.to_le_bytes();
+        progress[0] = self.progress;
+        out
+    }
+
+    /// Decodes a state written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Self {
+        let (sponge, msg, key_index, progress) = array_refs![bytes, 64, 32, 8, 1];
+        Self {
+            sponge: *sponge,
+            msg: *msg,
+            key_index: u64::from_le_bytes(*key_index),
+            progress: progress[0],
+        }
+    }
+}
+
+/// A chain being walked by [`Wots::walk`].
+struct Slot {
+    /// Position of the chain in the caller's values.
+    index: usize,
+    chain: u8,
+    pos: u8,
+    value: [u8; 32],
+}
+
+/// Address of step `step` of chain `chain` of key `key_index`:
+/// `u64le(key_index) || type || chain || step || 0^21`.
+fn address(key_index: u64, kind: u8, chain: u8, step: u8) -> [u8; 32] {
+    let mut address = [0u8; 32];
+    address[..8].copy_from_slice(&key_index.to_le_bytes());
+    address[8] = kind;
+    address[9] = chain;
+    address[10] = step;
+    address
+}
+
+/// Base-16 digits of `msg` followed by those of its checksum.
+fn digits(msg: &[u8; 32]) -> [u8; LEN] {
+    let mut digits = [0u8; LEN];
+    let (message, rest) = digits.split_at_mut(LEN1);
+    write_base_w::<4>(message, msg);
+    write_checksum_digits::<4>(rest, checksum::<4>(message));
+    digits
+}
+
+/// Writes the first `out.len()` base-`2^LOG_W` digits of `input` to `out`,
+/// reading each byte from its most significant bits, as SPHINCS+ does.
+///
+/// `LOG_W` must be 1, 2, 4 or 8. Fails with [`Error::BufferTooSmall`] if
+/// `input` holds fewer than `out.len()` digits.
+pub fn base_w<const LOG_W: u32>(out: &mut [u8], input: &[u8]) -> Result<(), Error> {
+    let required = (out.len() * LOG_W as usize).div_ceil(8);
+    if input.len() < required {
+        return Err(Error::BufferTooSmall {
+            required,
+            actual: input.len(),
+        });
+    }
+    write_base_w::<LOG_W>(out, input);
+    Ok(())
+}
+
+#[inline(always)]
+pub(crate) fn write_base_w<const LOG_W: u32>(out: &mut [u8], input: &[u8]) {
+    const { assert!(matches!(LOG_W, 1 | 2 | 4 | 8), "LOG_W must be 1, 2, 4 or 8") };
+    let mask = ((1u16 << LOG_W) - 1) as u8;
+    for (chunk, byte) in out.chunks_mut(8 / LOG_W as usize).zip(input) {
+        for (j, digit) in chunk.iter_mut().enumerate() {
+            *digit = (byte >> (8 - LOG_W as usize * (j + 1))) & mask;
+        }
+    }
+}
+
+/// Returns the WOTS checksum `sum(w - 1 - d)` of base-`2^LOG_W` `digits`.
+///
+/// Digits are taken modulo `w`.
+pub fn checksum<const LOG_W: u32>(digits: &[u8]) -> u64 {
+    let max = ((1u16 << LOG_W) - 1) as u8;
+    digits.iter().map(|&d| (max - (d & max)) as u64).sum()
+}
+
+/// Returns the number of base-`2^LOG_W` digits of the checksum of `len1`
+/// message digits, `floor(log_w(len1 * (w - 1))) + 1` as in SPHINCS+.
+pub const fn checksum_len<const LOG_W: u32>(len1: usize) -> usize {
+    let max = len1 as u64 * ((1 << LOG_W) - 1);
+    (64 - max.leading_zeros()).div_ceil(LOG_W) as usize
+}
+
+/// Writes `checksum` to `out` as `out.len()` base-`2^LOG_W` digits, most
+/// significant first.
+///
+/// This is the SPHINCS+ encoding, which shifts the checksum to a byte
+/// boundary and reads it back with [`base_w`]. Fails with
+/// [`Error::BufferTooSmall`] if the checksum needs more digits.
+pub fn checksum_digits<const LOG_W: u32>(out: &mut [u8], checksum: u64) -> Result<(), Error> {
+    let required = (64 - checksum.leading_zeros()).div_ceil(LOG_W) as usize;
+    if out.len() < required {
+        return Err(Error::BufferTooSmall {
+            required,
+            actual: out.len(),
+        });
+    }
+    write_checksum_digits::<LOG_W>(out, checksum);
+    Ok(())
+}
+
+#[inline(always)]
+pub(crate) fn write_checksum_digits<const LOG_W: u32>(out: &mut [u8], checksum: u64) {
+    let mask = (1u64 << LOG_W) - 1;
+    for (j, digit) in out.iter_mut().rev().enumerate() {
+        let shift = LOG_W as usize * j;
+        *digit = if shift < 64 {
+            (checksum >> shift & mask) as u8
+        } else {
+            0
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    const SEED: [u8; 16] = *b"wots test seed..";
+    #[cfg(feature = "std")]
+    const SECRET_SEED: [u8; 32] = [0x5a; 32];
+
+    #[test]
+    fn test_digits_and_checksum() {
+        let d = digits(&[0xff; 32]);
+        assert!(d[..LEN1].iter().all(|&d| d == 15));
+        assert_eq!(d[LEN1..], [0, 0, 0]);
+
+        // All-zero digits give the largest checksum, 64 * 15 = 0x3c0.
+        let d = digits(&[0; 32]);
+        assert_eq!(d[LEN1..], [0x3, 0xc, 0x0]);
+
+        let mut msg = [0u8; 32];
+        msg[0] = 0xa5;
+        assert_eq!(digits(&msg)[..3], [0xa, 0x5, 0x0]);
+    }
+
+    #[test]
+    fn test_base_w() {
+        let input = [0b1011_0010, 0xff];
+        let mut out = [0u8; 8];
+        base_w::<1>(&mut out, &input).unwrap();
+        assert_eq!(out, [1, 0, 1, 1, 0, 0, 1, 0]);
+        let mut out = [0u8; 5];
+        base_w::<2>(&mut out, &input).unwrap();
+        assert_eq!(out, [2, 3, 0, 2, 3]);
+        let mut out = [0u8; 3];
+        base_w::<4>(&mut out, &input).unwrap();
+        assert_eq!(out, [0xb, 0x2, 0xf]);
+        let mut out = [0u8; 2];
+        base_w::<8>(&mut out, &input).unwrap();
+        assert_eq!(out, input);
+
+        assert_eq!(
+            base_w::<4>(&mut [0; 5], &input),
+            Err(Error::BufferTooSmall {
+                required: 3,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_checksum_len() {
+        // SPHINCS+ parameter sets: w = 16 and n = 16, 24, 32.
+        assert_eq!(checksum_len::<4>(32), 3);
+        assert_eq!(checksum_len::<4>(48), 3);
+        assert_eq!(checksum_len::<4>(64), LEN2);
+        // w = 256 and n = 32: 8160 needs two bytes.
+        assert_eq!(checksum_len::<8>(32), 2);
+        // w = 4 and n = 32: 384 needs five digits.
+        assert_eq!(checksum_len::<2>(128), 5);
+        // w = 2 and n = 32: 256 needs nine bits.
+        assert_eq!(checksum_len::<1>(256), 9);
+    }
+
+    /// The SPHINCS+ encoding: shift to a byte boundary, then read back.
+    fn sphincs_checksum_digits<const LOG_W: u32>(checksum: u64, len2: usize) -> [u8; 8] {
+        let bits = len2 * LOG_W as usize;
+        let shifted = checksum << ((8 - bits % 8) % 8);
+        let bytes = shifted.to_be_bytes();
+        let mut out = [0u8; 8];
+        base_w::<LOG_W>(&mut out[..len2], &bytes[8 - bits.div_ceil(8)..]).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_checksum_digits_match_sphincs() {
+        for checksum in [0, 1, 0x155, 0x3c0, 0xfff] {
+            let mut out = [0u8; 3];
+            checksum_digits::<4>(&mut out, checksum).unwrap();
+            assert_eq!(out[..], sphincs_checksum_digits::<4>(checksum, 3)[..3]);
+        }
+        for checksum in [0, 7, 200, 383] {
+            let mut out = [0u8; 5];
+            checksum_digits::<2>(&mut out, checksum).unwrap();
+            assert_eq!(out[..], sphincs_checksum_digits::<2>(checksum, 5)[..5]);
+        }
+        assert_eq!(
+            checksum_digits::<4>(&mut [0; 2], 0x100),
+            Err(Error::BufferTooSmall {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(checksum::<4>(&[0, 15, 1, 0x1f]), 15 + 14);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_sign_verify() {
+        let wots = Wots::new(&SEED);
+        let pk = wots.public_key(&SECRET_SEED, 7);
+        for msg in [[0u8; 32], [0xff; 32], *b"an arbitrary 32-byte message...."] {
+            let sig = wots.sign(&SECRET_SEED, 7, &msg);
+            assert_eq!(wots.public_key_from_signature(7, &msg, &sig), pk);
+            assert!(wots.verify(&pk, 7, &msg, &sig));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_rejects_tampering() {
+        let wots = Wots::new(&SEED);
+        let msg = *b"an arbitrary 32-byte message....";
+        let pk = wots.public_key(&SECRET_SEED, 7);
+        let sig = wots.sign(&SECRET_SEED, 7, &msg);
+
+        let mut other = msg;
+        other[31] ^= 1;
+        assert!(!wots.verify(&pk, 7, &other, &sig));
+        assert!(!wots.verify(&pk, 8, &msg, &sig));
+        assert!(!Wots::new(&[0; 16]).verify(&pk, 7, &msg, &sig));
+        for i in [0, 32 * LEN1, SIGNATURE_LEN - 1] {
+            let mut forged = sig;
+            forged[i] ^= 1;
+            assert!(!wots.verify(&pk, 7, &msg, &forged));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_split_across_instructions() {
+        let wots = Wots::new(&SEED);
+        let msg = *b"an arbitrary 32-byte message....";
+        let pk = wots.public_key(&SECRET_SEED, 7);
+        let sig = wots.sign(&SECRET_SEED, 7, &msg);
+        let (values, _) = sig.as_chunks::<32>();
+
+        let mut state = WotsVerifyState::new(&wots, 7, &msg);
+        state.advance(&wots, &values[..20]).unwrap();
+        assert!(!state.verify(&pk));
+        assert_eq!(state.public_key(), None);
+
+        // Store the state between instructions.
+        let mut state = WotsVerifyState::from_bytes(&state.to_bytes());
+        assert_eq!(state.progress(), 20);
+        state.advance(&wots, &values[20..50]).unwrap();
+        state.advance(&wots, &values[50..]).unwrap();
+        assert_eq!(state.public_key(), Some(pk));
+        assert!(state.verify(&pk));
+        assert!(!state.verify(&[0; 32]));
+        assert_eq!(
+            state.advance(&wots, &values[..1]),
+            Err(Error::LengthOutOfRange {
+                min: 0,
+                max: 0,
+                actual: 1
+            })
+        );
+
+        let mut forged = WotsVerifyState::new(&wots, 7, &msg);
+        forged.advance(&wots, &values[1..]).unwrap();
+        forged.advance(&wots, &values[..1]).unwrap();
+        assert!(!forged.verify(&pk));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_chain_ends_match_single_chains() {
+        let wots = Wots::new(&SEED);
+        let starts = [0, 15, 3, 14, 7, 20, 0, 9, 1];
+        let values: [[u8; 32]; 9] = core::array::from_fn(|i| [i as u8; 32]);
+        let mut ends = values;
+        wots.chain_ends(5, 40, &starts, &mut ends).unwrap();
+        for i in 0..9 {
+            let from = starts[i].min(W_MAX);
+            let end = wots.chain(5, 40 + i as u8, from, W_MAX, &values[i]);
+            assert_eq!(ends[i], end, "chain {i}");
+        }
+
+        assert_eq!(
+            wots.chain_ends(5, 0, &starts[..2], &mut ends),
+            Err(Error::InvalidLength {
+                expected: 9,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            wots.chain_ends(5, 60, &starts, &mut ends),
+            Err(Error::LengthOutOfRange {
+                min: 0,
+                max: 7,
+                actual: 9
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_keys_are_independent() {
+        let wots = Wots::new(&SEED);
+        let pk = wots.public_key(&SECRET_SEED, 0);
+        assert_ne!(pk, wots.public_key(&SECRET_SEED, 1));
+        assert_ne!(pk, wots.public_key(&[0; 32], 0));
+        assert_ne!(pk, Wots::new(&[0; 16]).public_key(&SECRET_SEED, 0));
+    }
+}
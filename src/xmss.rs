@@ -0,0 +1,175 @@
+//! XMSS signatures: WOTS+ keys under a Merkle tree of height `HEIGHT`.
+//!
+//! Leaf `i` of the tree is the [`Wots`] public key of key index `i`, which
+//! already binds the public seed and the index; inner nodes are the
+//! `haraka512_domain(NODE, left || right)` nodes of [`MerkleVerifyState`].
+//! The public key is the root. A signature is
+//!
+//! ```text
+//! u64le(index) || WOTS+ signature (2144 bytes) || HEIGHT siblings, leaf first
+//! ```
+//!
+//! so a tree of height `h` signs `2^h` messages with one 32-byte public
+//! key. Verification recomputes the leaf from the WOTS+ signature and
+//! walks the authentication path to the root: at most 1005 Haraka-512
+//! calls, one Haraka-S over 2208 bytes and `h` domain Haraka-512 calls.
+//!
+//! With the `std` feature, `Xmss::public_key` and `Xmss::sign` build the
+//! whole tree from a 32-byte secret seed, which takes `2^h` WOTS+ key
+//! generations; they are meant for tests and small trees.
+
+#[cfg(feature = "std")]
+use crate::merkle::hash_node;
+use crate::merkle::MerkleVerifyState;
+use crate::wots::{self, Wots};
+
+/// XMSS instance of one public seed, for trees of `HEIGHT` levels.
+#[derive(Clone)]
+pub struct Xmss<const HEIGHT: usize> {
+    wots: Wots,
+}
+
+impl<const HEIGHT: usize> Xmss<HEIGHT> {
+    /// Length of a signature.
+    pub const SIGNATURE_LEN: usize = 8 + wots::SIGNATURE_LEN + 32 * HEIGHT;
+
+    /// Creates the instance of `seed`.
+    pub fn new(seed: &[u8; 16]) -> Self {
+        const { assert!(HEIGHT <= 64, "XMSS trees are at most 64 levels high") };
+        Self {
+            wots: Wots::new(seed),
+        }
+    }
+
+    /// Returns `true` if `sig` is a signature of `msg` under the public
+    /// key `root`.
+    ///
+    /// Signatures that are not [`SIGNATURE_LEN`](Self::SIGNATURE_LEN)
+    /// bytes long, or whose index lies outside the tree, are rejected.
+    pub fn verify(&self, root: &[u8; 32], msg: &[u8; 32], sig: &[u8]) -> bool {
+        let Some((index, rest)) = sig.split_first_chunk::<8>() else {
+            return false;
+        };
+        let Some((wots_sig, path)) = rest.split_first_chunk::<{ wots::SIGNATURE_LEN }>() else {
+            return false;
+        };
+        let (siblings, tail) = path.as_chunks::<32>();
+        if siblings.len() != HEIGHT || !tail.is_empty() {
+            return false;
+        }
+        let index = u64::from_le_bytes(*index);
+        let leaf = self.wots.public_key_from_signature(index, msg, wots_sig);
+        let mut state = MerkleVerifyState::new(&leaf, index);
+        state.advance(siblings).is_ok() && state.verify(root, HEIGHT as u8)
+    }
+
+    /// Derives the public key, the root of the tree, from `secret_seed`.
+    #[cfg(feature = "std")]
+    pub fn public_key(&self, secret_seed: &[u8; 32]) -> [u8; 32] {
+        let mut level = self.leaves(secret_seed);
+        while level.len() > 1 {
+            level = level
+                .chunks_exact(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+        }
+        level[0]
+    }
+
+    /// Signs `msg` with leaf `index` of the tree derived from
+    /// `secret_seed`.
+    ///
+    /// Every index must sign a single message; the caller keeps track of
+    /// the next unused one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below `2^HEIGHT`.
+    #[cfg(feature = "std")]
+    pub fn sign(&self, secret_seed: &[u8; 32], index: u64, msg: &[u8; 32]) -> std::vec::Vec<u8> {
+        assert!(
+            (index as u128) < 1 << HEIGHT,
+            "index {index} is outside a tree of height {HEIGHT}"
+        );
+        let mut sig = std::vec::Vec::with_capacity(Self::SIGNATURE_LEN);
+        sig.extend_from_slice(&index.to_le_bytes());
+        sig.extend_from_slice(&self.wots.sign(secret_seed, index, msg));
+        let mut level = self.leaves(secret_seed);
+        let mut i = index as usize;
+        while level.len() > 1 {
+            sig.extend_from_slice(&level[i ^ 1]);
+            level = level
+                .chunks_exact(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+            i /= 2;
+        }
+        sig
+    }
+
+    /// Public keys of all `2^HEIGHT` leaves.
+    #[cfg(feature = "std")]
+    fn leaves(&self, secret_seed: &[u8; 32]) -> std::vec::Vec<[u8; 32]> {
+        (0..1u64 << HEIGHT)
+            .map(|i| self.wots.public_key(secret_seed, i))
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    const SEED: [u8; 16] = *b"xmss test seed..";
+    const SECRET_SEED: [u8; 32] = [0xa5; 32];
+
+    #[test]
+    fn test_sign_verify_every_leaf() {
+        let xmss = Xmss::<2>::new(&SEED);
+        let root = xmss.public_key(&SECRET_SEED);
+        let msg = *b"an arbitrary 32-byte message....";
+        for index in 0..4 {
+            let sig = xmss.sign(&SECRET_SEED, index, &msg);
+            assert_eq!(sig.len(), Xmss::<2>::SIGNATURE_LEN);
+            assert!(xmss.verify(&root, &msg, &sig));
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let xmss = Xmss::<2>::new(&SEED);
+        let root = xmss.public_key(&SECRET_SEED);
+        let msg = *b"an arbitrary 32-byte message....";
+        let sig = xmss.sign(&SECRET_SEED, 1, &msg);
+
+        let mut other = msg;
+        other[0] ^= 1;
+        assert!(!xmss.verify(&root, &other, &sig));
+        assert!(!xmss.verify(&[0; 32], &msg, &sig));
+        assert!(!Xmss::<2>::new(&[0; 16]).verify(&root, &msg, &sig));
+        // Flip a bit of the index, the WOTS+ part and the last sibling.
+        for i in [0, 8, sig.len() - 1] {
+            let mut forged = sig.clone();
+            forged[i] ^= 1;
+            assert!(!xmss.verify(&root, &msg, &forged));
+        }
+        // An index past the tree wraps onto the same path.
+        let mut forged = sig.clone();
+        forged[0] += 4;
+        assert!(!xmss.verify(&root, &msg, &forged));
+
+        assert!(!xmss.verify(&root, &msg, &sig[..sig.len() - 1]));
+        let mut longer = sig.clone();
+        longer.extend_from_slice(&[0; 32]);
+        assert!(!xmss.verify(&root, &msg, &longer));
+    }
+
+    #[test]
+    fn test_height_zero_is_one_wots_key() {
+        let xmss = Xmss::<0>::new(&SEED);
+        let root = xmss.public_key(&SECRET_SEED);
+        assert_eq!(root, Wots::new(&SEED).public_key(&SECRET_SEED, 0));
+        let msg = [7; 32];
+        assert!(xmss.verify(&root, &msg, &xmss.sign(&SECRET_SEED, 0, &msg)));
+    }
+}